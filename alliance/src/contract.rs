@@ -0,0 +1,1035 @@
+//! Alliance Contract - Alliance Chain smart contract
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+use linera_sdk::{
+    linera_base_types::{AccountOwner, ChainId, WithContractAbi},
+    Contract, ContractRuntime,
+    views::{RootView, View, ViewError},
+};
+use linera_dominion_alliance::{AllianceAbi, AllianceResponse, Operation, Message};
+use linera_dominion_alliance::state::{
+    AllianceState, ApplicantData, MemberData, MembershipData, ProposalData, TreatyData, VoteData,
+};
+use linera_dominion_common::{
+    constants::{ALLIANCE_MAX_MEMBERS, ALLIANCE_MIN_MEMBERS, VOTING_DURATION},
+    errors::AllianceError,
+    messages::{AllianceMessage, DominionMessage, ViolationType},
+    resources::{ResourceError, Resources},
+    types::{AllianceMemberRole, AllianceProposalKind, CommitHash, VoteTally},
+};
+
+pub struct AllianceContract {
+    state: AllianceState,
+    runtime: ContractRuntime<Self>,
+}
+
+linera_sdk::contract!(AllianceContract);
+
+impl WithContractAbi for AllianceContract {
+    type Abi = AllianceAbi;
+}
+
+impl Contract for AllianceContract {
+    type Message = Message;
+    type Parameters = ();
+    type InstantiationArgument = ();
+    type EventValue = ();
+
+    async fn load(runtime: ContractRuntime<Self>) -> Self {
+        let state = AllianceState::load(runtime.root_view_storage_context())
+            .await
+            .expect("Failed to load state");
+        Self { state, runtime }
+    }
+
+    async fn instantiate(&mut self, _argument: Self::InstantiationArgument) {
+        // Alliances are founded explicitly, on any chain running the application
+    }
+
+    async fn execute_operation(&mut self, operation: Self::Operation) -> Result<AllianceResponse, AllianceError> {
+        let now_micros = self.runtime.system_time().micros();
+
+        self.resolve_proposals(now_micros).await
+            .map_err(|_| AllianceError::StorageError)?;
+
+        match operation {
+            // ===== Alliance Chain Operations =====
+            Operation::Found { name } => {
+                if self.state.is_founded() {
+                    return Err(AllianceError::AlreadyFounded);
+                }
+                // The founder's chain is only known once their join request arrives
+                let owner = self.runtime.authenticated_signer()
+                    .ok_or(AllianceError::NotAuthorized)?;
+                self.state.name.set(name);
+                self.state.founded_micros.set(Some(now_micros));
+                self.state.founder.set(Some(owner));
+            }
+
+            Operation::ApproveMember { member_chain } => {
+                self.require_rank(AllianceMemberRole::Officer).await?;
+                let applicant = self.state.applicants.get(&member_chain).await
+                    .map_err(|_| AllianceError::StorageError)?
+                    .ok_or(AllianceError::NotMember)?;
+                let members = self.state.members.count().await
+                    .map_err(|_| AllianceError::StorageError)?;
+                if members >= ALLIANCE_MAX_MEMBERS {
+                    return Err(AllianceError::MaximumMembersExceeded);
+                }
+                self.state.applicants.remove(&member_chain)
+                    .map_err(|_| AllianceError::StorageError)?;
+                self.state.members.insert(&member_chain, MemberData {
+                    owner: applicant.owner,
+                    role: AllianceMemberRole::Member,
+                    joined_micros: now_micros,
+                    contributed_crystals: 0,
+                })
+                .map_err(|_| AllianceError::StorageError)?;
+                self.update_membership(member_chain, Some(AllianceMemberRole::Member));
+            }
+
+            Operation::RejectApplicant { member_chain } => {
+                self.require_rank(AllianceMemberRole::Officer).await?;
+                if self.state.applicants.get(&member_chain).await
+                    .map_err(|_| AllianceError::StorageError)?
+                    .is_none()
+                {
+                    return Err(AllianceError::NotMember);
+                }
+                self.state.applicants.remove(&member_chain)
+                    .map_err(|_| AllianceError::StorageError)?;
+                self.update_membership(member_chain, None);
+            }
+
+            Operation::SetRole { member_chain, role } => {
+                let (leader_chain, _) = self.require_rank(AllianceMemberRole::Leader).await?;
+                if member_chain == leader_chain || role == AllianceMemberRole::Applicant {
+                    // The leader hands over leadership instead of stepping down
+                    return Err(AllianceError::InsufficientRank);
+                }
+                self.change_role(member_chain, role).await
+                    .map_err(|_| AllianceError::StorageError)??;
+            }
+
+            Operation::KickMember { member_chain } => {
+                let (_, officer) = self.require_rank(AllianceMemberRole::Officer).await?;
+                let member = self.state.members.get(&member_chain).await
+                    .map_err(|_| AllianceError::StorageError)?
+                    .ok_or(AllianceError::NotMember)?;
+                if member.role.rank() >= officer.role.rank() {
+                    return Err(AllianceError::InsufficientRank);
+                }
+                self.remove_member(member_chain).await
+                    .map_err(|_| AllianceError::StorageError)??;
+            }
+
+            Operation::SignTreaty { treaty_id } => {
+                self.require_rank(AllianceMemberRole::Leader).await?;
+                let mut treaty = self.pending_incoming_treaty(treaty_id).await?;
+                treaty.sign(now_micros);
+                let counterparty = treaty.counterparty;
+                let remote_treaty_id = treaty.remote_treaty_id;
+                self.state.treaties.insert(&treaty_id, treaty)
+                    .map_err(|_| AllianceError::StorageError)?;
+                self.runtime
+                    .prepare_message(Message::TreatySigned {
+                        treaty_id: remote_treaty_id,
+                        remote_treaty_id: treaty_id,
+                        signed_micros: now_micros,
+                    })
+                    .send_to(counterparty);
+            }
+
+            Operation::DeclineTreaty { treaty_id } => {
+                self.require_rank(AllianceMemberRole::Leader).await?;
+                let mut treaty = self.pending_incoming_treaty(treaty_id).await?;
+                treaty.status = TreatyData::DECLINED;
+                let counterparty = treaty.counterparty;
+                let remote_treaty_id = treaty.remote_treaty_id;
+                self.state.treaties.insert(&treaty_id, treaty)
+                    .map_err(|_| AllianceError::StorageError)?;
+                self.runtime
+                    .prepare_message(Message::TreatyDeclined { treaty_id: remote_treaty_id })
+                    .send_to(counterparty);
+            }
+
+            // ===== Member Chain Operations =====
+            Operation::RegisterDominion => {
+                let caller = self.runtime.authenticated_caller_id()
+                    .ok_or(AllianceError::NotAuthorized)?;
+                if self.state.dominion_app.get().is_some_and(|dominion_app| dominion_app != caller) {
+                    return Err(AllianceError::NotAuthorized);
+                }
+                self.state.dominion_app.set(Some(caller));
+            }
+
+            Operation::RequestJoin { alliance_chain } => {
+                if self.state.membership.get().is_some() {
+                    return Err(AllianceError::AlreadyMember);
+                }
+                let player = self.runtime.authenticated_signer()
+                    .ok_or(AllianceError::NotAuthorized)?;
+                let player_chain = self.runtime.chain_id();
+                self.state.applications.insert(&alliance_chain, now_micros)
+                    .map_err(|_| AllianceError::StorageError)?;
+                self.runtime
+                    .prepare_message(Message::Request(AllianceMessage::JoinRequest {
+                        player,
+                        player_chain,
+                    }))
+                    .with_authentication()
+                    .send_to(alliance_chain);
+            }
+
+            Operation::Leave => {
+                let player = self.runtime.authenticated_signer()
+                    .ok_or(AllianceError::NotAuthorized)?;
+                let player_chain = self.runtime.chain_id();
+                self.send_request(AllianceMessage::Leave { player, player_chain })?;
+            }
+
+            Operation::Deposit { resources } => {
+                // The Dominion debits the deposit from the wallet before calling
+                self.check_dominion_caller()?;
+                let contributor = self.runtime.authenticated_signer()
+                    .ok_or(AllianceError::NotAuthorized)?;
+                self.send_request(AllianceMessage::TreasuryDeposit {
+                    contributor,
+                    resources,
+                })?;
+            }
+
+            Operation::ProposeTreasurySpend { recipient, resources } => {
+                self.submit_proposal(AllianceProposalKind::TreasurySpend {
+                    recipient,
+                    resources,
+                })?;
+            }
+
+            Operation::ProposeKick { member_chain } => {
+                self.submit_proposal(AllianceProposalKind::KickMember { member_chain })?;
+            }
+
+            Operation::ProposeTreaty { treaty_type, other_alliance, penalty_crystals, duration_secs } => {
+                if other_alliance == self.alliance_chain()? {
+                    return Err(AllianceError::SelfTreaty);
+                }
+                self.submit_proposal(AllianceProposalKind::DeclareTreaty {
+                    treaty_type,
+                    other_alliance,
+                    penalty_crystals: penalty_crystals as u128,
+                    duration_secs,
+                })?;
+            }
+
+            Operation::ProposeRankChange { member_chain, role } => {
+                if role == AllianceMemberRole::Applicant {
+                    return Err(AllianceError::InsufficientRank);
+                }
+                self.submit_proposal(AllianceProposalKind::ChangeRank { member_chain, role })?;
+            }
+
+            Operation::Vote { proposal_id, approve } => {
+                let alliance_chain = self.alliance_chain()?;
+                let voter = self.runtime.authenticated_signer()
+                    .ok_or(AllianceError::NotAuthorized)?;
+                let key = (alliance_chain, proposal_id);
+                if self.state.voted.contains_key(&key).await
+                    .map_err(|_| AllianceError::StorageError)?
+                {
+                    return Err(AllianceError::AlreadyVoted);
+                }
+                self.state.voted.insert(&key, approve)
+                    .map_err(|_| AllianceError::StorageError)?;
+                self.send_request(AllianceMessage::Vote {
+                    proposal_id,
+                    voter,
+                    vote: approve,
+                    voting_power: 0,
+                })?;
+            }
+
+            Operation::ReportAttack { attacker, attacker_chain, evidence_hash } => {
+                // The Alliance Chain authenticates the report by this chain's membership
+                let evidence_hash = hex::decode(&evidence_hash).ok()
+                    .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                    .ok_or(AllianceError::InvalidEvidence)?;
+                let victim_alliance = self.alliance_chain()?;
+                self.send_request(AllianceMessage::ViolationReport {
+                    violator: attacker,
+                    violation_type: ViolationType::TreatyViolation {
+                        violator_chain: attacker_chain,
+                        victim_alliance,
+                    },
+                    evidence_hash,
+                })?;
+            }
+
+            Operation::TakeNotifications => {
+                self.check_dominion_caller()?;
+                let notifications = self.state.notifications.elements().await
+                    .map_err(|_| AllianceError::StorageError)?;
+                for _ in 0..notifications.len() {
+                    self.state.notifications.delete_front();
+                }
+                return Ok(AllianceResponse::Notifications(notifications));
+            }
+        }
+        Ok(AllianceResponse::Ok)
+    }
+
+    async fn execute_message(&mut self, message: Self::Message) {
+        let now_micros = self.runtime.system_time().micros();
+        let origin = self.runtime.message_origin_chain_id()
+            .expect("Incoming message must have an origin");
+
+        self.resolve_proposals(now_micros).await
+            .expect("Failed to resolve proposals");
+
+        match message {
+            Message::Request(request) => {
+                if !self.state.is_founded() {
+                    return;
+                }
+                if self.runtime.message_is_bouncing() == Some(true) {
+                    // The other alliance rejected our treaty proposal
+                    if let AllianceMessage::TreatyProposal { treaty_id, .. } = request {
+                        self.close_pending_treaty(origin, treaty_id).await;
+                    }
+                    return;
+                }
+                self.handle_request(request, origin, now_micros).await;
+            }
+
+            Message::MembershipUpdate { alliance_name, role } => {
+                // Only the alliance we belong to, or one we applied to while
+                // belonging to none, may change our membership
+                let current = self.state.membership.get().as_ref()
+                    .map(|membership| membership.alliance_chain);
+                let applied = self.state.applications.contains_key(&origin).await
+                    .expect("Failed to read applications");
+                // An answer, either way, ends the application
+                self.state.applications.remove(&origin)
+                    .expect("Failed to remove application");
+                match role {
+                    Some(role) if current == Some(origin) || (current.is_none() && applied) => {
+                        self.state.membership.set(Some(MembershipData {
+                            alliance_chain: origin,
+                            alliance_name,
+                            role,
+                        }));
+                    }
+                    Some(_) => {}
+                    None => {
+                        if current == Some(origin) {
+                            self.state.membership.set(None);
+                        }
+                    }
+                }
+            }
+
+            Message::Notify { notification } => {
+                if self.runtime.message_is_bouncing() == Some(true) {
+                    // A payout the recipient rejected goes back into the treasury
+                    if let DominionMessage::ResourceTransfer { resources, .. } = notification {
+                        self.credit_treasury(&resources)
+                            .expect("Failed to refund treasury");
+                    }
+                    return;
+                }
+                self.state.notifications.push_back(notification);
+            }
+
+            Message::TreatySigned { treaty_id, remote_treaty_id, signed_micros } => {
+                let treaty = self.state.treaty_with(origin, treaty_id).await
+                    .expect("Failed to read treaty");
+                if let Some(mut treaty) = treaty {
+                    if treaty.outgoing && treaty.status == TreatyData::PENDING {
+                        treaty.remote_treaty_id = remote_treaty_id;
+                        treaty.sign(signed_micros);
+                        self.state.treaties.insert(&treaty_id, treaty)
+                            .expect("Failed to sign treaty");
+                    }
+                }
+            }
+
+            Message::TreatyDeclined { treaty_id } => {
+                self.close_pending_treaty(origin, treaty_id).await;
+            }
+
+            Message::TreatyBreach { treaty_id, violator, violator_chain, evidence_hash } => {
+                self.settle_breach(origin, treaty_id, violator, violator_chain, evidence_hash, now_micros).await;
+            }
+
+            Message::TreatyPenalty { treaty_id, crystals } => {
+                if self.runtime.message_is_bouncing() == Some(true) {
+                    // The victim would not take the penalty: it goes back into the treasury
+                    self.credit_treasury(&Resources::crystals(crystals as u128))
+                        .expect("Failed to refund penalty");
+                    return;
+                }
+                let treaty = self.state.treaty_with(origin, treaty_id).await
+                    .expect("Failed to read treaty");
+                if let Some(mut treaty) = treaty {
+                    treaty.status = TreatyData::BROKEN;
+                    self.state.treaties.insert(&treaty_id, treaty)
+                        .expect("Failed to update treaty");
+                }
+                self.credit_treasury(&Resources::crystals(crystals as u128))
+                    .expect("Failed to credit penalty");
+            }
+        }
+    }
+
+    async fn store(mut self) {
+        self.state.save().await.expect("Failed to save state");
+    }
+}
+
+impl AllianceContract {
+    /// Handle a request that reached the Alliance Chain from `origin`
+    async fn handle_request(&mut self, request: AllianceMessage, origin: ChainId, now_micros: u64) {
+        match request {
+            AllianceMessage::JoinRequest { player, player_chain } => {
+                if !self.is_sent_by(player, player_chain, origin) {
+                    return;
+                }
+                let is_member = self.state.members.contains_key(&player_chain).await
+                    .expect("Failed to read members");
+                if is_member {
+                    return;
+                }
+                let members = self.state.members.count().await
+                    .expect("Failed to count members");
+                if members == 0 && *self.state.founder.get() == Some(player) {
+                    // The founder joins first and leads
+                    self.state.members.insert(&player_chain, MemberData {
+                        owner: player,
+                        role: AllianceMemberRole::Leader,
+                        joined_micros: now_micros,
+                        contributed_crystals: 0,
+                    })
+                    .expect("Failed to add founder");
+                    self.update_membership(player_chain, Some(AllianceMemberRole::Leader));
+                    return;
+                }
+                if members >= ALLIANCE_MAX_MEMBERS {
+                    self.update_membership(player_chain, None);
+                    return;
+                }
+                self.state.applicants.insert(&player_chain, ApplicantData {
+                    owner: player,
+                    requested_micros: now_micros,
+                })
+                .expect("Failed to record applicant");
+            }
+
+            AllianceMessage::Leave { player, player_chain } => {
+                if !self.is_sent_by(player, player_chain, origin) {
+                    return;
+                }
+                self.state.applicants.remove(&player_chain)
+                    .expect("Failed to remove applicant");
+                // Leaving without being a member still withdraws the application
+                let _ = self.remove_member(player_chain).await
+                    .expect("Failed to remove member");
+                self.update_membership(player_chain, None);
+            }
+
+            AllianceMessage::TreasuryDeposit { contributor, resources } => {
+                self.credit_treasury(&resources)
+                    .expect("Failed to credit deposit");
+
+                // Deposits from chains that are no longer members still count as donations
+                if let Some(member) = self.state.members.get_mut(&origin).await
+                    .expect("Failed to read members")
+                {
+                    if member.owner == contributor {
+                        member.contributed_crystals = member.contributed_crystals
+                            .saturating_add(saturate_u64(resources.chronos_crystals.0));
+                    }
+                }
+            }
+
+            AllianceMessage::SubmitProposal { proposer, proposal } => {
+                if self.sending_member(proposer, origin).await.is_none() {
+                    return;
+                }
+                let members = self.state.members.count().await
+                    .expect("Failed to count members");
+                if members < ALLIANCE_MIN_MEMBERS || !self.is_valid_proposal(&proposal).await {
+                    return;
+                }
+                self.state.add_proposal(ProposalData {
+                    proposer,
+                    proposer_chain: origin,
+                    kind: proposal,
+                    created_micros: now_micros,
+                    ends_micros: now_micros.saturating_add(VOTING_DURATION * 1_000_000),
+                    tally: VoteTally::default(),
+                    voter_count: 0,
+                    total_power: 0,
+                    status: ProposalData::OPEN,
+                })
+                .expect("Failed to record proposal");
+            }
+
+            AllianceMessage::Vote { proposal_id, voter, vote, voting_power: _ } => {
+                let Some(member) = self.sending_member(voter, origin).await else {
+                    return;
+                };
+                let Some(mut proposal) = self.state.proposals.get(&proposal_id).await
+                    .expect("Failed to read proposal")
+                else {
+                    return;
+                };
+                if proposal.status != ProposalData::OPEN || proposal.ends_micros <= now_micros {
+                    return;
+                }
+                let key = (proposal_id, origin);
+                if self.state.votes.contains_key(&key).await.expect("Failed to read votes") {
+                    return;
+                }
+                let power = member.voting_power();
+                self.state.votes.insert(&key, VoteData {
+                    voter,
+                    approve: vote,
+                    power,
+                    cast_micros: now_micros,
+                })
+                .expect("Failed to record vote");
+                proposal.tally.add(vote, power);
+                proposal.voter_count += 1;
+                self.state.proposals.insert(&proposal_id, proposal)
+                    .expect("Failed to record vote");
+            }
+
+            AllianceMessage::TreatyProposal {
+                treaty_id,
+                treaty_type,
+                other_alliance: _,
+                penalty_crystals,
+                duration_secs,
+            } => {
+                // Only Alliance Chains send treaty proposals, so the origin is the proposer
+                if origin == self.runtime.chain_id() {
+                    return;
+                }
+                let existing = self.state.find_live_treaty(origin, treaty_type, now_micros).await
+                    .expect("Failed to read treaties");
+                if existing.is_some() {
+                    self.runtime
+                        .prepare_message(Message::TreatyDeclined { treaty_id })
+                        .send_to(origin);
+                    return;
+                }
+                self.state.add_treaty(TreatyData {
+                    counterparty: origin,
+                    outgoing: false,
+                    remote_treaty_id: treaty_id,
+                    treaty_type,
+                    penalty_crystals: saturate_u64(penalty_crystals),
+                    duration_secs,
+                    signed_micros: None,
+                    expires_micros: None,
+                    status: TreatyData::PENDING,
+                })
+                .expect("Failed to record treaty");
+            }
+
+            AllianceMessage::ViolationReport { violator, violation_type, evidence_hash } => {
+                // Reports come from the battle results of a member's chain. Each
+                // pact partner checks whether the attacker is one of its members.
+                let is_member = self.state.members.contains_key(&origin).await
+                    .expect("Failed to read members");
+                if !is_member {
+                    return;
+                }
+                let ViolationType::TreatyViolation { violator_chain, victim_alliance } = violation_type else {
+                    return;
+                };
+                if victim_alliance != self.runtime.chain_id() {
+                    return;
+                }
+                let settled = self.state.settled_evidence.contains_key(&evidence_hash).await
+                    .expect("Failed to read evidence");
+                if settled {
+                    return;
+                }
+                let pacts = self.state.active_pacts(now_micros).await
+                    .expect("Failed to read treaties");
+                for (treaty_id, treaty) in pacts {
+                    self.state.settled_evidence.insert(&evidence_hash, treaty_id)
+                        .expect("Failed to record evidence");
+                    self.runtime
+                        .prepare_message(Message::TreatyBreach {
+                            treaty_id: treaty.remote_treaty_id,
+                            violator,
+                            violator_chain,
+                            evidence_hash,
+                        })
+                        .send_to(treaty.counterparty);
+                }
+            }
+        }
+    }
+
+    /// Close the proposals whose voting window has ended, carrying out those that passed
+    async fn resolve_proposals(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        if !self.state.is_founded() {
+            return Ok(());
+        }
+        for proposal_id in self.state.closed_proposals(now_micros).await? {
+            let Some(mut proposal) = self.state.proposals.get(&proposal_id).await? else {
+                continue;
+            };
+            proposal.total_power = self.state.total_voting_power().await?;
+            proposal.status = if !proposal.tally.passes(proposal.total_power) {
+                ProposalData::REJECTED
+            } else if self.execute_proposal(&proposal).await?.is_ok() {
+                ProposalData::PASSED
+            } else {
+                ProposalData::FAILED
+            };
+            self.state.proposals.insert(&proposal_id, proposal)?;
+        }
+        Ok(())
+    }
+
+    /// Carry out a proposal that passed
+    async fn execute_proposal(
+        &mut self,
+        proposal: &ProposalData,
+    ) -> Result<Result<(), AllianceError>, ViewError> {
+        match proposal.kind.clone() {
+            AllianceProposalKind::TreasurySpend { recipient, resources } => {
+                let Ok(treasury) = self.state.treasury.get().checked_sub(&resources) else {
+                    return Ok(Err(AllianceError::InsufficientTreasury));
+                };
+                self.state.treasury.set(treasury);
+                let memo = Some(format!("{} treasury", self.state.name.get()));
+                self.runtime
+                    .prepare_message(Message::Notify {
+                        notification: DominionMessage::ResourceTransfer {
+                            from: proposal.proposer,
+                            resources,
+                            memo,
+                        },
+                    })
+                    .with_tracking()
+                    .send_to(recipient);
+                Ok(Ok(()))
+            }
+
+            AllianceProposalKind::KickMember { member_chain } => {
+                self.remove_member(member_chain).await
+            }
+
+            AllianceProposalKind::ChangeRank { member_chain, role } => {
+                self.change_role(member_chain, role).await
+            }
+
+            AllianceProposalKind::DeclareTreaty {
+                treaty_type,
+                other_alliance,
+                penalty_crystals,
+                duration_secs,
+            } => {
+                // From the receiver's point of view, the other alliance is this one
+                let this_alliance = self.runtime.chain_id();
+                if other_alliance == this_alliance {
+                    return Ok(Err(AllianceError::SelfTreaty));
+                }
+                let now_micros = self.runtime.system_time().micros();
+                if self.state.find_live_treaty(other_alliance, treaty_type, now_micros).await?.is_some() {
+                    return Ok(Err(AllianceError::TreatyExists));
+                }
+                let treaty_id = self.state.add_treaty(TreatyData {
+                    counterparty: other_alliance,
+                    outgoing: true,
+                    remote_treaty_id: 0,
+                    treaty_type,
+                    penalty_crystals: saturate_u64(penalty_crystals),
+                    duration_secs,
+                    signed_micros: None,
+                    expires_micros: None,
+                    status: TreatyData::PENDING,
+                })?;
+                self.runtime
+                    .prepare_message(Message::Request(AllianceMessage::TreatyProposal {
+                        treaty_id,
+                        treaty_type,
+                        other_alliance: this_alliance,
+                        penalty_crystals,
+                        duration_secs,
+                    }))
+                    .with_tracking()
+                    .send_to(other_alliance);
+                Ok(Ok(()))
+            }
+        }
+    }
+
+    /// Whether a submitted proposal refers to the current alliance state
+    async fn is_valid_proposal(&mut self, proposal: &AllianceProposalKind) -> bool {
+        match proposal {
+            AllianceProposalKind::TreasurySpend { .. } => true,
+            AllianceProposalKind::KickMember { member_chain } => {
+                self.state.members.contains_key(member_chain).await
+                    .expect("Failed to read members")
+            }
+            AllianceProposalKind::ChangeRank { member_chain, role } => {
+                *role != AllianceMemberRole::Applicant
+                    && self.state.members.contains_key(member_chain).await
+                        .expect("Failed to read members")
+            }
+            AllianceProposalKind::DeclareTreaty { treaty_type, other_alliance, .. } => {
+                let now_micros = self.runtime.system_time().micros();
+                *other_alliance != self.runtime.chain_id()
+                    && self.state.find_live_treaty(*other_alliance, *treaty_type, now_micros).await
+                        .expect("Failed to read treaties")
+                        .is_none()
+            }
+        }
+    }
+
+    /// An incoming treaty proposal still waiting for our signature
+    async fn pending_incoming_treaty(&self, treaty_id: u64) -> Result<TreatyData, AllianceError> {
+        self.state.treaties.get(&treaty_id).await
+            .map_err(|_| AllianceError::StorageError)?
+            .filter(|treaty| !treaty.outgoing && treaty.status == TreatyData::PENDING)
+            .ok_or(AllianceError::TreatyNotFound(treaty_id))
+    }
+
+    /// Mark our treaty proposal to `counterparty` as declined
+    async fn close_pending_treaty(&mut self, counterparty: ChainId, treaty_id: u64) {
+        let treaty = self.state.treaty_with(counterparty, treaty_id).await
+            .expect("Failed to read treaty");
+        if let Some(mut treaty) = treaty {
+            if treaty.status == TreatyData::PENDING {
+                treaty.status = TreatyData::DECLINED;
+                self.state.treaties.insert(&treaty_id, treaty)
+                    .expect("Failed to decline treaty");
+            }
+        }
+    }
+
+    /// Slash the treasury for a breach of treaty by one of our members
+    ///
+    /// The penalty, capped by the crystals in the treasury, goes to the victim
+    /// alliance and the treaty ends. Breaches by chains outside the alliance are
+    /// ignored. Evidence is only settled once.
+    async fn settle_breach(
+        &mut self,
+        victim_alliance: ChainId,
+        treaty_id: u64,
+        violator: AccountOwner,
+        violator_chain: ChainId,
+        evidence_hash: CommitHash,
+        now_micros: u64,
+    ) {
+        let Some(mut treaty) = self.state.treaty_with(victim_alliance, treaty_id).await
+            .expect("Failed to read treaty")
+        else {
+            return;
+        };
+        let settled = self.state.settled_evidence.contains_key(&evidence_hash).await
+            .expect("Failed to read evidence");
+        let is_member = self.state.members.get(&violator_chain).await
+            .expect("Failed to read members")
+            .is_some_and(|member| member.owner == violator);
+        if !treaty.is_active(now_micros) || settled || !is_member {
+            return;
+        }
+        self.state.settled_evidence.insert(&evidence_hash, treaty_id)
+            .expect("Failed to record evidence");
+
+        let treasury = self.state.treasury.get_mut();
+        let crystals = treaty.penalty_crystals.min(saturate_u64(treasury.chronos_crystals.0));
+        treasury.chronos_crystals.0 -= crystals as u128;
+        let remote_treaty_id = treaty.remote_treaty_id;
+        treaty.status = TreatyData::BROKEN;
+        self.state.treaties.insert(&treaty_id, treaty)
+            .expect("Failed to update treaty");
+
+        self.runtime
+            .prepare_message(Message::TreatyPenalty { treaty_id: remote_treaty_id, crystals })
+            .with_tracking()
+            .send_to(victim_alliance);
+    }
+
+    /// Give a member another role; a new leader takes over from the current one
+    async fn change_role(
+        &mut self,
+        member_chain: ChainId,
+        role: AllianceMemberRole,
+    ) -> Result<Result<(), AllianceError>, ViewError> {
+        let Some(mut member) = self.state.members.get(&member_chain).await? else {
+            return Ok(Err(AllianceError::NotMember));
+        };
+        if role == AllianceMemberRole::Leader {
+            let mut leaders = Vec::new();
+            self.state.members
+                .for_each_index_value(|chain, member| {
+                    if member.role == AllianceMemberRole::Leader && chain != member_chain {
+                        leaders.push(chain);
+                    }
+                    Ok(())
+                })
+                .await?;
+            for leader_chain in leaders {
+                if let Some(leader) = self.state.members.get_mut(&leader_chain).await? {
+                    leader.role = AllianceMemberRole::Officer;
+                }
+                self.update_membership(leader_chain, Some(AllianceMemberRole::Officer));
+            }
+        }
+        member.role = role;
+        self.state.members.insert(&member_chain, member)?;
+        self.update_membership(member_chain, Some(role));
+        Ok(Ok(()))
+    }
+
+    /// Remove a member, handing leadership on if they led the alliance
+    async fn remove_member(&mut self, member_chain: ChainId) -> Result<Result<(), AllianceError>, ViewError> {
+        let Some(member) = self.state.members.get(&member_chain).await? else {
+            return Ok(Err(AllianceError::NotMember));
+        };
+        if member.role == AllianceMemberRole::Leader {
+            self.promote_successor(&member_chain).await?;
+        }
+        self.state.members.remove(&member_chain)?;
+        self.update_membership(member_chain, None);
+        Ok(Ok(()))
+    }
+
+    /// Hand leadership to the successor of a departing leader
+    async fn promote_successor(&mut self, leaving: &ChainId) -> Result<(), ViewError> {
+        let Some(successor) = self.state.successor(leaving).await? else {
+            return Ok(());
+        };
+        if let Some(member) = self.state.members.get_mut(&successor).await? {
+            member.role = AllianceMemberRole::Leader;
+        }
+        self.update_membership(successor, Some(AllianceMemberRole::Leader));
+        Ok(())
+    }
+
+    fn credit_treasury(&mut self, resources: &Resources) -> Result<(), ResourceError> {
+        let treasury = self.state.treasury.get_mut();
+        *treasury = treasury.checked_add(resources)?;
+        Ok(())
+    }
+
+    /// Check that the operation comes from the dominion registered on this chain
+    fn check_dominion_caller(&mut self) -> Result<(), AllianceError> {
+        let dominion_app = *self.state.dominion_app.get();
+        if dominion_app.is_none() || self.runtime.authenticated_caller_id() != dominion_app {
+            return Err(AllianceError::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    /// Check that a request about `player_chain` was signed by `player` on that chain
+    fn is_sent_by(&mut self, player: AccountOwner, player_chain: ChainId, origin: ChainId) -> bool {
+        origin == player_chain && self.runtime.authenticated_signer() == Some(player)
+    }
+
+    /// The member who sent a request from `origin`, if `player` signed it
+    async fn sending_member(&mut self, player: AccountOwner, origin: ChainId) -> Option<MemberData> {
+        if self.runtime.authenticated_signer() != Some(player) {
+            return None;
+        }
+        self.state.members.get(&origin).await
+            .expect("Failed to read members")
+            .filter(|member| member.owner == player)
+    }
+
+    /// The member signing this operation, if their role is at least `role`
+    async fn require_rank(
+        &mut self,
+        role: AllianceMemberRole,
+    ) -> Result<(ChainId, MemberData), AllianceError> {
+        if !self.state.is_founded() {
+            return Err(AllianceError::AllianceNotFounded);
+        }
+        let owner = self.runtime.authenticated_signer()
+            .ok_or(AllianceError::NotAuthorized)?;
+        let (chain, member) = self.state.member_by_owner(&owner).await
+            .map_err(|_| AllianceError::StorageError)?
+            .ok_or(AllianceError::NotMember)?;
+        if member.role.rank() < role.rank() {
+            return Err(AllianceError::InsufficientRank);
+        }
+        Ok((chain, member))
+    }
+
+    /// The Alliance Chain this member chain belongs to
+    fn alliance_chain(&self) -> Result<ChainId, AllianceError> {
+        self.state.membership.get().as_ref()
+            .map(|membership| membership.alliance_chain)
+            .ok_or(AllianceError::NotMember)
+    }
+
+    /// Send a request to the Alliance Chain on behalf of the signer
+    fn send_request(&mut self, request: AllianceMessage) -> Result<(), AllianceError> {
+        let alliance_chain = self.alliance_chain()?;
+        self.runtime
+            .prepare_message(Message::Request(request))
+            .with_authentication()
+            .send_to(alliance_chain);
+        Ok(())
+    }
+
+    /// Put a proposal to the vote of the current alliance
+    fn submit_proposal(&mut self, proposal: AllianceProposalKind) -> Result<(), AllianceError> {
+        let proposer = self.runtime.authenticated_signer()
+            .ok_or(AllianceError::NotAuthorized)?;
+        self.send_request(AllianceMessage::SubmitProposal { proposer, proposal })
+    }
+
+    /// Tell a member chain about its role in the alliance
+    fn update_membership(&mut self, member_chain: ChainId, role: Option<AllianceMemberRole>) {
+        let alliance_name = self.state.name.get().clone();
+        self.runtime
+            .prepare_message(Message::MembershipUpdate { alliance_name, role })
+            .send_to(member_chain);
+    }
+}
+
+/// Clamp a u128 amount into a u64 balance
+fn saturate_u64(amount: u128) -> u64 {
+    u64::try_from(amount).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        linera_base_types::{ApplicationId, CryptoHash, Timestamp},
+        util::BlockingWait,
+    };
+
+    use super::*;
+
+    fn chain(name: &str) -> ChainId {
+        ChainId(CryptoHash::test_hash(name))
+    }
+
+    fn owner(name: &str) -> AccountOwner {
+        AccountOwner::from(CryptoHash::test_hash(name))
+    }
+
+    fn application(name: &str) -> ApplicationId {
+        ApplicationId::new(CryptoHash::test_hash(name))
+    }
+
+    /// The alliance application on `chain_id`, created on the `alliance` chain
+    fn create_alliance(chain_id: ChainId) -> AllianceContract {
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(())
+            .with_application_creator_chain_id(chain("alliance"))
+            .with_chain_id(chain_id)
+            .with_system_time(Timestamp::from(1_000_000));
+        let mut contract = AllianceContract {
+            state: AllianceState::load(runtime.root_view_storage_context())
+                .blocking_wait()
+                .expect("Failed to read from mock key value store"),
+            runtime,
+        };
+        contract.instantiate(()).now_or_never().expect("Instantiation should not await anything");
+        contract
+    }
+
+    fn execute(contract: &mut AllianceContract, operation: Operation) -> Result<AllianceResponse, AllianceError> {
+        contract.execute_operation(operation).now_or_never().expect("Operation should not await anything")
+    }
+
+    fn receive(contract: &mut AllianceContract, origin: ChainId, signer: Option<AccountOwner>, message: Message) {
+        contract.runtime.set_message_origin_chain_id(origin);
+        contract.runtime.set_message_is_bouncing(false);
+        contract.runtime.set_authenticated_signer(signer);
+        contract.execute_message(message).now_or_never().expect("Message should not await anything");
+    }
+
+    fn join_request(player: AccountOwner, player_chain: ChainId) -> Message {
+        Message::Request(AllianceMessage::JoinRequest { player, player_chain })
+    }
+
+    #[test]
+    fn test_founder_leads_from_own_chain() {
+        let mut alliance = create_alliance(chain("alliance"));
+        alliance.runtime.set_authenticated_signer(owner("founder"));
+        execute(&mut alliance, Operation::Found { name: "Vanguard".to_string() }).expect("Founding should succeed");
+        assert_eq!(alliance.state.members.count().blocking_wait().unwrap(), 0);
+
+        // Naming another chain in the request does not make it a member
+        receive(&mut alliance, chain("founder"), Some(owner("founder")), join_request(owner("founder"), chain("victim")));
+        assert!(!alliance.state.members.contains_key(&chain("victim")).blocking_wait().unwrap());
+
+        // Someone else's request is an application, not a leadership
+        receive(&mut alliance, chain("other"), Some(owner("other")), join_request(owner("other"), chain("other")));
+        assert!(!alliance.state.members.contains_key(&chain("other")).blocking_wait().unwrap());
+
+        receive(&mut alliance, chain("founder"), Some(owner("founder")), join_request(owner("founder"), chain("founder")));
+        let leader = alliance.state.members.get(&chain("founder")).blocking_wait().unwrap()
+            .expect("The founder should be a member");
+        assert_eq!(leader.role, AllianceMemberRole::Leader);
+
+        let sent = alliance.runtime.created_send_message_requests();
+        assert!(sent.iter().any(|request| {
+            request.destination == chain("founder")
+                && matches!(request.message, Message::MembershipUpdate { role: Some(AllianceMemberRole::Leader), .. })
+        }));
+    }
+
+    #[test]
+    fn test_membership_update_needs_an_application() {
+        let mut member = create_alliance(chain("player"));
+        let update = || Message::MembershipUpdate {
+            alliance_name: "Vanguard".to_string(),
+            role: Some(AllianceMemberRole::Leader),
+        };
+
+        receive(&mut member, chain("alliance"), None, update());
+        assert!(member.state.membership.get().is_none());
+
+        member.runtime.set_authenticated_signer(owner("player"));
+        execute(&mut member, Operation::RequestJoin { alliance_chain: chain("alliance") })
+            .expect("Applying should succeed");
+        // Only the alliance applied to may answer
+        receive(&mut member, chain("other"), None, update());
+        assert!(member.state.membership.get().is_none());
+
+        receive(&mut member, chain("alliance"), None, update());
+        let membership = member.state.membership.get().clone().expect("The application should be answered");
+        assert_eq!(membership.alliance_chain, chain("alliance"));
+        assert!(!member.state.applications.contains_key(&chain("alliance")).blocking_wait().unwrap());
+
+        // A stale answer from another alliance cannot remove it
+        receive(&mut member, chain("other"), None, Message::MembershipUpdate {
+            alliance_name: "Other".to_string(),
+            role: None,
+        });
+        assert!(member.state.membership.get().is_some());
+    }
+
+    #[test]
+    fn test_only_registered_dominion_takes_notifications() {
+        let mut member = create_alliance(chain("player"));
+        member.runtime.set_authenticated_caller_id(application("dominion"));
+        assert!(matches!(execute(&mut member, Operation::TakeNotifications), Err(AllianceError::NotAuthorized)));
+
+        execute(&mut member, Operation::RegisterDominion).expect("The first registration should count");
+        member.runtime.set_authenticated_caller_id(application("intruder"));
+        assert!(matches!(execute(&mut member, Operation::RegisterDominion), Err(AllianceError::NotAuthorized)));
+        assert!(matches!(execute(&mut member, Operation::TakeNotifications), Err(AllianceError::NotAuthorized)));
+
+        member.runtime.set_authenticated_caller_id(application("dominion"));
+        assert!(matches!(
+            execute(&mut member, Operation::TakeNotifications),
+            Ok(AllianceResponse::Notifications(notifications)) if notifications.is_empty()
+        ));
+    }
+}
@@ -0,0 +1,217 @@
+//! # Linera Dominion - Alliance Chain Application
+//!
+//! An Alliance Chain keeps an alliance's members, their roles and the shared
+//! treasury. The same application runs on the members' User Chains, where it
+//! relays their requests and records their current membership.
+
+pub mod state;
+
+use async_graphql::{Request, Response};
+use linera_sdk::{
+    graphql::GraphQLMutationRoot,
+    linera_base_types::{AccountOwner, ChainId},
+};
+use linera_dominion_common::{
+    errors::AllianceError,
+    messages::{AllianceMessage, DominionMessage},
+    resources::Resources,
+    types::{AllianceMemberRole, CommitHash, TreatyType},
+};
+use serde::{Deserialize, Serialize};
+
+// Re-export state types
+pub use state::{
+    AllianceState, MemberData, ApplicantData, MembershipData,
+    ProposalData, VoteData, TreatyData,
+};
+
+// ==================== OPERATIONS ====================
+
+/// Operations on the Alliance application
+#[derive(Debug, Serialize, Deserialize, GraphQLMutationRoot)]
+pub enum Operation {
+    // ===== Alliance Chain Operations =====
+    /// Found an alliance on this chain
+    ///
+    /// The signer leads it once they ask to join from their User Chain.
+    Found {
+        name: String,
+    },
+
+    /// Admit a pending applicant (officers and above)
+    ApproveMember {
+        member_chain: ChainId,
+    },
+
+    /// Turn down a pending applicant (officers and above)
+    RejectApplicant {
+        member_chain: ChainId,
+    },
+
+    /// Change a member's role (leader only)
+    ///
+    /// Naming another member leader demotes the current leader to officer.
+    SetRole {
+        member_chain: ChainId,
+        role: AllianceMemberRole,
+    },
+
+    /// Remove a member of lower rank (officers and above)
+    KickMember {
+        member_chain: ChainId,
+    },
+
+    /// Sign a treaty another alliance proposed (leader only)
+    SignTreaty {
+        treaty_id: u64,
+    },
+
+    /// Turn down a treaty another alliance proposed (leader only)
+    DeclineTreaty {
+        treaty_id: u64,
+    },
+
+    // ===== Member Chain Operations =====
+    /// Make the calling application the dominion of this chain
+    ///
+    /// Only the first registration counts: the dominion registers when it is created.
+    RegisterDominion,
+
+    /// Apply to the alliance on `alliance_chain`
+    RequestJoin {
+        alliance_chain: ChainId,
+    },
+
+    /// Leave the current alliance
+    Leave,
+
+    /// Send resources to the treasury of the current alliance
+    ///
+    /// Only an application that has already debited the resources (the
+    /// dominion) may call this.
+    Deposit {
+        resources: Resources,
+    },
+
+    /// Propose paying resources from the treasury to `recipient`
+    ProposeTreasurySpend {
+        recipient: ChainId,
+        resources: Resources,
+    },
+
+    /// Propose removing a member
+    ProposeKick {
+        member_chain: ChainId,
+    },
+
+    /// Propose offering a treaty to another alliance
+    ProposeTreaty {
+        treaty_type: TreatyType,
+        other_alliance: ChainId,
+        penalty_crystals: u64,
+        duration_secs: Option<u64>,
+    },
+
+    /// Propose giving a member another role
+    ProposeRankChange {
+        member_chain: ChainId,
+        role: AllianceMemberRole,
+    },
+
+    /// Vote on an open proposal of the current alliance
+    Vote {
+        proposal_id: u64,
+        approve: bool,
+    },
+
+    /// Report an attack on this chain that broke a non-aggression pact
+    ///
+    /// Called by the Battle application with a battle's result. The Alliance
+    /// Chain takes reports from member chains only, and the attacker's alliance
+    /// checks the attacker against its own members and treaties.
+    ReportAttack {
+        attacker: AccountOwner,
+        attacker_chain: ChainId,
+        /// Hex-encoded battle evidence computed by the Battle Chain
+        evidence_hash: String,
+    },
+
+    /// Hand the queued notifications to the calling application
+    TakeNotifications,
+}
+
+// ==================== MESSAGES ====================
+
+/// Messages for the Alliance application
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Message {
+    /// Request from a member chain to the Alliance Chain
+    Request(AllianceMessage),
+
+    /// Membership change sent from the Alliance Chain to a member chain
+    ///
+    /// `role` is `None` when the membership (or application) ended.
+    MembershipUpdate {
+        alliance_name: String,
+        role: Option<AllianceMemberRole>,
+    },
+
+    /// Notification for the dominion application of a member chain
+    Notify {
+        notification: DominionMessage,
+    },
+
+    // ===== Between Alliance Chains =====
+    // `treaty_id` is the receiver's id for the treaty.
+
+    /// A proposed treaty was signed; `remote_treaty_id` is the signer's id
+    TreatySigned {
+        treaty_id: u64,
+        remote_treaty_id: u64,
+        signed_micros: u64,
+    },
+
+    /// A proposed treaty was turned down
+    TreatyDeclined {
+        treaty_id: u64,
+    },
+
+    /// A member of the receiving alliance may have attacked the sender's
+    TreatyBreach {
+        treaty_id: u64,
+        violator: AccountOwner,
+        violator_chain: ChainId,
+        evidence_hash: CommitHash,
+    },
+
+    /// Penalty paid for breaking a treaty
+    TreatyPenalty {
+        treaty_id: u64,
+        crystals: u64,
+    },
+}
+
+// ==================== RESPONSES ====================
+
+/// Alliance operation response
+#[derive(Debug, Serialize, Deserialize)]
+pub enum AllianceResponse {
+    Ok,
+    /// Notifications taken from the queue, oldest first
+    Notifications(Vec<DominionMessage>),
+}
+
+// ==================== ABI ====================
+
+/// Application ABI
+pub struct AllianceAbi;
+
+impl linera_sdk::abi::ContractAbi for AllianceAbi {
+    type Operation = Operation;
+    type Response = Result<AllianceResponse, AllianceError>;
+}
+
+impl linera_sdk::abi::ServiceAbi for AllianceAbi {
+    type Query = Request;
+    type QueryResponse = Response;
+}
@@ -0,0 +1,246 @@
+//! Alliance Service - GraphQL query service for Alliance Chain
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+use std::sync::Arc;
+use async_graphql::{Object, Request, Response, Schema, EmptySubscription, SimpleObject};
+use linera_sdk::{
+    graphql::GraphQLMutationRoot,
+    linera_base_types::{AccountOwner, ChainId, WithServiceAbi},
+    Service, ServiceRuntime,
+    views::View,
+};
+use linera_dominion_alliance::{AllianceAbi, Operation};
+use linera_dominion_alliance::state::{
+    AllianceState, MembershipData, ProposalData, TreatyData, VoteData,
+};
+use linera_dominion_common::{
+    constants::ALLIANCE_MIN_MEMBERS,
+    resources::Resources,
+    types::AllianceMemberRole,
+};
+
+pub struct AllianceService {
+    state: AllianceState,
+    runtime: Arc<ServiceRuntime<Self>>,
+}
+
+linera_sdk::service!(AllianceService);
+
+impl WithServiceAbi for AllianceService {
+    type Abi = AllianceAbi;
+}
+
+impl Service for AllianceService {
+    type Parameters = ();
+
+    async fn new(runtime: ServiceRuntime<Self>) -> Self {
+        let state = AllianceState::load(runtime.root_view_storage_context())
+            .await
+            .expect("Failed to load state");
+        Self {
+            state,
+            runtime: Arc::new(runtime),
+        }
+    }
+
+    async fn handle_query(&self, request: Request) -> Response {
+        let name = self.state.name.get().clone();
+        let founded_micros = *self.state.founded_micros.get();
+        let treasury = *self.state.treasury.get();
+        let membership = self.state.membership.get().clone();
+
+        let mut members = Vec::new();
+        self.state.members
+            .for_each_index_value(|chain_id, member| {
+                members.push(MemberView {
+                    chain_id,
+                    owner: member.owner,
+                    role: member.role,
+                    joined_micros: member.joined_micros,
+                    contributed_crystals: member.contributed_crystals,
+                });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read members");
+
+        let mut applicants = Vec::new();
+        self.state.applicants
+            .for_each_index_value(|chain_id, applicant| {
+                applicants.push(ApplicantView {
+                    chain_id,
+                    owner: applicant.owner,
+                    requested_micros: applicant.requested_micros,
+                });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read applicants");
+
+        let mut proposals = Vec::new();
+        self.state.proposals
+            .for_each_index_value(|proposal_id, proposal| {
+                proposals.push(ProposalView {
+                    proposal_id,
+                    description: format!("{:?}", proposal.kind),
+                    proposal: proposal.into_owned(),
+                });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read proposals");
+
+        let mut votes = Vec::new();
+        self.state.votes
+            .for_each_index_value(|(proposal_id, voter_chain), vote| {
+                votes.push(VoteView {
+                    proposal_id,
+                    voter_chain,
+                    vote: vote.into_owned(),
+                });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read votes");
+
+        let mut treaties = Vec::new();
+        self.state.treaties
+            .for_each_index_value(|treaty_id, treaty| {
+                treaties.push(TreatyView {
+                    treaty_id,
+                    treaty: treaty.into_owned(),
+                });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read treaties");
+
+        let schema = Schema::build(
+            QueryRoot {
+                name,
+                founded_micros,
+                members,
+                applicants,
+                treasury,
+                membership,
+                proposals,
+                votes,
+                treaties,
+            },
+            Operation::mutation_root(self.runtime.clone()),
+            EmptySubscription,
+        )
+        .finish();
+        schema.execute(request).await
+    }
+}
+
+struct QueryRoot {
+    name: String,
+    founded_micros: Option<u64>,
+    members: Vec<MemberView>,
+    applicants: Vec<ApplicantView>,
+    treasury: Resources,
+    membership: Option<MembershipData>,
+    proposals: Vec<ProposalView>,
+    votes: Vec<VoteView>,
+    treaties: Vec<TreatyView>,
+}
+
+#[Object]
+impl QueryRoot {
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn founded_micros(&self) -> Option<u64> {
+        self.founded_micros
+    }
+
+    async fn member_count(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Whether the alliance has enough members to act as one
+    async fn is_active(&self) -> bool {
+        self.founded_micros.is_some() && self.members.len() >= ALLIANCE_MIN_MEMBERS
+    }
+
+    async fn members(&self) -> &[MemberView] {
+        &self.members
+    }
+
+    async fn applicants(&self) -> &[ApplicantView] {
+        &self.applicants
+    }
+
+    async fn treasury(&self) -> &Resources {
+        &self.treasury
+    }
+
+    async fn proposals(&self) -> &[ProposalView] {
+        &self.proposals
+    }
+
+    /// Every vote cast, for auditing the tallies
+    async fn votes(&self, proposal_id: Option<u64>) -> Vec<&VoteView> {
+        self.votes
+            .iter()
+            .filter(|vote| proposal_id.is_none_or(|id| vote.proposal_id == id))
+            .collect()
+    }
+
+    async fn treaties(&self) -> &[TreatyView] {
+        &self.treaties
+    }
+
+    /// The alliance this chain belongs to, when used as a member chain
+    async fn membership(&self) -> Option<&MembershipData> {
+        self.membership.as_ref()
+    }
+}
+
+/// A member and their User Chain
+#[derive(SimpleObject)]
+struct MemberView {
+    chain_id: ChainId,
+    owner: AccountOwner,
+    role: AllianceMemberRole,
+    joined_micros: u64,
+    contributed_crystals: u64,
+}
+
+/// A pending applicant and their User Chain
+#[derive(SimpleObject)]
+struct ApplicantView {
+    chain_id: ChainId,
+    owner: AccountOwner,
+    requested_micros: u64,
+}
+
+/// A proposal and a readable description of what it does
+#[derive(SimpleObject)]
+struct ProposalView {
+    proposal_id: u64,
+    description: String,
+    #[graphql(flatten)]
+    proposal: ProposalData,
+}
+
+/// A vote and the member chain that cast it
+#[derive(SimpleObject)]
+struct VoteView {
+    proposal_id: u64,
+    voter_chain: ChainId,
+    #[graphql(flatten)]
+    vote: VoteData,
+}
+
+/// A treaty and this alliance's id for it
+#[derive(SimpleObject)]
+struct TreatyView {
+    treaty_id: u64,
+    #[graphql(flatten)]
+    treaty: TreatyData,
+}
@@ -0,0 +1,305 @@
+//! State management for the Alliance Chain
+
+use async_graphql::SimpleObject;
+use linera_dominion_common::{
+    messages::DominionMessage,
+    resources::Resources,
+    types::{AllianceMemberRole, AllianceProposalKind, Treaty, TreatyType, VoteTally},
+};
+use linera_sdk::linera_base_types::{AccountOwner, ApplicationId, ChainId, Timestamp};
+use linera_sdk::views::{
+    linera_views, MapView, QueueView, RegisterView, RootView, ViewError, ViewStorageContext,
+};
+use serde::{Deserialize, Serialize};
+
+/// A member of the alliance, keyed by their User Chain
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MemberData {
+    pub owner: AccountOwner,
+    pub role: AllianceMemberRole,
+    pub joined_micros: u64,
+    /// Crystals deposited into the treasury, the basis of voting power
+    pub contributed_crystals: u64,
+}
+
+impl MemberData {
+    /// Votes the member casts: one per contributed crystal, and at least one
+    pub fn voting_power(&self) -> u64 {
+        self.contributed_crystals.max(1)
+    }
+}
+
+/// A player waiting for an officer to approve them
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ApplicantData {
+    pub owner: AccountOwner,
+    pub requested_micros: u64,
+}
+
+/// A proposal put to the vote of the alliance
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ProposalData {
+    pub proposer: AccountOwner,
+    pub proposer_chain: ChainId,
+    #[graphql(skip)]
+    pub kind: AllianceProposalKind,
+    pub created_micros: u64,
+    /// End of the voting window
+    pub ends_micros: u64,
+    pub tally: VoteTally,
+    pub voter_count: u32,
+    /// Voting power of all members when the vote closed
+    pub total_power: u64,
+    pub status: u8,             // 0=open, 1=passed, 2=rejected, 3=failed
+}
+
+impl ProposalData {
+    pub const OPEN: u8 = 0;
+    pub const PASSED: u8 = 1;
+    pub const REJECTED: u8 = 2;
+    /// Passed, but could no longer be carried out when the vote closed
+    pub const FAILED: u8 = 3;
+}
+
+/// A vote cast on a proposal, kept for the audit trail
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct VoteData {
+    pub voter: AccountOwner,
+    pub approve: bool,
+    pub power: u64,
+    pub cast_micros: u64,
+}
+
+/// A treaty with another alliance, as seen from this Alliance Chain
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct TreatyData {
+    pub counterparty: ChainId,
+    /// Whether this alliance proposed the treaty
+    pub outgoing: bool,
+    /// The counterparty's id for the treaty (0 until known for outgoing treaties)
+    pub remote_treaty_id: u64,
+    pub treaty_type: TreatyType,
+    /// Crystals the alliance that breaks the treaty pays the other
+    pub penalty_crystals: u64,
+    pub duration_secs: Option<u64>,
+    pub signed_micros: Option<u64>,
+    pub expires_micros: Option<u64>,
+    pub status: u8,             // 0=pending, 1=active, 2=declined, 3=broken
+}
+
+impl TreatyData {
+    pub const PENDING: u8 = 0;
+    pub const ACTIVE: u8 = 1;
+    pub const DECLINED: u8 = 2;
+    /// Ended by a violation and its penalty
+    pub const BROKEN: u8 = 3;
+
+    /// Whether the treaty is signed and in force at `now_micros`
+    pub fn is_active(&self, now_micros: u64) -> bool {
+        self.status == Self::ACTIVE && self.expires_micros.is_none_or(|expires| now_micros < expires)
+    }
+
+    /// Whether the treaty is still awaiting a signature or in force
+    pub fn is_live(&self, now_micros: u64) -> bool {
+        self.status == Self::PENDING || self.is_active(now_micros)
+    }
+
+    /// Sign the treaty at `signed_micros`, starting its term
+    pub fn sign(&mut self, signed_micros: u64) {
+        self.status = Self::ACTIVE;
+        self.signed_micros = Some(signed_micros);
+        self.expires_micros = self.duration_secs
+            .map(|secs| signed_micros.saturating_add(secs.saturating_mul(1_000_000)));
+    }
+
+    /// The treaty between this alliance and its counterparty
+    pub fn to_treaty(&self, treaty_id: u64, this_alliance: ChainId) -> Treaty {
+        Treaty {
+            id: treaty_id,
+            treaty_type: self.treaty_type,
+            party_a: this_alliance,
+            party_b: self.counterparty,
+            created_at: Timestamp::from(self.signed_micros.unwrap_or_default()),
+            expires_at: self.expires_micros.map(Timestamp::from),
+            penalty_crystals: self.penalty_crystals as u128,
+        }
+    }
+}
+
+/// The alliance a member chain belongs to, as last reported by its Alliance Chain
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MembershipData {
+    pub alliance_chain: ChainId,
+    pub alliance_name: String,
+    pub role: AllianceMemberRole,
+}
+
+#[derive(RootView)]
+#[view(context = ViewStorageContext)]
+pub struct AllianceState {
+    // ===== Alliance Chain =====
+    pub name: RegisterView<String>,
+    /// When the alliance was founded; `None` until `Found`
+    pub founded_micros: RegisterView<Option<u64>>,
+    /// Signer of `Found`, who becomes leader on joining from their User Chain
+    pub founder: RegisterView<Option<AccountOwner>>,
+    pub members: MapView<ChainId, MemberData>,
+    pub applicants: MapView<ChainId, ApplicantData>,
+    pub treasury: RegisterView<Resources>,
+    pub proposals: MapView<u64, ProposalData>,
+    pub proposal_count: RegisterView<u64>,
+    /// Votes: key = (proposal_id, voter chain)
+    pub votes: MapView<(u64, ChainId), VoteData>,
+    pub treaties: MapView<u64, TreatyData>,
+    pub treaty_count: RegisterView<u64>,
+    /// Battle evidence already used for a violation claim: value = treaty_id
+    pub settled_evidence: MapView<[u8; 32], u64>,
+
+    // ===== Member Chain =====
+    pub membership: RegisterView<Option<MembershipData>>,
+    /// Alliance Chains this chain applied to: value = when
+    pub applications: MapView<ChainId, u64>,
+    /// The dominion application allowed to deposit and take notifications
+    pub dominion_app: RegisterView<Option<ApplicationId>>,
+    /// Proposals this chain voted on: key = (alliance chain, proposal_id)
+    pub voted: MapView<(ChainId, u64), bool>,
+    /// Notifications for the dominion application of this chain
+    pub notifications: QueueView<DominionMessage>,
+}
+
+impl AllianceState {
+    pub fn is_founded(&self) -> bool {
+        self.founded_micros.get().is_some()
+    }
+
+    /// The member chain and record of the member signing as `owner`
+    pub async fn member_by_owner(
+        &self,
+        owner: &AccountOwner,
+    ) -> Result<Option<(ChainId, MemberData)>, ViewError> {
+        let mut found = None;
+        self.members
+            .for_each_index_value_while(|chain, member| {
+                if member.owner == *owner {
+                    found = Some((chain, member.into_owned()));
+                    return Ok(false);
+                }
+                Ok(true)
+            })
+            .await?;
+        Ok(found)
+    }
+
+    /// Combined voting power of all members
+    pub async fn total_voting_power(&self) -> Result<u64, ViewError> {
+        let mut total = 0u64;
+        self.members
+            .for_each_index_value(|_, member| {
+                total = total.saturating_add(member.voting_power());
+                Ok(())
+            })
+            .await?;
+        Ok(total)
+    }
+
+    pub fn add_proposal(&mut self, proposal: ProposalData) -> Result<u64, ViewError> {
+        let proposal_id = *self.proposal_count.get();
+        self.proposals.insert(&proposal_id, proposal)?;
+        self.proposal_count.set(proposal_id + 1);
+        Ok(proposal_id)
+    }
+
+    pub fn add_treaty(&mut self, treaty: TreatyData) -> Result<u64, ViewError> {
+        let treaty_id = *self.treaty_count.get();
+        self.treaties.insert(&treaty_id, treaty)?;
+        self.treaty_count.set(treaty_id + 1);
+        Ok(treaty_id)
+    }
+
+    /// A pending or active treaty of `treaty_type` with `counterparty`
+    pub async fn find_live_treaty(
+        &self,
+        counterparty: ChainId,
+        treaty_type: TreatyType,
+        now_micros: u64,
+    ) -> Result<Option<u64>, ViewError> {
+        let mut found = None;
+        self.treaties
+            .for_each_index_value_while(|treaty_id, treaty| {
+                if treaty.counterparty == counterparty
+                    && treaty.treaty_type == treaty_type
+                    && treaty.is_live(now_micros)
+                {
+                    found = Some(treaty_id);
+                    return Ok(false);
+                }
+                Ok(true)
+            })
+            .await?;
+        Ok(found)
+    }
+
+    /// Non-aggression pacts in force at `now_micros`
+    pub async fn active_pacts(&self, now_micros: u64) -> Result<Vec<(u64, TreatyData)>, ViewError> {
+        let mut pacts = Vec::new();
+        self.treaties
+            .for_each_index_value(|treaty_id, treaty| {
+                if treaty.treaty_type == TreatyType::NonAggressionPact && treaty.is_active(now_micros) {
+                    pacts.push((treaty_id, treaty.into_owned()));
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(pacts)
+    }
+
+    /// The treaty this alliance knows as `treaty_id`, if it is with `counterparty`
+    pub async fn treaty_with(
+        &self,
+        counterparty: ChainId,
+        treaty_id: u64,
+    ) -> Result<Option<TreatyData>, ViewError> {
+        Ok(self.treaties.get(&treaty_id).await?
+            .filter(|treaty| treaty.counterparty == counterparty))
+    }
+
+    /// Open proposals whose voting window has closed by `now_micros`
+    pub async fn closed_proposals(&self, now_micros: u64) -> Result<Vec<u64>, ViewError> {
+        let mut closed = Vec::new();
+        self.proposals
+            .for_each_index_value(|proposal_id, proposal| {
+                if proposal.status == ProposalData::OPEN && proposal.ends_micros <= now_micros {
+                    closed.push(proposal_id);
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(closed)
+    }
+
+    /// The member who takes over from a departing leader
+    ///
+    /// Officers come before members; among equals the longest-serving wins.
+    pub async fn successor(&self, leaving: &ChainId) -> Result<Option<ChainId>, ViewError> {
+        let mut best: Option<(ChainId, u8, u64)> = None;
+        self.members
+            .for_each_index_value(|chain, member| {
+                if chain == *leaving {
+                    return Ok(());
+                }
+                let rank = member.role.rank();
+                let better = match best {
+                    Some((_, best_rank, best_joined)) => {
+                        rank > best_rank || (rank == best_rank && member.joined_micros < best_joined)
+                    }
+                    None => true,
+                };
+                if better {
+                    best = Some((chain, rank, member.joined_micros));
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(best.map(|(chain, _, _)| chain))
+    }
+}
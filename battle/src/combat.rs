@@ -1,35 +1,49 @@
 //! Combat calculation module
 
-use crate::state::CombatantData;
+use linera_dominion_common::{
+    config::GameConfig,
+    research::TechModifiers,
+    resources::Resources,
+    units::{ShipStats, ShipType},
+};
 
-/// Ship attack power by type (index = ship_type)
-const SHIP_ATTACK: [u64; 10] = [5, 15, 50, 150, 100, 5, 1, 20, 80, 300];
+use crate::state::CombatantData;
 
-/// Ship defense power by type (index = ship_type)  
-const SHIP_DEFENSE: [u64; 10] = [2, 10, 30, 100, 150, 10, 5, 15, 50, 200];
+/// Stats of the ship type at `index`, or `None` for unknown types
+fn ship_stats(config: &GameConfig, index: usize) -> Option<ShipStats> {
+    let ship_type = ShipType::from_u8(u8::try_from(index).ok()?)?;
+    Some(config.ship_stats(ship_type))
+}
 
 /// Calculate damage for a combat round
-pub fn calculate_damage(attacker: &CombatantData, defender: &CombatantData) -> (u64, u64) {
+///
+/// Only the remaining ships fight. Attack and defense are scaled by each
+/// side's research modifiers.
+pub fn calculate_damage(
+    config: &GameConfig,
+    attacker: &CombatantData,
+    defender: &CombatantData,
+) -> (u64, u64) {
     // Calculate base attack power from ships
-    let attacker_base: u64 = attacker.ships.iter().enumerate().map(|(i, &c)| {
-        let attack = SHIP_ATTACK.get(i).copied().unwrap_or(10);
-        c as u64 * attack
+    let attacker_base: u64 = attacker.remaining_ships.iter().enumerate().map(|(i, &c)| {
+        let attack = ship_stats(config, i).map_or(0, |stats| stats.attack as u64);
+        c as u64 * TechModifiers::apply(attacker.modifiers.attack, attack)
     }).sum();
     
-    let defender_base: u64 = defender.ships.iter().enumerate().map(|(i, &c)| {
-        let attack = SHIP_ATTACK.get(i).copied().unwrap_or(10);
-        c as u64 * attack
+    let defender_base: u64 = defender.remaining_ships.iter().enumerate().map(|(i, &c)| {
+        let attack = ship_stats(config, i).map_or(0, |stats| stats.attack as u64);
+        c as u64 * TechModifiers::apply(defender.modifiers.attack, attack)
     }).sum();
     
     // Calculate defense
-    let attacker_defense: u64 = attacker.ships.iter().enumerate().map(|(i, &c)| {
-        let defense = SHIP_DEFENSE.get(i).copied().unwrap_or(5);
-        c as u64 * defense
+    let attacker_defense: u64 = attacker.remaining_ships.iter().enumerate().map(|(i, &c)| {
+        let defense = ship_stats(config, i).map_or(0, |stats| stats.defense as u64);
+        c as u64 * TechModifiers::apply(attacker.modifiers.defense, defense)
     }).sum();
     
-    let defender_defense: u64 = defender.ships.iter().enumerate().map(|(i, &c)| {
-        let defense = SHIP_DEFENSE.get(i).copied().unwrap_or(5);
-        c as u64 * defense
+    let defender_defense: u64 = defender.remaining_ships.iter().enumerate().map(|(i, &c)| {
+        let defense = ship_stats(config, i).map_or(0, |stats| stats.defense as u64);
+        c as u64 * TechModifiers::apply(defender.modifiers.defense, defense)
     }).sum();
     
     // Calculate net damage (attack - defense, minimum 0)
@@ -40,17 +54,21 @@ pub fn calculate_damage(attacker: &CombatantData, defender: &CombatantData) -> (
 }
 
 /// Calculate ship losses based on damage
-pub fn calculate_losses(combatant: &CombatantData, damage: u64) -> Vec<u32> {
-    let mut losses = vec![0u32; combatant.ships.len()];
+pub fn calculate_losses(config: &GameConfig, combatant: &CombatantData, damage: u64) -> Vec<u32> {
+    let mut losses = vec![0u32; combatant.remaining_ships.len()];
     let mut remaining_damage = damage;
     
     // Apply damage to ships (starting from weakest)
-    for (i, &count) in combatant.ships.iter().enumerate() {
+    for (i, &count) in combatant.remaining_ships.iter().enumerate() {
         if count == 0 || remaining_damage == 0 {
             continue;
         }
         
-        let hp_per_ship = SHIP_DEFENSE.get(i).copied().unwrap_or(10) * 10;
+        let Some(stats) = ship_stats(config, i) else {
+            continue;
+        };
+        let base_hp = stats.max_health as u64;
+        let hp_per_ship = TechModifiers::apply(combatant.modifiers.hull, base_hp).max(1);
         let ships_lost = (remaining_damage / hp_per_ship).min(count as u64) as u32;
         
         losses[i] = ships_lost;
@@ -69,6 +87,22 @@ pub fn apply_losses(combatant: &mut CombatantData, losses: &[u32]) {
     }
 }
 
+/// Resources left as debris by the ships a combatant lost
+pub fn calculate_debris(config: &GameConfig, combatant: &CombatantData) -> Resources {
+    let mut debris = Resources::zero();
+    for (i, (&count, &remaining)) in combatant.ships.iter().zip(&combatant.remaining_ships).enumerate() {
+        let Some(ship_type) = u8::try_from(i).ok().and_then(ShipType::from_u8) else {
+            continue;
+        };
+        let lost = count.saturating_sub(remaining) as u128;
+        let wreckage = config.ship_cost(ship_type).map(|amount| {
+            amount.saturating_mul(lost).saturating_mul(config.debris_recovery_percentage as u128) / 100
+        });
+        debris = debris.checked_add(&wreckage).unwrap_or(debris);
+    }
+    debris
+}
+
 /// Check if combatant is defeated (no remaining ships)
 pub fn is_defeated(combatant: &CombatantData) -> bool {
     combatant.remaining_ships.iter().all(|&c| c == 0)
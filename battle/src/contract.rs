@@ -3,14 +3,19 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
 use linera_sdk::{
-    linera_base_types::WithContractAbi,
+    linera_base_types::{ChainId, WithContractAbi},
     Contract, ContractRuntime,
     views::{RootView, View},
 };
+use linera_dominion_alliance::{AllianceAbi, Operation as AllianceOperation};
 use linera_dominion_battle::{
     BattleState, BattleAbi, BattleError, BattleParameters, BattleInstantiationArg,
     Operation, Message,
-    state::CombatantData,
+    combat::{apply_losses, calculate_damage, calculate_debris, calculate_losses, is_defeated},
+    state::{CombatantData, TurnRecordData},
+};
+use linera_dominion_common::{
+    crypto::battle_evidence, resources::Resources, types::non_aggression_pact,
 };
 
 pub struct BattleContract {
@@ -40,35 +45,49 @@ impl Contract for BattleContract {
     async fn instantiate(&mut self, arg: Self::InstantiationArgument) {
         let params = self.runtime.application_parameters();
         let now = self.runtime.system_time();
+        assert!(params.config.is_valid(), "Invalid game configuration");
         
         self.state.initialize(
             params.max_turns,
             params.turn_duration_micros,
             now.micros(),
         );
+        self.state.config.set(params.config);
+        self.state.battle_id.set(arg.battle_id);
+        self.state.attacker_owner.set(Some(arg.attacker_owner));
+        self.state.alliance_app.set(arg.alliance_app);
+        
+        // Attacking a member of an alliance bound by a non-aggression pact breaks it
+        let pact = match (arg.attacker_alliance, arg.defender_alliance) {
+            (Some(attacker_alliance), Some(defender_alliance)) if attacker_alliance != defender_alliance => {
+                non_aggression_pact(&arg.treaties, attacker_alliance, defender_alliance, now).cloned()
+            }
+            _ => None,
+        };
+        self.state.pact.set(pact);
         
         // Set up attacker (combatant id 0)
         let attacker = CombatantData {
+            fleet_id: arg.attacker_fleet_id,
             owner_chain: arg.attacker_chain.to_string(),
             ships: arg.attacker_ships.clone(),
             remaining_ships: arg.attacker_ships.clone(),
-            bonded_iron: 0,
-            bonded_deuterium: 0,
-            bonded_crystals: 0,
+            bonded: Resources::zero(),
             is_defender: false,
             has_retreated: false,
+            modifiers: arg.attacker_modifiers,
         };
         
         // Set up defender (combatant id 1)
         let defender = CombatantData {
+            fleet_id: arg.defender_fleet_id,
             owner_chain: arg.defender_chain.to_string(),
             ships: arg.defender_ships.clone(),
             remaining_ships: arg.defender_ships.clone(),
-            bonded_iron: 0,
-            bonded_deuterium: 0,
-            bonded_crystals: 0,
+            bonded: Resources::zero(),
             is_defender: true,
             has_retreated: false,
+            modifiers: arg.defender_modifiers,
         };
         
         self.state.combatants.insert(&0, attacker).expect("insert attacker");
@@ -91,7 +110,7 @@ impl Contract for BattleContract {
             }
             
             Operation::RequestResolution => {
-                self.process_turn(now_micros);
+                self.process_turn(now_micros).await;
                 Ok(())
             }
             
@@ -106,7 +125,7 @@ impl Contract for BattleContract {
                     return Err(BattleError::TimeoutNotReached);
                 }
                 
-                self.state.is_active.set(false);
+                self.finish().await;
                 Ok(())
             }
             
@@ -122,9 +141,42 @@ impl Contract for BattleContract {
             Message::SubmitCommand { fleet_id: _, command: _ } => {}
             Message::RequestResolution => {}
             Message::ForceTimeout => {
-                self.state.is_active.set(false);
+                if *self.state.is_active.get() {
+                    self.finish().await;
+                }
+            }
+            Message::BattleResult {
+                attacker,
+                attacker_chain,
+                defender_chain,
+                evidence_hash,
+                pact_treaty_id,
+                alliance_app,
+                ..
+            } => {
+                // Only the Battle Chain, where this battle was created, reports its result
+                let origin = self.runtime.message_origin_chain_id();
+                if origin != Some(self.runtime.application_creator_chain_id())
+                    || defender_chain != self.runtime.chain_id()
+                    || pact_treaty_id.is_none()
+                {
+                    return;
+                }
+                let Some(alliance_app) = alliance_app else {
+                    return;
+                };
+                // The alliances check the report against their own members and
+                // treaties; the call fails for defenders outside any alliance
+                let _ = self.runtime.call_application(
+                    false,
+                    alliance_app.with_abi::<AllianceAbi>(),
+                    &AllianceOperation::ReportAttack {
+                        attacker,
+                        attacker_chain,
+                        evidence_hash: hex::encode(evidence_hash),
+                    },
+                );
             }
-            Message::BattleResult { .. } => {}
         }
     }
 
@@ -134,14 +186,225 @@ impl Contract for BattleContract {
 }
 
 impl BattleContract {
-    fn process_turn(&mut self, _now_micros: u64) {
+    /// Fight a round, ending the battle once a side is destroyed or the turns run out
+    async fn process_turn(&mut self, now_micros: u64) {
         let current_turn = *self.state.current_turn.get();
         let max_turns = *self.state.max_turns.get();
+        let config = self.state.config.get().clone();
+        
+        let attacker = self.state.combatants.get(&0).await.expect("Failed to read attacker");
+        let defender = self.state.combatants.get(&1).await.expect("Failed to read defender");
+        let (Some(mut attacker), Some(mut defender)) = (attacker, defender) else {
+            return;
+        };
+        let (attacker_damage, defender_damage) = calculate_damage(&config, &attacker, &defender);
+        let attacker_losses = calculate_losses(&config, &attacker, defender_damage);
+        let defender_losses = calculate_losses(&config, &defender, attacker_damage);
+        apply_losses(&mut attacker, &attacker_losses);
+        apply_losses(&mut defender, &defender_losses);
+        let is_decided = is_defeated(&attacker) || is_defeated(&defender);
         
+        self.state.turn_records.insert(&current_turn, TurnRecordData {
+            actions: Vec::new(),
+            damages: vec![attacker_damage, defender_damage],
+            losses: attacker_losses.into_iter().chain(defender_losses).collect(),
+            timestamp_micros: now_micros,
+        })
+        .expect("Failed to record turn");
+        self.state.combatants.insert(&0, attacker).expect("Failed to update attacker");
+        self.state.combatants.insert(&1, defender).expect("Failed to update defender");
         self.state.current_turn.set(current_turn + 1);
         
-        if current_turn + 1 >= max_turns {
-            self.state.is_active.set(false);
+        if is_decided || current_turn + 1 >= max_turns {
+            self.finish().await;
+        }
+    }
+    
+    /// End the battle and send its result to the defender's chain
+    async fn finish(&mut self) {
+        self.state.is_active.set(false);
+        
+        let attacker = self.state.combatants.get(&0).await.expect("Failed to read attacker");
+        let defender = self.state.combatants.get(&1).await.expect("Failed to read defender");
+        let (Some(attacker), Some(defender)) = (attacker, defender) else {
+            return;
+        };
+        let config = self.state.config.get().clone();
+        let winner = match (is_defeated(&attacker), is_defeated(&defender)) {
+            (false, true) => Some(attacker.fleet_id),
+            (true, false) => Some(defender.fleet_id),
+            _ => None,
+        };
+        let debris = calculate_debris(&config, &attacker)
+            .checked_add(&calculate_debris(&config, &defender))
+            .expect("Failed to add up debris");
+        self.state.winner.set(winner);
+        self.state.debris.set(debris);
+        
+        let (Ok(attacker_chain), Ok(defender_chain)) =
+            (attacker.owner_chain.parse::<ChainId>(), defender.owner_chain.parse::<ChainId>())
+        else {
+            return;
+        };
+        let Some(attacker_owner) = *self.state.attacker_owner.get() else {
+            return;
+        };
+        let battle_id = *self.state.battle_id.get();
+        let started_micros = *self.state.start_time_micros.get();
+        
+        let pact_treaty_id = self.state.pact.get().as_ref().map(|treaty| treaty.id);
+        let alliance_app = *self.state.alliance_app.get();
+        
+        self.runtime
+            .prepare_message(Message::BattleResult {
+                battle_id,
+                winner,
+                attacker_surviving: attacker.remaining_ships,
+                defender_surviving: defender.remaining_ships,
+                debris,
+                attacker: attacker_owner,
+                attacker_chain,
+                defender_chain,
+                evidence_hash: battle_evidence(battle_id, &attacker_chain, &defender_chain, started_micros),
+                pact_treaty_id,
+                alliance_app,
+            })
+            .with_tracking()
+            .send_to(defender_chain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        linera_base_types::{AccountOwner, ApplicationId, CryptoHash, Timestamp},
+        util::BlockingWait,
+    };
+    use linera_dominion_common::{
+        config::GameConfig,
+        research::TechModifiers,
+        types::{Treaty, TreatyType},
+        units::ShipType,
+    };
+
+    use super::*;
+
+    fn chain(name: &str) -> ChainId {
+        ChainId(CryptoHash::test_hash(name))
+    }
+
+    fn owner(name: &str) -> AccountOwner {
+        AccountOwner::from(CryptoHash::test_hash(name))
+    }
+
+    fn ships(ship_type: ShipType, count: u32) -> Vec<u32> {
+        let mut ships = vec![0; ShipType::all().len()];
+        ships[ship_type as usize] = count;
+        ships
+    }
+
+    fn parameters() -> BattleParameters {
+        BattleParameters {
+            region_chain: chain("region"),
+            position_x: 5,
+            position_y: 5,
+            max_turns: 10,
+            turn_timeout_secs: 60,
+            turn_duration_micros: 60_000_000,
+            config: GameConfig::default(),
+        }
+    }
+
+    /// A battle between a fleet of battleships and a lone scout, whose
+    /// alliances are bound by a non-aggression pact
+    fn argument() -> BattleInstantiationArg {
+        BattleInstantiationArg {
+            battle_id: 7,
+            attacker_fleet_id: 1,
+            attacker_owner: owner("attacker"),
+            attacker_chain: chain("attacker"),
+            attacker_ships: ships(ShipType::Battleship, 20),
+            attacker_modifiers: TechModifiers::default(),
+            defender_fleet_id: 2,
+            defender_owner: owner("defender"),
+            defender_chain: chain("defender"),
+            defender_ships: ships(ShipType::Scout, 1),
+            defender_modifiers: TechModifiers::default(),
+            attacker_alliance: Some(chain("red")),
+            defender_alliance: Some(chain("blue")),
+            treaties: vec![Treaty {
+                id: 3,
+                treaty_type: TreatyType::NonAggressionPact,
+                party_a: chain("blue"),
+                party_b: chain("red"),
+                created_at: Timestamp::from(0),
+                expires_at: None,
+                penalty_crystals: 500,
+            }],
+            alliance_app: Some(ApplicationId::new(CryptoHash::test_hash("alliance"))),
         }
     }
+
+    fn create_battle(chain_id: ChainId) -> BattleContract {
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(parameters())
+            .with_application_creator_chain_id(chain("battle"))
+            .with_chain_id(chain_id)
+            .with_system_time(Timestamp::from(1_000_000));
+        let mut contract = BattleContract {
+            state: BattleState::load(runtime.root_view_storage_context())
+                .blocking_wait()
+                .expect("Failed to read from mock key value store"),
+            runtime,
+        };
+        contract.instantiate(argument()).now_or_never().expect("Instantiation should not await anything");
+        contract
+    }
+
+    #[test]
+    fn test_battle_resolves_and_reports_pact() {
+        let mut battle = create_battle(chain("battle"));
+        assert_eq!(battle.state.pact.get().as_ref().map(|treaty| treaty.id), Some(3));
+
+        while *battle.state.is_active.get() {
+            battle.execute_operation(Operation::RequestResolution)
+                .now_or_never()
+                .expect("Operation should not await anything")
+                .expect("An active battle should resolve a turn");
+        }
+        assert_eq!(*battle.state.winner.get(), Some(1));
+        assert!(!battle.state.debris.get().is_zero());
+
+        let sent = battle.runtime.created_send_message_requests();
+        assert!(sent.iter().any(|request| {
+            request.destination == chain("defender")
+                && request.is_tracked
+                && matches!(
+                    request.message,
+                    Message::BattleResult { battle_id: 7, winner: Some(1), pact_treaty_id: Some(3), .. }
+                )
+        }));
+    }
+
+    #[test]
+    fn test_battle_result_only_from_battle_chain() {
+        let mut defender = create_battle(chain("defender"));
+        let result = Message::BattleResult {
+            battle_id: 7,
+            winner: Some(1),
+            attacker_surviving: Vec::new(),
+            defender_surviving: Vec::new(),
+            debris: Resources::zero(),
+            attacker: owner("attacker"),
+            attacker_chain: chain("attacker"),
+            defender_chain: chain("defender"),
+            evidence_hash: [0; 32],
+            pact_treaty_id: Some(3),
+            alliance_app: argument().alliance_app,
+        };
+        // Reporting would call the alliance application, which is not mocked
+        defender.runtime.set_message_origin_chain_id(chain("attacker"));
+        defender.execute_message(result).now_or_never().expect("Message should not await anything");
+    }
 }
@@ -5,18 +5,21 @@
 pub mod state;
 pub mod combat;
 
-use async_graphql::{Request, Response, InputObject, SimpleObject, Enum};
+use async_graphql::{Request, Response, SimpleObject, Enum};
 use linera_sdk::{
     graphql::GraphQLMutationRoot,
-    linera_base_types::{AccountOwner, ChainId, Timestamp},
+    linera_base_types::{AccountOwner, ApplicationId, ChainId, Timestamp},
     views::{linera_views, RootView, ViewStorageContext},
 };
+use linera_dominion_common::{
+    config::GameConfig, research::TechModifiers, resources::Resources, types::{CommitHash, Treaty},
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 // Re-export state types
 pub use state::{
-    BattleState, CombatantData, TurnRecordData, WarBondData,
+    BattleState, CombatantData, TurnRecordData,
 };
 
 // ==================== ENUMS ====================
@@ -46,16 +49,6 @@ pub enum TerminationReason {
     MaxTurnsReached,
 }
 
-// ==================== INPUT TYPES ====================
-
-/// Resource input
-#[derive(Debug, Clone, Serialize, Deserialize, InputObject)]
-pub struct ResourceInput {
-    pub iron: u64,
-    pub deuterium: u64,
-    pub crystals: u64,
-}
-
 // ==================== OPERATIONS ====================
 
 /// Operations on Battle Chain
@@ -76,7 +69,7 @@ pub enum Operation {
     
     /// Add resources to war bond
     ExtendWarBond {
-        additional: ResourceInput,
+        additional: Resources,
     },
 }
 
@@ -91,10 +84,12 @@ pub enum Message {
         attacker_owner: AccountOwner,
         attacker_chain: ChainId,
         attacker_ships: Vec<u32>,
+        attacker_modifiers: TechModifiers,
         defender_fleet_id: u64,
         defender_owner: AccountOwner,
         defender_chain: ChainId,
         defender_ships: Vec<u32>,
+        defender_modifiers: TechModifiers,
     },
     
     /// Submit command via message
@@ -109,14 +104,23 @@ pub enum Message {
     /// Force timeout
     ForceTimeout,
     
-    /// Battle result to be sent back
+    /// Battle result, sent to the defender's chain once the battle ends
+    ///
+    /// `winner` is the fleet id of the side left standing. When the attack
+    /// broke the non-aggression pact `pact_treaty_id`, the defender's chain
+    /// reports it to its alliance through `alliance_app`.
     BattleResult {
         battle_id: u64,
         winner: Option<u64>,
         attacker_surviving: Vec<u32>,
         defender_surviving: Vec<u32>,
-        debris_iron: u64,
-        debris_deuterium: u64,
+        debris: Resources,
+        attacker: AccountOwner,
+        attacker_chain: ChainId,
+        defender_chain: ChainId,
+        evidence_hash: CommitHash,
+        pact_treaty_id: Option<u64>,
+        alliance_app: Option<ApplicationId>,
     },
 }
 
@@ -167,6 +171,9 @@ pub struct BattleParameters {
     pub turn_timeout_secs: u64,
     /// Turn duration in microseconds
     pub turn_duration_micros: u64,
+    /// Ship stats and costs used in combat
+    #[serde(default)]
+    pub config: GameConfig,
 }
 
 /// Battle instantiation argument
@@ -177,10 +184,23 @@ pub struct BattleInstantiationArg {
     pub attacker_owner: AccountOwner,
     pub attacker_chain: ChainId,
     pub attacker_ships: Vec<u32>,
+    pub attacker_modifiers: TechModifiers,
     pub defender_fleet_id: u64,
     pub defender_owner: AccountOwner,
     pub defender_chain: ChainId,
     pub defender_ships: Vec<u32>,
+    pub defender_modifiers: TechModifiers,
+    /// Alliance Chains of the combatants, if they belong to one
+    #[serde(default)]
+    pub attacker_alliance: Option<ChainId>,
+    #[serde(default)]
+    pub defender_alliance: Option<ChainId>,
+    /// Treaties between the two alliances when the battle was created
+    #[serde(default)]
+    pub treaties: Vec<Treaty>,
+    /// Alliance application the defender's chain reports a broken pact to
+    #[serde(default)]
+    pub alliance_app: Option<ApplicationId>,
 }
 
 // ==================== ABI ====================
@@ -10,6 +10,7 @@ use linera_sdk::{
 };
 use linera_dominion_battle::BattleAbi;
 use linera_dominion_battle::state::BattleState;
+use linera_dominion_common::resources::Resources;
 
 pub struct BattleService {
     state: BattleState,
@@ -42,6 +43,8 @@ impl Service for BattleService {
         let is_active = *self.state.is_active.get();
         let start_time_micros = *self.state.start_time_micros.get();
         let combatant_count = *self.state.combatant_count.get();
+        let winner = *self.state.winner.get();
+        let debris = *self.state.debris.get();
         
         let schema = async_graphql::Schema::build(
             QueryRoot {
@@ -54,6 +57,8 @@ impl Service for BattleService {
                 is_active,
                 start_time_micros,
                 combatant_count,
+                winner,
+                debris,
             },
             async_graphql::EmptyMutation,
             async_graphql::EmptySubscription,
@@ -73,6 +78,8 @@ struct QueryRoot {
     is_active: bool,
     start_time_micros: u64,
     combatant_count: u64,
+    winner: Option<u64>,
+    debris: Resources,
 }
 
 #[Object]
@@ -112,4 +119,14 @@ impl QueryRoot {
     async fn combatant_count(&self) -> u64 {
         self.combatant_count
     }
+
+    /// Fleet id of the side left standing, once the battle is over
+    async fn winner(&self) -> Option<u64> {
+        self.winner
+    }
+
+    /// Resources left by the ships destroyed in the battle
+    async fn debris(&self) -> Resources {
+        self.debris
+    }
 }
@@ -1,36 +1,36 @@
 //! State management for the Battle Chain
 
 use async_graphql::SimpleObject;
+use linera_dominion_common::{
+    config::GameConfig, research::TechModifiers, resources::Resources, types::Treaty,
+};
+use linera_sdk::linera_base_types::{AccountOwner, ApplicationId};
 use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct CombatantData {
+    pub fleet_id: u64,
     pub owner_chain: String,
     pub ships: Vec<u32>,
     pub remaining_ships: Vec<u32>,
-    pub bonded_iron: u64,
-    pub bonded_deuterium: u64,
-    pub bonded_crystals: u64,
+    pub bonded: Resources,
     pub is_defender: bool,
     pub has_retreated: bool,
+    /// Research modifiers of the fleet owner
+    pub modifiers: TechModifiers,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct TurnRecordData {
     pub actions: Vec<u8>,
+    /// Damage dealt by the attacker, then by the defender
     pub damages: Vec<u64>,
+    /// Ships lost by type: the attacker's, then the defender's
     pub losses: Vec<u32>,
     pub timestamp_micros: u64,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
-pub struct WarBondData {
-    pub iron: u64,
-    pub deuterium: u64,
-    pub crystals: u64,
-}
-
 #[derive(RootView, SimpleObject)]
 #[view(context = ViewStorageContext)]
 pub struct BattleState {
@@ -46,7 +46,21 @@ pub struct BattleState {
     pub combatants: MapView<u64, CombatantData>,
     pub combatant_count: RegisterView<u64>,
     pub turn_records: MapView<u32, TurnRecordData>,
-    pub war_bonds: MapView<u64, WarBondData>,
+    pub war_bonds: MapView<u64, Resources>,
+    /// Owner of the attacking fleet, named in the battle result
+    pub attacker_owner: RegisterView<Option<AccountOwner>>,
+    /// Game configuration the battle was created with
+    pub config: RegisterView<GameConfig>,
+    /// Non-aggression pact between the combatants' alliances that the attack breaks
+    #[graphql(skip)]
+    pub pact: RegisterView<Option<Treaty>>,
+    /// Alliance application the defender's chain reports a broken pact to
+    #[graphql(skip)]
+    pub alliance_app: RegisterView<Option<ApplicationId>>,
+    /// Fleet id of the side left standing, once the battle is over
+    pub winner: RegisterView<Option<u64>>,
+    /// Resources left by the ships destroyed in the battle
+    pub debris: RegisterView<Resources>,
 }
 
 impl BattleState {
@@ -61,5 +75,8 @@ impl BattleState {
         self.is_active.set(true);
         self.start_time_micros.set(now_micros);
         self.combatant_count.set(0);
+        self.attacker_owner.set(None);
+        self.winner.set(None);
+        self.debris.set(Resources::zero());
     }
 }
@@ -22,6 +22,12 @@ cargo build --package linera-dominion-region --release --target wasm32-unknown-u
 echo "📦 Building Battle Chain contract..."
 cargo build --package linera-dominion-battle --release --target wasm32-unknown-unknown
 
+echo "📦 Building Alliance Chain contract..."
+cargo build --package linera-dominion-alliance --release --target wasm32-unknown-unknown
+
+echo "📦 Building Senate Chain contract..."
+cargo build --package linera-dominion-senate --release --target wasm32-unknown-unknown
+
 # Create output directory
 mkdir -p target/deploy
 
@@ -41,6 +47,16 @@ if [ -f target/wasm32-unknown-unknown/release/linera_dominion_battle.wasm ]; the
     echo "✅ Battle contract: target/deploy/linera_dominion_battle.wasm"
 fi
 
+if [ -f target/wasm32-unknown-unknown/release/linera_dominion_alliance.wasm ]; then
+    cp target/wasm32-unknown-unknown/release/linera_dominion_alliance.wasm target/deploy/
+    echo "✅ Alliance contract: target/deploy/linera_dominion_alliance.wasm"
+fi
+
+if [ -f target/wasm32-unknown-unknown/release/linera_dominion_senate.wasm ]; then
+    cp target/wasm32-unknown-unknown/release/linera_dominion_senate.wasm target/deploy/
+    echo "✅ Senate contract: target/deploy/linera_dominion_senate.wasm"
+fi
+
 echo ""
 echo "🎮 Linera Dominion build complete!"
 echo ""
@@ -0,0 +1,365 @@
+//! Runtime game configuration
+//!
+//! Every chain loads a `GameConfig` from its application parameters and keeps
+//! it in its state. Costs, rates and ship stats are read from it rather than
+//! from the constants, which only provide the defaults. Parameter changes
+//! approved by the Senate are queued and applied at their effective time.
+
+use async_graphql::SimpleObject;
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    BUILDING_COST_SCALING_FACTOR, CONSTRUCTION_CANCEL_REFUND_PERCENTAGE, CONSTRUCTION_TIME_MULTIPLIER,
+    CONSTRUCTION_TIME_SCALING_FACTOR, CRYSTALS_SCALING_FACTOR, DEBRIS_RECOVERY_PERCENTAGE,
+    DEMOLITION_SALVAGE_PERCENTAGE, DEUTERIUM_SCALING_FACTOR, DIPLOMACY_PROPOSAL_EXPIRATION,
+    INVASION_DEFENSE_WINDOW, INVASION_LOOT_PERCENTAGE, INVASION_MAX_ROUNDS, IRON_SCALING_FACTOR,
+    RESEARCH_CANCEL_REFUND_PERCENTAGE,
+};
+use crate::governance::{decode_parameter_value, GovernedParameter};
+use crate::resources::Resources;
+use crate::types::{BuildingType, Technology};
+use crate::units::{ShipStats, ShipType};
+
+/// Version of the configuration format
+pub const GAME_CONFIG_FORMAT: u32 = 3;
+
+/// Cost and build time of a building at level 1
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, SimpleObject)]
+pub struct BuildingConfig {
+    pub cost: Resources,
+    pub build_time_secs: u64,
+}
+
+/// Cost, build time and stats of a ship
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SimpleObject)]
+pub struct ShipConfig {
+    pub cost: Resources,
+    pub build_time_secs: u64,
+    pub stats: ShipStats,
+}
+
+/// A Senate-approved change waiting for its effective time
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SimpleObject)]
+pub struct PendingChange {
+    pub parameter: GovernedParameter,
+    pub value: u64,
+    pub effective_micros: u64,
+}
+
+/// Game configuration shared by all chains
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SimpleObject)]
+pub struct GameConfig {
+    /// Format of the configuration
+    pub format: u32,
+    /// Number of parameter changes applied since the defaults
+    pub version: u64,
+
+    // ===== Production (per hour at level 1, scaling fixed point 150 = 1.5x) =====
+    pub iron_base_rate: u64,
+    pub iron_scaling_factor: u64,
+    pub deuterium_base_rate: u64,
+    pub deuterium_scaling_factor: u64,
+    pub crystals_base_rate: u64,
+    pub crystals_scaling_factor: u64,
+
+    // ===== Construction =====
+    pub building_cost_scaling_factor: u64,
+    pub construction_time_multiplier: u64,
+    pub construction_time_scaling_factor: u64,
+    /// Share of the unspent cost refunded when cancelling a building or ship order (percentage)
+    pub construction_cancel_refund_percentage: u64,
+    /// Share of the cumulative investment salvaged when demolishing (percentage)
+    pub demolition_salvage_percentage: u64,
+    /// Indexed by `BuildingType` discriminant
+    pub buildings: Vec<BuildingConfig>,
+    /// Indexed by `ShipType` discriminant
+    pub ships: Vec<ShipConfig>,
+
+    // ===== Research =====
+    /// Cost of level 1, indexed by `Technology` discriminant
+    pub research_costs: Vec<Resources>,
+    /// Share of the unspent cost refunded when cancelling research (percentage)
+    pub research_cancel_refund_percentage: u64,
+
+    // ===== Combat =====
+    /// Share of the cost of destroyed ships left as debris (percentage)
+    pub debris_recovery_percentage: u64,
+    pub invasion_max_rounds: u32,
+    /// Time the defender has to commit fleets to an invasion, in seconds
+    pub invasion_defense_window: u64,
+    /// Share of the defender's resources a victorious invasion can carry off (percentage)
+    pub invasion_loot_percentage: u64,
+
+    // ===== Diplomacy =====
+    /// Time an alliance or peace proposal stays open, in seconds
+    pub diplomacy_proposal_expiration: u64,
+
+    // ===== Economy =====
+    pub war_bond_cost: u64,
+    pub trade_fee_percentage: u64,
+    pub trade_offer_expiration: u64,
+    pub region_entry_fee: u64,
+    pub stake_decay_rate_per_hour: u64,
+
+    /// Approved changes not yet in effect, earliest first
+    pub pending: Vec<PendingChange>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        let mut config = Self {
+            format: GAME_CONFIG_FORMAT,
+            version: 0,
+            iron_base_rate: 0,
+            iron_scaling_factor: IRON_SCALING_FACTOR,
+            deuterium_base_rate: 0,
+            deuterium_scaling_factor: DEUTERIUM_SCALING_FACTOR,
+            crystals_base_rate: 0,
+            crystals_scaling_factor: CRYSTALS_SCALING_FACTOR,
+            building_cost_scaling_factor: BUILDING_COST_SCALING_FACTOR,
+            construction_time_multiplier: CONSTRUCTION_TIME_MULTIPLIER,
+            construction_time_scaling_factor: CONSTRUCTION_TIME_SCALING_FACTOR,
+            construction_cancel_refund_percentage: CONSTRUCTION_CANCEL_REFUND_PERCENTAGE,
+            demolition_salvage_percentage: DEMOLITION_SALVAGE_PERCENTAGE,
+            buildings: BuildingType::all().iter().map(|building_type| default_building(*building_type)).collect(),
+            ships: ShipType::all().iter().map(|ship_type| default_ship(*ship_type)).collect(),
+            research_costs: Technology::all().iter().map(Technology::base_research_cost).collect(),
+            research_cancel_refund_percentage: RESEARCH_CANCEL_REFUND_PERCENTAGE,
+            debris_recovery_percentage: DEBRIS_RECOVERY_PERCENTAGE as u64,
+            invasion_max_rounds: INVASION_MAX_ROUNDS,
+            invasion_defense_window: INVASION_DEFENSE_WINDOW,
+            invasion_loot_percentage: INVASION_LOOT_PERCENTAGE,
+            diplomacy_proposal_expiration: DIPLOMACY_PROPOSAL_EXPIRATION,
+            war_bond_cost: 0,
+            trade_fee_percentage: 0,
+            trade_offer_expiration: 0,
+            region_entry_fee: 0,
+            stake_decay_rate_per_hour: 0,
+            pending: Vec::new(),
+        };
+        for parameter in GovernedParameter::all() {
+            config.set_parameter(*parameter, parameter.default_value());
+        }
+        config
+    }
+}
+
+impl GameConfig {
+    /// Whether the tables cover every building, ship type and technology
+    pub fn is_valid(&self) -> bool {
+        self.format == GAME_CONFIG_FORMAT
+            && self.buildings.len() == BuildingType::all().len()
+            && self.ships.len() == ShipType::all().len()
+            && self.research_costs.len() == Technology::all().len()
+    }
+
+    pub fn building(&self, building_type: BuildingType) -> &BuildingConfig {
+        &self.buildings[building_type as usize]
+    }
+
+    pub fn ship(&self, ship_type: ShipType) -> &ShipConfig {
+        &self.ships[ship_type as usize]
+    }
+
+    /// Cost of reaching `level`
+    pub fn building_cost(&self, building_type: BuildingType, level: u32) -> Resources {
+        let factor = self.building_cost_scaling_factor as u128;
+        self.building(building_type).cost.map(|amount| {
+            (1..level).fold(amount, |value, _| value.saturating_mul(factor) / 100)
+        })
+    }
+
+    /// Construction time in seconds for reaching `level`, before research
+    pub fn building_time(&self, building_type: BuildingType, level: u32) -> u64 {
+        scale_by_level(
+            self.building(building_type).build_time_secs.saturating_mul(self.construction_time_multiplier),
+            self.construction_time_scaling_factor,
+            level,
+        )
+    }
+
+    pub fn ship_cost(&self, ship_type: ShipType) -> Resources {
+        self.ship(ship_type).cost
+    }
+
+    /// Build time of one ship in seconds, before research
+    pub fn ship_time(&self, ship_type: ShipType) -> u64 {
+        self.ship(ship_type).build_time_secs.saturating_mul(self.construction_time_multiplier)
+    }
+
+    pub fn ship_stats(&self, ship_type: ShipType) -> ShipStats {
+        self.ship(ship_type).stats
+    }
+
+    /// Crystals bonded when declaring war
+    pub fn war_bond(&self) -> Resources {
+        Resources::crystals(self.war_bond_cost as u128)
+    }
+
+    /// Crystals paid for every fleet sent into a region
+    pub fn entry_fee(&self) -> Resources {
+        Resources::crystals(self.region_entry_fee as u128)
+    }
+
+    /// Share of a trade's proceeds taken as a fee, rounded down
+    pub fn trade_fee(&self, proceeds: &Resources) -> Resources {
+        let percentage = self.trade_fee_percentage as u128;
+        proceeds.map(|amount| amount / 100 * percentage + amount % 100 * percentage / 100)
+    }
+
+    /// Research cost for reaching `level`, growing linearly with the level
+    pub fn research_cost(&self, technology: Technology, level: u32) -> Resources {
+        self.research_costs[technology as usize].map(|amount| amount.saturating_mul(level as u128))
+    }
+
+    /// Current value of a governed parameter
+    pub fn parameter(&self, parameter: GovernedParameter) -> u64 {
+        match parameter {
+            GovernedParameter::IronBaseRate => self.iron_base_rate,
+            GovernedParameter::DeuteriumBaseRate => self.deuterium_base_rate,
+            GovernedParameter::CrystalsBaseRate => self.crystals_base_rate,
+            GovernedParameter::WarBondCost => self.war_bond_cost,
+            GovernedParameter::TradeFeePercentage => self.trade_fee_percentage,
+            GovernedParameter::RegionEntryFee => self.region_entry_fee,
+            GovernedParameter::StakeDecayRatePerHour => self.stake_decay_rate_per_hour,
+            GovernedParameter::TradeOfferExpiration => self.trade_offer_expiration,
+        }
+    }
+
+    fn set_parameter(&mut self, parameter: GovernedParameter, value: u64) {
+        let field = match parameter {
+            GovernedParameter::IronBaseRate => &mut self.iron_base_rate,
+            GovernedParameter::DeuteriumBaseRate => &mut self.deuterium_base_rate,
+            GovernedParameter::CrystalsBaseRate => &mut self.crystals_base_rate,
+            GovernedParameter::WarBondCost => &mut self.war_bond_cost,
+            GovernedParameter::TradeFeePercentage => &mut self.trade_fee_percentage,
+            GovernedParameter::RegionEntryFee => &mut self.region_entry_fee,
+            GovernedParameter::StakeDecayRatePerHour => &mut self.stake_decay_rate_per_hour,
+            GovernedParameter::TradeOfferExpiration => &mut self.trade_offer_expiration,
+        };
+        *field = value;
+    }
+
+    /// Queue a `ParameterUpdate` from the Senate
+    ///
+    /// Returns `false` for unknown parameters, undecodable values and values
+    /// outside the parameter's bounds.
+    pub fn schedule(&mut self, parameter_name: &str, new_value: &[u8], effective_from: Timestamp) -> bool {
+        let Some(parameter) = GovernedParameter::from_name(parameter_name) else {
+            return false;
+        };
+        let Some(value) = decode_parameter_value(new_value) else {
+            return false;
+        };
+        let bounds = parameter.bounds();
+        if value < bounds.min || value > bounds.max {
+            return false;
+        }
+        let effective_micros = effective_from.micros();
+        let position = self.pending
+            .iter()
+            .position(|change| change.effective_micros > effective_micros)
+            .unwrap_or(self.pending.len());
+        self.pending.insert(position, PendingChange { parameter, value, effective_micros });
+        true
+    }
+
+    /// Effective time of the earliest queued change due by `now_micros`
+    pub fn next_due(&self, now_micros: u64) -> Option<u64> {
+        self.pending
+            .first()
+            .map(|change| change.effective_micros)
+            .filter(|effective| *effective <= now_micros)
+    }
+
+    /// Apply every queued change due by `now_micros`
+    pub fn apply_due(&mut self, now_micros: u64) {
+        while self.next_due(now_micros).is_some() {
+            let change = self.pending.remove(0);
+            self.set_parameter(change.parameter, change.value);
+            self.version += 1;
+        }
+    }
+}
+
+fn default_building(building_type: BuildingType) -> BuildingConfig {
+    let (iron, deuterium, build_time_secs) = match building_type {
+        BuildingType::MinerDrone => (100, 25, 60),
+        BuildingType::GasSiphon => (75, 100, 60),
+        BuildingType::ChronosCollider => (1000, 500, 600),
+        BuildingType::Shipyard => (400, 200, 300),
+        BuildingType::WarpGate => (2000, 1000, 1800),
+        BuildingType::PlanetaryShield => (500, 250, 600),
+        BuildingType::ResearchLab => (200, 100, 300),
+        BuildingType::Warehouse => (150, 50, 120),
+        BuildingType::OrbitalCannon => (800, 400, 900),
+        BuildingType::SubspaceRelay => (300, 150, 300),
+    };
+    BuildingConfig {
+        cost: Resources::new(iron, deuterium, 0),
+        build_time_secs,
+    }
+}
+
+fn default_ship(ship_type: ShipType) -> ShipConfig {
+    ShipConfig {
+        cost: ship_type.construction_cost(),
+        build_time_secs: ship_type.construction_time(),
+        stats: ship_type.base_stats(),
+    }
+}
+
+/// Scale a level-1 value by `factor` (fixed point, 150 = 1.5x) per additional level
+pub fn scale_by_level(base: u64, factor: u64, level: u32) -> u64 {
+    let mut value = base as u128;
+    for _ in 1..level {
+        value = value * factor as u128 / 100;
+    }
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::IRON_BASE_RATE;
+    use crate::governance::encode_parameter_value;
+
+    #[test]
+    fn test_scheduled_parameter_change() {
+        let mut config = GameConfig::default();
+        assert!(config.is_valid());
+        assert_eq!(config.iron_base_rate, IRON_BASE_RATE);
+        assert_eq!(config.ship_cost(ShipType::Cruiser), ShipType::Cruiser.construction_cost());
+        assert_eq!(config.research_cost(Technology::IonDrives, 2), Resources::new(3000, 1600, 60));
+
+        let later = Timestamp::from(2_000);
+        let sooner = Timestamp::from(1_000);
+        assert!(config.schedule("IRON_BASE_RATE", &encode_parameter_value(14), later));
+        assert!(config.schedule("IRON_BASE_RATE", &encode_parameter_value(12), sooner));
+        assert!(!config.schedule("UNKNOWN", &encode_parameter_value(1), sooner));
+        assert!(!config.schedule("IRON_BASE_RATE", &encode_parameter_value(1_000), sooner));
+
+        // Nothing changes before the effective time
+        config.apply_due(999);
+        assert_eq!(config.iron_base_rate, IRON_BASE_RATE);
+        assert_eq!(config.next_due(1_500), Some(1_000));
+
+        config.apply_due(1_500);
+        assert_eq!((config.iron_base_rate, config.version), (12, 1));
+        config.apply_due(2_000);
+        assert_eq!((config.iron_base_rate, config.version), (14, 2));
+        assert!(config.pending.is_empty());
+    }
+
+    #[test]
+    fn test_trade_fee() {
+        let mut config = GameConfig::default();
+        config.trade_fee_percentage = 0;
+        assert!(config.trade_fee(&Resources::new(1_000, 50, 7)).is_zero());
+
+        config.trade_fee_percentage = 3;
+        assert_eq!(config.trade_fee(&Resources::new(1_000, 50, 7)), Resources::new(30, 1, 0));
+        assert_eq!(config.trade_fee(&Resources::new(u128::MAX, 0, 0)).iron.0, u128::MAX / 100 * 3 + 1);
+    }
+}
@@ -1,6 +1,8 @@
 //! Game constants for Linera Dominion
 //!
 //! These values are the base parameters that can be modified by the Senate Chain.
+//! Costs, rates and stats are read from [`crate::config::GameConfig`] at
+//! runtime; the constants here are its defaults.
 
 // ========== Resource Production Rates (per hour) ==========
 
@@ -19,33 +21,28 @@ pub const CRYSTALS_BASE_RATE: u64 = 1; // Actually 0.1/hr, adjusted with precisi
 /// Crystal production scaling factor (120 = 1.2x per level)
 pub const CRYSTALS_SCALING_FACTOR: u64 = 120;
 
-// ========== Building Costs (Iron / Deuterium) ==========
+// ========== Building Costs ==========
 
-/// Base cost for Miner Drone
-pub const MINER_DRONE_COST_IRON: u128 = 50;
-pub const MINER_DRONE_COST_DEUT: u128 = 0;
+/// Building cost scaling factor per level (150 = 1.5x per level)
+pub const BUILDING_COST_SCALING_FACTOR: u64 = 150;
 
-/// Base cost for Gas Siphon
-pub const GAS_SIPHON_COST_IRON: u128 = 100;
-pub const GAS_SIPHON_COST_DEUT: u128 = 20;
+/// Share of the unspent construction cost refunded on cancellation (percentage)
+pub const CONSTRUCTION_CANCEL_REFUND_PERCENTAGE: u64 = 90;
 
-/// Base cost for Chronos Collider
-pub const CHRONOS_COLLIDER_COST_IRON: u128 = 500;
-pub const CHRONOS_COLLIDER_COST_DEUT: u128 = 500;
+/// Share of the unspent research cost refunded on cancellation (percentage)
+pub const RESEARCH_CANCEL_REFUND_PERCENTAGE: u64 = 90;
 
-/// Base cost for Shipyard
-pub const SHIPYARD_COST_IRON: u128 = 200;
-pub const SHIPYARD_COST_DEUT: u128 = 100;
-
-/// Base cost for Warp Gate
-pub const WARP_GATE_COST_IRON: u128 = 1000;
-pub const WARP_GATE_COST_DEUT: u128 = 1000;
+/// Share of the cumulative investment salvaged when demolishing (percentage)
+pub const DEMOLITION_SALVAGE_PERCENTAGE: u64 = 25;
 
 // ========== Capacity and Limits ==========
 
 /// Maximum buildings per player base
 pub const MAX_BUILDINGS_PER_BASE: usize = 50;
 
+/// Maximum level a building can be upgraded to
+pub const MAX_BUILDING_LEVEL: u32 = 30;
+
 /// Maximum fleets per player
 pub const MAX_FLEETS_PER_PLAYER: usize = 20;
 
@@ -69,15 +66,27 @@ pub const RANGE_PER_WARP_GATE_LEVEL: u32 = 1;
 /// Base construction time multiplier
 pub const CONSTRUCTION_TIME_MULTIPLIER: u64 = 1;
 
+/// Construction time scaling factor per level (130 = 1.3x per level)
+pub const CONSTRUCTION_TIME_SCALING_FACTOR: u64 = 130;
+
 /// Research time multiplier per tech level
 pub const RESEARCH_TIME_MULTIPLIER: u64 = 3600; // 1 hour base
 
+/// Research speedup per ResearchLab level beyond the first (percentage)
+pub const RESEARCH_LAB_SPEEDUP_PERCENTAGE: u64 = 10;
+
 /// Fleet movement speed base (distance units per second)
 pub const BASE_MOVEMENT_SPEED: u64 = 10;
 
+/// Distance along each axis at which a fleet can scan another
+pub const SCAN_RANGE: i64 = 10;
+
 /// Trade offer default expiration time
 pub const TRADE_OFFER_EXPIRATION: u64 = 86400; // 24 hours
 
+/// Alliance and peace proposal expiration time
+pub const DIPLOMACY_PROPOSAL_EXPIRATION: u64 = 86400; // 24 hours
+
 /// Battle turn timeout
 pub const BATTLE_TURN_TIMEOUT: u64 = 60; // 1 minute
 
@@ -95,6 +104,9 @@ pub const STAKE_DECAY_RATE_PER_HOUR: u64 = 1; // 1% per hour
 /// Minimum stake to claim a planet
 pub const MINIMUM_PLANET_STAKE: u128 = 1000;
 
+/// Planets checked for stake decay by one sweep operation
+pub const STAKE_DECAY_BATCH_SIZE: usize = 50;
+
 /// Region entry fee (energy/crystals)
 pub const REGION_ENTRY_FEE: u128 = 1;
 
@@ -107,6 +119,32 @@ pub const WAR_BOND_DRAIN_RATE: u128 = 1;
 /// Trade fee percentage (0 = no fee)
 pub const TRADE_FEE_PERCENTAGE: u64 = 0;
 
+// ========== Technology Effects (percentage per level) ==========
+
+/// AdvancedMining production bonus per level
+pub const ADVANCED_MINING_BONUS_PER_LEVEL: u32 = 5;
+
+/// IonDrives fleet speed bonus per level
+pub const ION_DRIVES_SPEED_BONUS_PER_LEVEL: u32 = 10;
+
+/// ExpandedCargoBays cargo capacity bonus per level
+pub const CARGO_BAYS_BONUS_PER_LEVEL: u32 = 10;
+
+/// ReinforcedHulls ship health bonus per level
+pub const REINFORCED_HULLS_BONUS_PER_LEVEL: u32 = 10;
+
+/// PlasmaWeapons attack bonus per level
+pub const PLASMA_WEAPONS_BONUS_PER_LEVEL: u32 = 10;
+
+/// ShieldHarmonics defense bonus per level
+pub const SHIELD_HARMONICS_BONUS_PER_LEVEL: u32 = 10;
+
+/// NanoConstruction construction time reduction per level
+pub const NANO_CONSTRUCTION_REDUCTION_PER_LEVEL: u32 = 5;
+
+/// Maximum construction time reduction from NanoConstruction
+pub const MAX_CONSTRUCTION_TIME_REDUCTION: u32 = 50;
+
 // ========== Combat Parameters ==========
 
 /// Base damage variance (percentage)
@@ -127,6 +165,18 @@ pub const EXPERIENCE_PER_KILL: u32 = 50;
 /// Debris recovery percentage
 pub const DEBRIS_RECOVERY_PERCENTAGE: u32 = 30;
 
+/// Maximum rounds of an invasion engagement
+pub const INVASION_MAX_ROUNDS: u32 = 6;
+
+/// Time the defender has to commit fleets to an invasion
+pub const INVASION_DEFENSE_WINDOW: u64 = 3600; // 1 hour
+
+/// Share of the defender's resources a victorious invasion can carry off (percentage)
+pub const INVASION_LOOT_PERCENTAGE: u64 = 50;
+
+/// Planetary Shield hit points per building level
+pub const PLANETARY_SHIELD_HP_PER_LEVEL: u64 = 2000;
+
 // ========== Governance Parameters ==========
 
 /// Voting duration for proposals
@@ -138,6 +188,12 @@ pub const QUORUM_PERCENTAGE: u64 = 10;
 /// Approval threshold percentage
 pub const APPROVAL_THRESHOLD: u64 = 51;
 
+/// Crystals a player must have staked in the Senate to propose a change
+pub const SENATE_PROPOSAL_STAKE: u64 = 100;
+
+/// Delay between a Senate vote passing and the change taking effect
+pub const PARAMETER_ACTIVATION_DELAY: u64 = 86400; // 24 hours
+
 /// Alliance minimum members
 pub const ALLIANCE_MIN_MEMBERS: usize = 3;
 
@@ -36,6 +36,18 @@ impl Coordinate {
         ((self.x - other.x).abs() + (self.y - other.y).abs()) as u64
     }
 
+    /// Point reached after covering `elapsed` out of `total` of the straight line to `other`
+    pub fn interpolate(&self, other: &Coordinate, elapsed: u64, total: u64) -> Coordinate {
+        if total == 0 || elapsed >= total {
+            return *other;
+        }
+        let lerp = |from: i64, to: i64| {
+            let offset = (to as i128 - from as i128) * elapsed as i128 / total as i128;
+            from + offset as i64
+        };
+        Coordinate::new(lerp(self.x, other.x), lerp(self.y, other.y))
+    }
+
     /// Get the sector this coordinate belongs to
     pub fn to_sector(&self, sector_size: i64) -> SectorCoordinate {
         SectorCoordinate {
@@ -274,4 +286,13 @@ mod tests {
         let sector = coord.to_sector(100);
         assert_eq!(sector, SectorCoordinate::new(-2, -3));
     }
+
+    #[test]
+    fn test_interpolate() {
+        let a = Coordinate::new(0, 100);
+        let b = Coordinate::new(-40, 0);
+        assert_eq!(a.interpolate(&b, 0, 4), a);
+        assert_eq!(a.interpolate(&b, 1, 4), Coordinate::new(-10, 75));
+        assert_eq!(a.interpolate(&b, 5, 4), b);
+    }
 }
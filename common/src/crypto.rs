@@ -2,10 +2,13 @@
 //!
 //! Implements commit-reveal scheme for Fog of War and other crypto operations.
 
+use linera_sdk::linera_base_types::ChainId;
 use sha3::{Sha3_256, Digest};
 use serde::{Deserialize, Serialize};
 
-use crate::types::{CommitHash, Salt};
+use crate::constants::DEFAULT_UNIVERSE_SEED;
+use crate::coordinates::SectorCoordinate;
+use crate::types::{CommitHash, PlanetType, Salt};
 use crate::units::Fleet;
 
 /// Generate a random salt (in production, use proper randomness)
@@ -41,6 +44,29 @@ pub fn verify_fleet_reveal(fleet: &Fleet, salt: &Salt, commitment: &CommitHash)
     computed == *commitment
 }
 
+/// Create the commitment a Region Chain sees for a fleet's composition
+/// CompositionHash = SHA3_256(FleetId || ShipCounts || Salt)
+pub fn commit_ship_counts(fleet_id: u64, ship_counts: &[u32], salt: &Salt) -> CommitHash {
+    let composition_bytes = bcs::to_bytes(&(fleet_id, ship_counts)).unwrap_or_default();
+    
+    let mut hasher = Sha3_256::new();
+    hasher.update(&composition_bytes);
+    hasher.update(salt);
+    
+    let result = hasher.finalize();
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&result);
+    hash
+}
+
+/// Verify a revealed composition against its hex commitment, with a hex salt
+pub fn verify_ship_counts(fleet_id: u64, ship_counts: &[u32], salt: &str, commitment: &str) -> bool {
+    let Some(salt) = hex::decode(salt).ok().and_then(|bytes| Salt::try_from(bytes).ok()) else {
+        return false;
+    };
+    hex::encode(commit_ship_counts(fleet_id, ship_counts, &salt)) == commitment
+}
+
 /// Generate a deterministic hash for procedural generation
 pub fn procedural_hash(seed: &[u8; 32], x: i64, y: i64, purpose: &str) -> [u8; 32] {
     let mut hasher = Sha3_256::new();
@@ -110,6 +136,53 @@ pub fn has_planet(seed: &[u8; 32], x: i64, y: i64) -> bool {
     hash[0] < 38 // 38/256 ≈ 15%
 }
 
+/// Expand the numeric universe seed of the application parameters
+pub fn universe_seed_bytes(seed: u64) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(DEFAULT_UNIVERSE_SEED);
+    hasher.update(seed.to_le_bytes());
+    
+    let result = hasher.finalize();
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&result);
+    hash
+}
+
+/// A planet placed by the procedural generator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeededPlanet {
+    /// Offset of the planet from the sector's corner, row by row
+    pub id: u64,
+    pub x: i64,
+    pub y: i64,
+    pub planet_type: PlanetType,
+    pub name: String,
+}
+
+/// Every planet of a sector, in id order
+///
+/// Region Chains seed their planets with this when instantiated, and clients
+/// can call it to preview any sector without querying its chain.
+pub fn generate_sector(seed: &[u8; 32], sector: SectorCoordinate, sector_size: i64) -> Vec<SeededPlanet> {
+    let (origin_x, origin_y) = (sector.x * sector_size, sector.y * sector_size);
+    let mut planets = Vec::new();
+    for dy in 0..sector_size {
+        for dx in 0..sector_size {
+            let (x, y) = (origin_x + dx, origin_y + dy);
+            if has_planet(seed, x, y) {
+                planets.push(SeededPlanet {
+                    id: (dy * sector_size + dx) as u64,
+                    x,
+                    y,
+                    planet_type: generate_planet_type(seed, x, y),
+                    name: generate_planet_name(seed, x, y),
+                });
+            }
+        }
+    }
+    planets
+}
+
 /// Commitment structure for secure transactions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Commitment<T> {
@@ -163,25 +236,47 @@ pub fn message_hash(msg_id: u64, chain_id: &[u8]) -> [u8; 32] {
     hash
 }
 
+/// Evidence that a battle between two chains took place
+/// Evidence = SHA3_256(BattleId || AttackerChain || DefenderChain || StartTime)
+pub fn battle_evidence(
+    battle_id: u64,
+    attacker_chain: &ChainId,
+    defender_chain: &ChainId,
+    started_micros: u64,
+) -> CommitHash {
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"LINERA_DOMINION_BATTLE_V1");
+    hasher.update(battle_id.to_le_bytes());
+    hasher.update(bcs::to_bytes(attacker_chain).unwrap_or_default());
+    hasher.update(bcs::to_bytes(defender_chain).unwrap_or_default());
+    hasher.update(started_micros.to_le_bytes());
+    
+    let result = hasher.finalize();
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&result);
+    hash
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::units::{Ship, ShipType};
     use crate::coordinates::Coordinate;
-    use linera_sdk::linera_base_types::{AccountOwner, ChainId, Timestamp};
+    use linera_sdk::linera_base_types::{AccountOwner, ChainId, CryptoHash, Timestamp};
 
     #[test]
     fn test_commit_reveal() {
         // Create a simple fleet for testing
         let fleet = Fleet {
             id: 1,
-            owner: AccountOwner::User(linera_sdk::linera_base_types::CryptoHash::test_hash("test")),
-            owner_chain: ChainId::root(0),
+            owner: AccountOwner::Address32(CryptoHash::from([1u8; 32])),
+            owner_chain: ChainId(CryptoHash::from([2u8; 32])),
             ships: vec![Ship::new(ShipType::Scout)],
             cargo: crate::resources::Resources::zero(),
             position: Coordinate::new(0, 0),
             state: crate::types::FleetState::Idle,
             last_update: Timestamp::from(0),
+            modifiers: crate::research::TechModifiers::default(),
         };
         
         let salt = generate_salt(b"test_seed");
@@ -195,6 +290,18 @@ mod tests {
         assert!(!verify_fleet_reveal(&fleet, &wrong_salt, &commitment));
     }
 
+    #[test]
+    fn test_ship_counts_reveal() {
+        let salt = generate_salt(b"test_seed");
+        let commitment = hex::encode(commit_ship_counts(7, &[3, 0, 1], &salt));
+        let salt = hex::encode(salt);
+
+        assert!(verify_ship_counts(7, &[3, 0, 1], &salt, &commitment));
+        assert!(!verify_ship_counts(7, &[3, 0, 2], &salt, &commitment));
+        assert!(!verify_ship_counts(8, &[3, 0, 1], &salt, &commitment));
+        assert!(!verify_ship_counts(7, &[3, 0, 1], "not hex", &commitment));
+    }
+
     #[test]
     fn test_procedural_generation() {
         let seed = crate::constants::DEFAULT_UNIVERSE_SEED;
@@ -208,4 +315,21 @@ mod tests {
         let name3 = generate_planet_name(&seed, 11, 20);
         assert_ne!(name1, name3);
     }
+
+    #[test]
+    fn test_sector_generation() {
+        let seed = universe_seed_bytes(12345);
+        let sector = SectorCoordinate::new(-1, 2);
+        let planets = generate_sector(&seed, sector, 20);
+        assert_eq!(planets, generate_sector(&seed, sector, 20));
+        assert!(!planets.is_empty());
+        
+        for planet in &planets {
+            assert!(Coordinate::new(planet.x, planet.y).in_sector(&sector, 20));
+            assert!(has_planet(&seed, planet.x, planet.y));
+            assert_eq!(planet.id, ((planet.y - 40) * 20 + planet.x + 20) as u64);
+        }
+        assert!(planets.windows(2).all(|pair| pair[0].id < pair[1].id));
+        assert_ne!(planets, generate_sector(&universe_seed_bytes(1), sector, 20));
+    }
 }
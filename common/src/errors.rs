@@ -156,6 +156,15 @@ pub enum BattleError {
 /// Errors that can occur in Alliance Chain operations  
 #[derive(Debug, Clone, Serialize, Deserialize, Error)]
 pub enum AllianceError {
+    #[error("No alliance has been founded on this chain")]
+    AllianceNotFounded,
+    
+    #[error("An alliance has already been founded on this chain")]
+    AlreadyFounded,
+    
+    #[error("Not authorized")]
+    NotAuthorized,
+    
     #[error("Already a member")]
     AlreadyMember,
     
@@ -188,6 +197,40 @@ pub enum AllianceError {
     
     #[error("Maximum member count exceeded")]
     MaximumMembersExceeded,
+    
+    #[error("Treaty not found: {0}")]
+    TreatyNotFound(u64),
+    
+    #[error("Invalid violation evidence")]
+    InvalidEvidence,
+    
+    #[error("Storage error")]
+    StorageError,
+}
+
+/// Errors that can occur in Senate Chain operations
+#[derive(Debug, Clone, Serialize, Deserialize, Error)]
+pub enum SenateError {
+    #[error("Value must be between {min} and {max}")]
+    OutOfBounds { min: u64, max: u64 },
+    
+    #[error("A single vote can change this parameter by at most {max_step}")]
+    ChangeTooLarge { max_step: u64 },
+    
+    #[error("Proposed value equals the current value")]
+    NoChange,
+    
+    #[error("Proposal not found: {0}")]
+    ProposalNotFound(u64),
+    
+    #[error("Already voted on this proposal")]
+    AlreadyVoted,
+    
+    #[error("Not authorized")]
+    NotAuthorized,
+    
+    #[error("Storage error")]
+    StorageError,
 }
 
 /// Combined error type for all game errors
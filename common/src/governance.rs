@@ -0,0 +1,149 @@
+//! Senate governance of universe parameters
+//!
+//! Lists the parameters the Senate Chain can change and the bounds each change
+//! must respect, so that a single vote cannot break the economy.
+
+use async_graphql::{Enum, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    CRYSTALS_BASE_RATE, DEUTERIUM_BASE_RATE, IRON_BASE_RATE, REGION_ENTRY_FEE,
+    STAKE_DECAY_RATE_PER_HOUR, TRADE_FEE_PERCENTAGE, TRADE_OFFER_EXPIRATION, WAR_BOND_COST,
+};
+use crate::errors::SenateError;
+
+/// A universe parameter the Senate can change
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Enum)]
+pub enum GovernedParameter {
+    IronBaseRate,
+    DeuteriumBaseRate,
+    CrystalsBaseRate,
+    WarBondCost,
+    TradeFeePercentage,
+    RegionEntryFee,
+    StakeDecayRatePerHour,
+    TradeOfferExpiration,
+}
+
+/// Limits on the values of a governed parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, SimpleObject)]
+pub struct ParameterBounds {
+    pub min: u64,
+    pub max: u64,
+    /// Largest change a single vote can make, as a percentage of the current value
+    pub max_step_percentage: u64,
+}
+
+impl GovernedParameter {
+    pub fn all() -> &'static [GovernedParameter] {
+        &[
+            GovernedParameter::IronBaseRate,
+            GovernedParameter::DeuteriumBaseRate,
+            GovernedParameter::CrystalsBaseRate,
+            GovernedParameter::WarBondCost,
+            GovernedParameter::TradeFeePercentage,
+            GovernedParameter::RegionEntryFee,
+            GovernedParameter::StakeDecayRatePerHour,
+            GovernedParameter::TradeOfferExpiration,
+        ]
+    }
+
+    /// Name of the parameter in `ParameterUpdate` messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            GovernedParameter::IronBaseRate => "IRON_BASE_RATE",
+            GovernedParameter::DeuteriumBaseRate => "DEUTERIUM_BASE_RATE",
+            GovernedParameter::CrystalsBaseRate => "CRYSTALS_BASE_RATE",
+            GovernedParameter::WarBondCost => "WAR_BOND_COST",
+            GovernedParameter::TradeFeePercentage => "TRADE_FEE_PERCENTAGE",
+            GovernedParameter::RegionEntryFee => "REGION_ENTRY_FEE",
+            GovernedParameter::StakeDecayRatePerHour => "STAKE_DECAY_RATE_PER_HOUR",
+            GovernedParameter::TradeOfferExpiration => "TRADE_OFFER_EXPIRATION",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|parameter| parameter.name() == name)
+    }
+
+    /// Value before any Senate vote
+    pub fn default_value(&self) -> u64 {
+        match self {
+            GovernedParameter::IronBaseRate => IRON_BASE_RATE,
+            GovernedParameter::DeuteriumBaseRate => DEUTERIUM_BASE_RATE,
+            GovernedParameter::CrystalsBaseRate => CRYSTALS_BASE_RATE,
+            GovernedParameter::WarBondCost => WAR_BOND_COST as u64,
+            GovernedParameter::TradeFeePercentage => TRADE_FEE_PERCENTAGE,
+            GovernedParameter::RegionEntryFee => REGION_ENTRY_FEE as u64,
+            GovernedParameter::StakeDecayRatePerHour => STAKE_DECAY_RATE_PER_HOUR,
+            GovernedParameter::TradeOfferExpiration => TRADE_OFFER_EXPIRATION,
+        }
+    }
+
+    pub fn bounds(&self) -> ParameterBounds {
+        let (min, max, max_step_percentage) = match self {
+            GovernedParameter::IronBaseRate => (1, 100, 25),
+            GovernedParameter::DeuteriumBaseRate => (1, 50, 25),
+            GovernedParameter::CrystalsBaseRate => (1, 10, 50),
+            GovernedParameter::WarBondCost => (10, 1000, 50),
+            GovernedParameter::TradeFeePercentage => (0, 10, 50),
+            GovernedParameter::RegionEntryFee => (0, 100, 50),
+            GovernedParameter::StakeDecayRatePerHour => (0, 10, 50),
+            GovernedParameter::TradeOfferExpiration => (3600, 604800, 50), // 1 hour to 1 week
+        };
+        ParameterBounds { min, max, max_step_percentage }
+    }
+
+    /// Check that one vote may move the parameter from `current` to `proposed`
+    ///
+    /// The step allowed is a share of the current value, and at least one unit
+    /// so that parameters at zero can still move.
+    pub fn check_change(&self, current: u64, proposed: u64) -> Result<(), SenateError> {
+        let bounds = self.bounds();
+        if proposed < bounds.min || proposed > bounds.max {
+            return Err(SenateError::OutOfBounds { min: bounds.min, max: bounds.max });
+        }
+        if proposed == current {
+            return Err(SenateError::NoChange);
+        }
+        let max_step = (current as u128 * bounds.max_step_percentage as u128 / 100).max(1) as u64;
+        if proposed.abs_diff(current) > max_step {
+            return Err(SenateError::ChangeTooLarge { max_step });
+        }
+        Ok(())
+    }
+}
+
+/// Encode a parameter value for a `ParameterUpdate` message
+pub fn encode_parameter_value(value: u64) -> Vec<u8> {
+    bcs::to_bytes(&value).unwrap_or_default()
+}
+
+/// Decode a parameter value from a `ParameterUpdate` message
+pub fn decode_parameter_value(bytes: &[u8]) -> Option<u64> {
+    bcs::from_bytes(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameter_bounds() {
+        let parameter = GovernedParameter::IronBaseRate;
+        assert_eq!(parameter.default_value(), IRON_BASE_RATE);
+        assert_eq!(GovernedParameter::from_name(parameter.name()), Some(parameter));
+
+        // 25% of 10 rounds down to 2
+        assert!(parameter.check_change(10, 12).is_ok());
+        assert!(matches!(parameter.check_change(10, 13), Err(SenateError::ChangeTooLarge { max_step: 2 })));
+        assert!(matches!(parameter.check_change(10, 10), Err(SenateError::NoChange)));
+        assert!(matches!(parameter.check_change(1, 0), Err(SenateError::OutOfBounds { .. })));
+
+        // A parameter at zero can still move by one
+        assert!(GovernedParameter::TradeFeePercentage.check_change(0, 1).is_ok());
+
+        let value = 12_345;
+        assert_eq!(decode_parameter_value(&encode_parameter_value(value)), Some(value));
+    }
+}
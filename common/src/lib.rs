@@ -6,6 +6,7 @@
 //! - Region Chains (spatial sharding layer)
 //! - Battle Chains (ephemeral combat instances)
 //! - Alliance Chains (DAO governance)
+//! - Senate Chain (universe parameter governance)
 
 pub mod types;
 pub mod resources;
@@ -15,6 +16,9 @@ pub mod messages;
 pub mod errors;
 pub mod constants;
 pub mod crypto;
+pub mod research;
+pub mod governance;
+pub mod config;
 
 pub use types::*;
 pub use resources::*;
@@ -24,3 +28,6 @@ pub use messages::*;
 pub use errors::*;
 pub use constants::*;
 pub use crypto::*;
+pub use research::*;
+pub use governance::*;
+pub use config::*;
@@ -90,9 +90,17 @@ pub enum DominionMessage {
         memo: Option<String>,
     },
     
-    /// Stake return from a planet
+    /// A planet's stake was accepted; `stake` is the planet's whole stake
+    PlanetStaked {
+        planet_id: PlanetId,
+        position: Coordinate,
+        stake: Resources,
+    },
+    
+    /// Stake return from a planet the player does not (or no longer) hold
     StakeReturn {
         planet_id: PlanetId,
+        position: Coordinate,
         resources: Resources,
     },
 }
@@ -235,13 +243,28 @@ pub enum AllianceMessage {
         player_chain: ChainId,
     },
     
+    /// Member leaving the alliance
+    Leave {
+        player: PlayerId,
+        player_chain: ChainId,
+    },
+    
     /// Contribution to alliance treasury
     TreasuryDeposit {
         contributor: PlayerId,
         resources: Resources,
     },
     
+    /// Proposal submitted by a member
+    SubmitProposal {
+        proposer: PlayerId,
+        proposal: crate::types::AllianceProposalKind,
+    },
+    
     /// Vote on a proposal
+    ///
+    /// The Alliance Chain counts the voting power it has on record for the
+    /// voter, not the claimed `voting_power`.
     Vote {
         proposal_id: u64,
         voter: PlayerId,
@@ -251,9 +274,13 @@ pub enum AllianceMessage {
     
     /// Treaty proposal
     TreatyProposal {
+        /// The proposing alliance's id for the treaty
+        treaty_id: u64,
         treaty_type: crate::types::TreatyType,
         other_alliance: ChainId,
         penalty_crystals: u128,
+        /// How long the treaty lasts once signed; `None` for no expiry
+        duration_secs: Option<u64>,
     },
     
     /// Member violation (auto-slashing)
@@ -358,8 +385,8 @@ pub struct BattleFinalState {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ViolationType {
-    /// Attacked treaty partner
-    TreatyViolation { treaty_id: u64, victim_alliance: ChainId },
+    /// Attacked a member of an alliance bound by a non-aggression pact
+    TreatyViolation { violator_chain: ChainId, victim_alliance: ChainId },
     /// Failed to pay alliance dues
     MissedContribution,
     /// Used alliance assets without authorization
@@ -0,0 +1,234 @@
+//! Tech tree for Linera Dominion
+//!
+//! Declares the prerequisite graph, research costs and research durations
+//! shared by every chain that needs to reason about technology levels.
+
+use async_graphql::{InputObject, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    ADVANCED_MINING_BONUS_PER_LEVEL, CARGO_BAYS_BONUS_PER_LEVEL, ION_DRIVES_SPEED_BONUS_PER_LEVEL,
+    MAX_CONSTRUCTION_TIME_REDUCTION, NANO_CONSTRUCTION_REDUCTION_PER_LEVEL,
+    PLASMA_WEAPONS_BONUS_PER_LEVEL, REINFORCED_HULLS_BONUS_PER_LEVEL,
+    RESEARCH_LAB_SPEEDUP_PERCENTAGE, RESEARCH_TIME_MULTIPLIER, SHIELD_HARMONICS_BONUS_PER_LEVEL,
+};
+use crate::resources::Resources;
+use crate::types::Technology;
+
+/// A technology level that must be reached before another can be researched
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TechRequirement {
+    pub technology: Technology,
+    pub level: u32,
+}
+
+impl Technology {
+    /// Technologies that must be researched before this one can be started
+    pub fn prerequisites(&self) -> &'static [TechRequirement] {
+        match self {
+            Technology::AdvancedMining
+            | Technology::ReinforcedHulls
+            | Technology::IonDrives
+            | Technology::ExpandedCargoBays
+            | Technology::LongRangeSensors => &[],
+            Technology::PlasmaWeapons => &[
+                TechRequirement { technology: Technology::ReinforcedHulls, level: 2 },
+            ],
+            Technology::ShieldHarmonics => &[
+                TechRequirement { technology: Technology::ReinforcedHulls, level: 3 },
+            ],
+            Technology::NanoConstruction => &[
+                TechRequirement { technology: Technology::AdvancedMining, level: 3 },
+            ],
+            Technology::WarpTechnology => &[
+                TechRequirement { technology: Technology::IonDrives, level: 3 },
+            ],
+            Technology::StealthSystems => &[
+                TechRequirement { technology: Technology::LongRangeSensors, level: 3 },
+                TechRequirement { technology: Technology::IonDrives, level: 2 },
+            ],
+            Technology::TemporalMechanics => &[
+                TechRequirement { technology: Technology::WarpTechnology, level: 2 },
+                TechRequirement { technology: Technology::ShieldHarmonics, level: 2 },
+            ],
+        }
+    }
+
+    /// Default research cost for level 1
+    pub fn base_research_cost(&self) -> Resources {
+        match self {
+            Technology::AdvancedMining => Resources::new(500, 200, 10),
+            Technology::ReinforcedHulls => Resources::new(1000, 500, 20),
+            Technology::PlasmaWeapons => Resources::new(2000, 1000, 50),
+            Technology::IonDrives => Resources::new(1500, 800, 30),
+            Technology::WarpTechnology => Resources::new(5000, 3000, 200),
+            Technology::ShieldHarmonics => Resources::new(2500, 1500, 100),
+            Technology::NanoConstruction => Resources::new(3000, 2000, 150),
+            Technology::ExpandedCargoBays => Resources::new(800, 400, 10),
+            Technology::LongRangeSensors => Resources::new(1200, 600, 25),
+            Technology::StealthSystems => Resources::new(4000, 2500, 300),
+            Technology::TemporalMechanics => Resources::new(10000, 8000, 1000),
+        }
+    }
+
+    /// Research time for level 1 without any lab bonus, in hours
+    pub fn base_research_hours(&self) -> u64 {
+        match self {
+            Technology::AdvancedMining => 1,
+            Technology::ReinforcedHulls => 2,
+            Technology::PlasmaWeapons => 4,
+            Technology::IonDrives => 3,
+            Technology::WarpTechnology => 12,
+            Technology::ShieldHarmonics => 6,
+            Technology::NanoConstruction => 8,
+            Technology::ExpandedCargoBays => 1,
+            Technology::LongRangeSensors => 2,
+            Technology::StealthSystems => 10,
+            Technology::TemporalMechanics => 24,
+        }
+    }
+}
+
+/// Research time in seconds for reaching `level` with a ResearchLab of `lab_level`
+///
+/// Every lab level beyond the first speeds research up by
+/// `RESEARCH_LAB_SPEEDUP_PERCENTAGE`.
+pub fn research_time(technology: Technology, level: u32, lab_level: u32) -> u64 {
+    let base = technology.base_research_hours() * level as u64 * RESEARCH_TIME_MULTIPLIER;
+    let speedup = 100 + RESEARCH_LAB_SPEEDUP_PERCENTAGE * lab_level.saturating_sub(1) as u64;
+    base * 100 / speedup
+}
+
+/// First prerequisite of `technology` not met by the levels reported by `level_of`
+pub fn missing_prerequisite(
+    technology: Technology,
+    level_of: impl Fn(Technology) -> u32,
+) -> Option<TechRequirement> {
+    technology
+        .prerequisites()
+        .iter()
+        .find(|requirement| level_of(requirement.technology) < requirement.level)
+        .copied()
+}
+
+/// Stat modifiers granted by researched technologies
+///
+/// Every field is a percentage of the base value (100 = unmodified). User
+/// chains compute them from their research and ship them with fleets, so
+/// Region and Battle chains apply exactly the same effects.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, SimpleObject, InputObject)]
+#[graphql(input_name = "TechModifiersInput")]
+pub struct TechModifiers {
+    /// Resource production (AdvancedMining)
+    pub production: u32,
+    /// Fleet speed (IonDrives)
+    pub speed: u32,
+    /// Cargo capacity (ExpandedCargoBays)
+    pub cargo: u32,
+    /// Ship health (ReinforcedHulls)
+    pub hull: u32,
+    /// Ship attack (PlasmaWeapons)
+    pub attack: u32,
+    /// Ship defense (ShieldHarmonics)
+    pub defense: u32,
+    /// Construction time (NanoConstruction)
+    pub construction_time: u32,
+}
+
+impl Default for TechModifiers {
+    fn default() -> Self {
+        Self {
+            production: 100,
+            speed: 100,
+            cargo: 100,
+            hull: 100,
+            attack: 100,
+            defense: 100,
+            construction_time: 100,
+        }
+    }
+}
+
+impl TechModifiers {
+    /// Modifiers granted by the technology levels reported by `level_of`
+    pub fn from_levels(level_of: impl Fn(Technology) -> u32) -> Self {
+        let bonus = |technology: Technology, per_level: u32| {
+            100u32.saturating_add(level_of(technology).saturating_mul(per_level))
+        };
+        let reduction = level_of(Technology::NanoConstruction)
+            .saturating_mul(NANO_CONSTRUCTION_REDUCTION_PER_LEVEL)
+            .min(MAX_CONSTRUCTION_TIME_REDUCTION);
+
+        Self {
+            production: bonus(Technology::AdvancedMining, ADVANCED_MINING_BONUS_PER_LEVEL),
+            speed: bonus(Technology::IonDrives, ION_DRIVES_SPEED_BONUS_PER_LEVEL),
+            cargo: bonus(Technology::ExpandedCargoBays, CARGO_BAYS_BONUS_PER_LEVEL),
+            hull: bonus(Technology::ReinforcedHulls, REINFORCED_HULLS_BONUS_PER_LEVEL),
+            attack: bonus(Technology::PlasmaWeapons, PLASMA_WEAPONS_BONUS_PER_LEVEL),
+            defense: bonus(Technology::ShieldHarmonics, SHIELD_HARMONICS_BONUS_PER_LEVEL),
+            construction_time: 100 - reduction,
+        }
+    }
+
+    /// Apply a percentage modifier to a base value
+    pub fn apply(percent: u32, value: u64) -> u64 {
+        let scaled = value as u128 * percent as u128 / 100;
+        u64::try_from(scaled).unwrap_or(u64::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tech_tree_is_acyclic() {
+        // Resolve the tree layer by layer; every technology must become available
+        let mut unlocked: Vec<Technology> = Vec::new();
+        while unlocked.len() < Technology::all().len() {
+            let next: Vec<Technology> = Technology::all()
+                .iter()
+                .filter(|tech| !unlocked.contains(tech))
+                .filter(|tech| {
+                    tech.prerequisites()
+                        .iter()
+                        .all(|requirement| unlocked.contains(&requirement.technology))
+                })
+                .copied()
+                .collect();
+            assert!(!next.is_empty(), "cycle in tech tree");
+            unlocked.extend(next);
+        }
+    }
+
+    #[test]
+    fn test_missing_prerequisite() {
+        let missing = missing_prerequisite(Technology::WarpTechnology, |_| 2);
+        assert_eq!(
+            missing,
+            Some(TechRequirement { technology: Technology::IonDrives, level: 3 })
+        );
+        assert_eq!(missing_prerequisite(Technology::WarpTechnology, |_| 3), None);
+    }
+
+    #[test]
+    fn test_research_lab_speedup() {
+        let base = research_time(Technology::AdvancedMining, 1, 1);
+        assert_eq!(base, RESEARCH_TIME_MULTIPLIER);
+        assert!(research_time(Technology::AdvancedMining, 1, 5) < base);
+    }
+
+    #[test]
+    fn test_tech_modifiers() {
+        assert_eq!(TechModifiers::from_levels(|_| 0), TechModifiers::default());
+
+        let modifiers = TechModifiers::from_levels(|tech| match tech {
+            Technology::IonDrives => 2,
+            Technology::NanoConstruction => 40,
+            _ => 0,
+        });
+        assert_eq!(modifiers.speed, 120);
+        assert_eq!(modifiers.construction_time, 100 - MAX_CONSTRUCTION_TIME_REDUCTION);
+        assert_eq!(TechModifiers::apply(modifiers.speed, 150), 180);
+    }
+}
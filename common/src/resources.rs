@@ -3,35 +3,94 @@
 //! Implements the Time-Energy Standard economic model with lazy evaluation
 //! for resource generation based on delta-time integration.
 
-use async_graphql::{SimpleObject, InputObject};
+use std::fmt;
+
+use async_graphql::{InputObject, InputValueError, InputValueResult, Scalar, ScalarType, SimpleObject, Value};
 use linera_sdk::linera_base_types::Timestamp;
 use serde::{Deserialize, Serialize};
 
+use crate::config::GameConfig;
+use crate::research::TechModifiers;
 use crate::types::BuildingType;
-use crate::constants::{
-    IRON_BASE_RATE, DEUTERIUM_BASE_RATE, CRYSTALS_BASE_RATE,
-    IRON_SCALING_FACTOR, DEUTERIUM_SCALING_FACTOR, CRYSTALS_SCALING_FACTOR,
-    STAKE_DECAY_RATE_PER_HOUR,
-};
+
+/// An amount of a single resource
+///
+/// GraphQL has no 128-bit integers, so amounts are exchanged as decimal
+/// strings; integer literals are accepted as input too.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Quantity(pub u128);
+
+impl Quantity {
+    pub const ZERO: Quantity = Quantity(0);
+
+    pub fn checked_add(self, other: Quantity) -> Option<Quantity> {
+        self.0.checked_add(other.0).map(Quantity)
+    }
+
+    pub fn checked_sub(self, other: Quantity) -> Option<Quantity> {
+        self.0.checked_sub(other.0).map(Quantity)
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl From<u128> for Quantity {
+    fn from(value: u128) -> Self {
+        Quantity(value)
+    }
+}
+
+impl From<u64> for Quantity {
+    fn from(value: u64) -> Self {
+        Quantity(value as u128)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[Scalar]
+impl ScalarType for Quantity {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        let parsed = match &value {
+            Value::String(text) => text.parse().ok(),
+            Value::Number(number) => number.as_u64().map(u128::from),
+            _ => None,
+        };
+        parsed.map(Quantity).ok_or_else(|| InputValueError::expected_type(value))
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
 
 /// The three primary resources in Linera Dominion
-/// Note: We use String for u128 fields because GraphQL doesn't support u128
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+///
+/// The one shape for resource amounts in every state, message and operation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, SimpleObject, InputObject)]
+#[graphql(input_name = "ResourcesInput")]
 pub struct Resources {
     /// Common metal for basic construction
-    pub iron: u128,
+    pub iron: Quantity,
     /// Fuel for ships and advanced structures
-    pub deuterium: u128,
+    pub deuterium: Quantity,
     /// Rare resource for advanced tech and governance voting
-    pub chronos_crystals: u128,
+    pub chronos_crystals: Quantity,
 }
 
 impl Resources {
     pub fn new(iron: u128, deuterium: u128, chronos_crystals: u128) -> Self {
         Self {
-            iron,
-            deuterium,
-            chronos_crystals,
+            iron: Quantity(iron),
+            deuterium: Quantity(deuterium),
+            chronos_crystals: Quantity(chronos_crystals),
         }
     }
 
@@ -39,6 +98,11 @@ impl Resources {
         Self::default()
     }
 
+    /// Only Chronos Crystals
+    pub fn crystals(amount: u128) -> Self {
+        Self::new(0, 0, amount)
+    }
+
     /// Check if we have at least the specified amounts
     pub fn has_at_least(&self, other: &Resources) -> bool {
         self.iron >= other.iron
@@ -46,61 +110,84 @@ impl Resources {
             && self.chronos_crystals >= other.chronos_crystals
     }
 
-    /// Saturating subtraction
-    pub fn saturating_sub(&self, other: &Resources) -> Resources {
-        Resources {
-            iron: self.iron.saturating_sub(other.iron),
-            deuterium: self.deuterium.saturating_sub(other.deuterium),
-            chronos_crystals: self.chronos_crystals.saturating_sub(other.chronos_crystals),
-        }
+    /// Add resources, failing if any amount overflows
+    pub fn checked_add(&self, other: &Resources) -> Result<Resources, ResourceError> {
+        Ok(Resources {
+            iron: self.iron.checked_add(other.iron).ok_or(ResourceError::Overflow)?,
+            deuterium: self.deuterium.checked_add(other.deuterium).ok_or(ResourceError::Overflow)?,
+            chronos_crystals: self.chronos_crystals
+                .checked_add(other.chronos_crystals)
+                .ok_or(ResourceError::Overflow)?,
+        })
     }
 
-    /// Add resources
-    pub fn saturating_add(&self, other: &Resources) -> Resources {
-        Resources {
-            iron: self.iron.saturating_add(other.iron),
-            deuterium: self.deuterium.saturating_add(other.deuterium),
-            chronos_crystals: self.chronos_crystals.saturating_add(other.chronos_crystals),
+    /// Subtract resources, failing if any amount is short
+    pub fn checked_sub(&self, other: &Resources) -> Result<Resources, ResourceError> {
+        if !self.has_at_least(other) {
+            return Err(ResourceError::InsufficientResources {
+                required: *other,
+                available: *self,
+            });
         }
+        Ok(Resources {
+            iron: Quantity(self.iron.0 - other.iron.0),
+            deuterium: Quantity(self.deuterium.0 - other.deuterium.0),
+            chronos_crystals: Quantity(self.chronos_crystals.0 - other.chronos_crystals.0),
+        })
     }
 
-    /// Check if all resources are zero
-    pub fn is_zero(&self) -> bool {
-        self.iron == 0 && self.deuterium == 0 && self.chronos_crystals == 0
+    /// Multiply every amount, failing on overflow
+    pub fn checked_mul(&self, factor: u128) -> Result<Resources, ResourceError> {
+        let scale = |amount: Quantity| amount.0.checked_mul(factor).map(Quantity).ok_or(ResourceError::Overflow);
+        Ok(Resources {
+            iron: scale(self.iron)?,
+            deuterium: scale(self.deuterium)?,
+            chronos_crystals: scale(self.chronos_crystals)?,
+        })
     }
 
-    /// Total value (simplified for voting weight)
-    pub fn total_value(&self) -> u128 {
-        // Chronos crystals are worth more
-        self.iron + self.deuterium * 2 + self.chronos_crystals * 100
+    /// Smaller of each amount
+    pub fn min(&self, other: &Resources) -> Resources {
+        Resources {
+            iron: self.iron.min(other.iron),
+            deuterium: self.deuterium.min(other.deuterium),
+            chronos_crystals: self.chronos_crystals.min(other.chronos_crystals),
+        }
     }
-}
 
-impl std::ops::Add for Resources {
-    type Output = Resources;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        self.saturating_add(&rhs)
+    /// Apply `f` to each amount
+    pub fn map(&self, f: impl Fn(u128) -> u128) -> Resources {
+        Resources::new(f(self.iron.0), f(self.deuterium.0), f(self.chronos_crystals.0))
     }
-}
 
-impl std::ops::Sub for Resources {
-    type Output = Resources;
+    /// Check if all resources are zero
+    pub fn is_zero(&self) -> bool {
+        self.iron.is_zero() && self.deuterium.is_zero() && self.chronos_crystals.is_zero()
+    }
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        self.saturating_sub(&rhs)
+    /// Sum of all amounts
+    pub fn total(&self) -> Option<u128> {
+        self.iron.0
+            .checked_add(self.deuterium.0)?
+            .checked_add(self.chronos_crystals.0)
     }
-}
 
-impl std::ops::AddAssign for Resources {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = self.saturating_add(&rhs);
+    /// Total value (simplified for voting weight)
+    pub fn total_value(&self) -> Option<u128> {
+        // Chronos crystals are worth more
+        self.iron.0
+            .checked_add(self.deuterium.0.checked_mul(2)?)?
+            .checked_add(self.chronos_crystals.0.checked_mul(100)?)
     }
 }
 
-impl std::ops::SubAssign for Resources {
-    fn sub_assign(&mut self, rhs: Self) {
-        *self = self.saturating_sub(&rhs);
+impl fmt::Display for Resources {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} iron, {} deuterium, {} crystals",
+            self.iron, self.deuterium, self.chronos_crystals
+        )
     }
 }
 
@@ -139,62 +226,57 @@ impl ResourceWallet {
 
     /// Calculate current resources using delta-time integration
     /// R_current = R_last + (Rate × (T_now - T_last))
-    pub fn calculate_current(&self, now: Timestamp) -> Resources {
+    pub fn calculate_current(&self, now: Timestamp) -> Result<Resources, ResourceError> {
         let elapsed_micros = now.micros().saturating_sub(self.last_update.micros());
-        let elapsed_seconds = elapsed_micros / 1_000_000;
+        let produced = self
+            .production_rates
+            .accrue(elapsed_micros, &mut ProductionRemainder::default());
 
-        Resources {
-            iron: self.resources.iron
-                .saturating_add(self.production_rates.iron_per_second as u128 * elapsed_seconds as u128),
-            deuterium: self.resources.deuterium
-                .saturating_add(self.production_rates.deuterium_per_second as u128 * elapsed_seconds as u128),
-            chronos_crystals: self.resources.chronos_crystals
-                .saturating_add(self.production_rates.crystals_per_second as u128 * elapsed_seconds as u128),
-        }
+        self.resources.checked_add(&produced)
     }
 
     /// Update the wallet to the current timestamp
-    pub fn update_to_now(&mut self, now: Timestamp) {
-        self.resources = self.calculate_current(now);
+    pub fn update_to_now(&mut self, now: Timestamp) -> Result<(), ResourceError> {
+        self.resources = self.calculate_current(now)?;
         self.last_update = now;
+        Ok(())
     }
 
     /// Debit resources (update first, then subtract)
     pub fn debit(&mut self, amount: &Resources, now: Timestamp) -> Result<(), ResourceError> {
-        self.update_to_now(now);
-        
-        if !self.resources.has_at_least(amount) {
-            return Err(ResourceError::InsufficientResources {
-                required: *amount,
-                available: self.resources,
-            });
-        }
-
-        self.resources -= *amount;
+        self.update_to_now(now)?;
+        self.resources = self.resources.checked_sub(amount)?;
         Ok(())
     }
 
     /// Credit resources
-    pub fn credit(&mut self, amount: &Resources, now: Timestamp) {
-        self.update_to_now(now);
-        self.resources += *amount;
+    pub fn credit(&mut self, amount: &Resources, now: Timestamp) -> Result<(), ResourceError> {
+        self.update_to_now(now)?;
+        self.resources = self.resources.checked_add(amount)?;
+        Ok(())
     }
 }
 
+/// Fixed-point precision of the iron and deuterium per-second rates
+pub const RATE_PRECISION: u64 = 1_000_000;
+
+/// Fixed-point precision of the Chronos Crystal per-second rate
+pub const CRYSTAL_RATE_PRECISION: u64 = 1_000_000_000;
+
 /// Production rates for resource generation
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
 pub struct ProductionRates {
-    /// Iron per second (scaled by 1000 for precision)
+    /// Iron per second (scaled by RATE_PRECISION)
     pub iron_per_second: u64,
-    /// Deuterium per second (scaled by 1000 for precision)
+    /// Deuterium per second (scaled by RATE_PRECISION)
     pub deuterium_per_second: u64,
-    /// Chronos Crystals per second (scaled by 1000000 for precision)
+    /// Chronos Crystals per second (scaled by CRYSTAL_RATE_PRECISION)
     pub crystals_per_second: u64,
 }
 
 impl ProductionRates {
     /// Calculate production rates based on building levels
-    pub fn from_buildings(buildings: &[(BuildingType, u32)]) -> Self {
+    pub fn from_buildings(buildings: &[(BuildingType, u32)], config: &GameConfig) -> Self {
         let mut rates = ProductionRates::default();
 
         for (building_type, level) in buildings {
@@ -202,25 +284,28 @@ impl ProductionRates {
                 BuildingType::MinerDrone => {
                     // Base rate * scaling_factor^level
                     let rate = Self::calculate_rate(
-                        IRON_BASE_RATE,
-                        IRON_SCALING_FACTOR,
+                        config.iron_base_rate,
+                        config.iron_scaling_factor,
                         *level,
+                        RATE_PRECISION,
                     );
                     rates.iron_per_second += rate;
                 }
                 BuildingType::GasSiphon => {
                     let rate = Self::calculate_rate(
-                        DEUTERIUM_BASE_RATE,
-                        DEUTERIUM_SCALING_FACTOR,
+                        config.deuterium_base_rate,
+                        config.deuterium_scaling_factor,
                         *level,
+                        RATE_PRECISION,
                     );
                     rates.deuterium_per_second += rate;
                 }
                 BuildingType::ChronosCollider => {
                     let rate = Self::calculate_rate(
-                        CRYSTALS_BASE_RATE,
-                        CRYSTALS_SCALING_FACTOR,
+                        config.crystals_base_rate,
+                        config.crystals_scaling_factor,
                         *level,
+                        CRYSTAL_RATE_PRECISION,
                     );
                     rates.crystals_per_second += rate;
                 }
@@ -231,15 +316,24 @@ impl ProductionRates {
         rates
     }
 
+    /// Scale all rates by a percentage (e.g. a research production modifier)
+    pub fn with_modifier(self, percent: u32) -> Self {
+        Self {
+            iron_per_second: TechModifiers::apply(percent, self.iron_per_second),
+            deuterium_per_second: TechModifiers::apply(percent, self.deuterium_per_second),
+            crystals_per_second: TechModifiers::apply(percent, self.crystals_per_second),
+        }
+    }
+
     /// Calculate rate with exponential scaling
-    /// rate = base_rate * (scaling_factor ^ level) / 3600 (convert from hourly)
-    fn calculate_rate(base_rate_hourly: u64, scaling_factor: u64, level: u32) -> u64 {
+    /// rate = base_rate * (scaling_factor ^ level) * precision / 3600 (convert from hourly)
+    fn calculate_rate(base_rate_hourly: u64, scaling_factor: u64, level: u32, precision: u64) -> u64 {
         if level == 0 {
             return 0;
         }
         
         // Scaling factor is stored as fixed point (150 = 1.5x)
-        let mut rate = base_rate_hourly as u128;
+        let mut rate = base_rate_hourly as u128 * precision as u128;
         for _ in 1..level {
             rate = rate * scaling_factor as u128 / 100;
         }
@@ -247,28 +341,159 @@ impl ProductionRates {
         // Convert from hourly to per-second
         (rate / 3600) as u64
     }
+
+    /// Whole resources produced over `elapsed_micros`
+    ///
+    /// The sub-unit part of the production is accumulated in `remainder` so that
+    /// frequent settlements do not truncate slow producers to zero.
+    pub fn accrue(&self, elapsed_micros: u64, remainder: &mut ProductionRemainder) -> Resources {
+        let (iron, iron_rem) = Self::accrue_one(
+            self.iron_per_second,
+            RATE_PRECISION,
+            elapsed_micros,
+            remainder.iron,
+        );
+        let (deuterium, deuterium_rem) = Self::accrue_one(
+            self.deuterium_per_second,
+            RATE_PRECISION,
+            elapsed_micros,
+            remainder.deuterium,
+        );
+        let (chronos_crystals, crystals_rem) = Self::accrue_one(
+            self.crystals_per_second,
+            CRYSTAL_RATE_PRECISION,
+            elapsed_micros,
+            remainder.crystals,
+        );
+
+        remainder.iron = iron_rem;
+        remainder.deuterium = deuterium_rem;
+        remainder.crystals = crystals_rem;
+
+        Resources::new(iron, deuterium, chronos_crystals)
+    }
+
+    fn accrue_one(rate: u64, precision: u64, elapsed_micros: u64, carried: u64) -> (u128, u64) {
+        let unit = precision as u128 * 1_000_000;
+        let total = rate as u128 * elapsed_micros as u128 + carried as u128;
+        (total / unit, (total % unit) as u64)
+    }
+}
+
+/// Fractional production carried between settlements
+///
+/// Values are in rate-precision units multiplied by microseconds, always
+/// below one whole resource.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ProductionRemainder {
+    pub iron: u64,
+    pub deuterium: u64,
+    pub crystals: u64,
 }
 
 /// Calculate stake decay based on time elapsed
-pub fn calculate_stake_decay(stake: &Resources, elapsed_hours: u64) -> Resources {
-    let decay_factor = STAKE_DECAY_RATE_PER_HOUR * elapsed_hours;
+pub fn calculate_stake_decay(stake: &Resources, elapsed_hours: u64, config: &GameConfig) -> Resources {
+    let decay_factor = config.stake_decay_rate_per_hour.saturating_mul(elapsed_hours);
     let decay_factor = std::cmp::min(decay_factor, 100); // Cap at 100% decay
+    let kept = (100 - decay_factor) as u128;
     
-    Resources {
-        iron: stake.iron * (100 - decay_factor) as u128 / 100,
-        deuterium: stake.deuterium * (100 - decay_factor) as u128 / 100,
-        chronos_crystals: stake.chronos_crystals * (100 - decay_factor) as u128 / 100,
+    // Split the amount so the multiplication cannot overflow
+    stake.map(|amount| amount / 100 * kept + amount % 100 * kept / 100)
+}
+
+/// Whole hours of decay after which a stake totals less than `minimum`
+///
+/// `None` if that never happens, when decay is off.
+pub fn stake_lifetime_hours(stake: &Resources, minimum: u128, config: &GameConfig) -> Option<u64> {
+    if config.stake_decay_rate_per_hour == 0 {
+        return None;
     }
+    (0..=100u64.div_ceil(config.stake_decay_rate_per_hour)).find(|hours| {
+        calculate_stake_decay(stake, *hours, config).total().is_some_and(|total| total < minimum)
+    })
 }
 
 /// Errors related to resource operations
-#[derive(Debug, Clone, Serialize, Deserialize, thiserror::Error)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
 pub enum ResourceError {
-    #[error("Insufficient resources: required {required:?}, available {available:?}")]
+    #[error("Insufficient resources: required {required}, available {available}")]
     InsufficientResources {
         required: Resources,
         available: Resources,
     },
     #[error("Invalid resource amount")]
     InvalidAmount,
+    #[error("Resource amount overflow")]
+    Overflow,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::IRON_BASE_RATE;
+
+    const HOUR_MICROS: u64 = 3_600 * 1_000_000;
+
+    #[test]
+    fn test_production_from_buildings() {
+        let rates = ProductionRates::from_buildings(&[(BuildingType::MinerDrone, 1)], &GameConfig::default());
+        let produced = rates.accrue(HOUR_MICROS, &mut ProductionRemainder::default());
+        assert_eq!(produced.iron, Quantity(IRON_BASE_RATE as u128 - 1)); // rounding of the per-second rate
+        assert_eq!(produced.deuterium, Quantity::ZERO);
+    }
+
+    #[test]
+    fn test_accrue_carries_remainder() {
+        let rates = ProductionRates::from_buildings(&[(BuildingType::MinerDrone, 1)], &GameConfig::default());
+
+        let mut remainder = ProductionRemainder::default();
+        let mut settled = 0;
+        for _ in 0..3_600 {
+            settled += rates.accrue(1_000_000, &mut remainder).iron.0;
+        }
+
+        let mut single = ProductionRemainder::default();
+        assert_eq!(settled, rates.accrue(HOUR_MICROS, &mut single).iron.0);
+        assert_eq!(remainder, single);
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let wallet = Resources::new(u128::MAX, 10, 5);
+        assert_eq!(wallet.checked_add(&Resources::new(1, 0, 0)), Err(ResourceError::Overflow));
+        assert_eq!(wallet.checked_mul(2), Err(ResourceError::Overflow));
+        assert_eq!(
+            wallet.checked_sub(&Resources::crystals(6)),
+            Err(ResourceError::InsufficientResources {
+                required: Resources::crystals(6),
+                available: wallet,
+            })
+        );
+        assert_eq!(wallet.checked_sub(&wallet), Ok(Resources::zero()));
+    }
+
+    #[test]
+    fn test_stake_lifetime() {
+        let mut config = GameConfig {
+            stake_decay_rate_per_hour: 10,
+            ..GameConfig::default()
+        };
+        let stake = Resources::new(1_000, 1_000, 0);
+        assert_eq!(calculate_stake_decay(&stake, 3, &config), Resources::new(700, 700, 0));
+        // 2000 * (1 - 0.1 * h) < 1000 first holds at h = 6
+        assert_eq!(stake_lifetime_hours(&stake, 1_000, &config), Some(6));
+        assert_eq!(stake_lifetime_hours(&stake, 5_000, &config), Some(0));
+
+        config.stake_decay_rate_per_hour = 0;
+        assert_eq!(stake_lifetime_hours(&stake, 1_000, &config), None);
+    }
+
+    #[test]
+    fn test_quantity_scalar() {
+        let large = Quantity(u64::MAX as u128 + 1);
+        assert_eq!(large.to_value(), Value::String("18446744073709551616".into()));
+        assert_eq!(Quantity::parse(large.to_value()).ok(), Some(large));
+        assert_eq!(Quantity::parse(Value::from(42u64)).ok(), Some(Quantity(42)));
+        assert!(Quantity::parse(Value::String("-1".into())).is_err());
+    }
 }
@@ -3,9 +3,13 @@
 //! These types are shared across all chain types and define the fundamental
 //! game objects like players, fleets, buildings, and more.
 
+use async_graphql::{Enum, SimpleObject};
 use linera_sdk::linera_base_types::{AccountOwner, ChainId, Timestamp};
 use serde::{Deserialize, Serialize};
 
+use crate::constants::{APPROVAL_THRESHOLD, QUORUM_PERCENTAGE};
+use crate::resources::Resources;
+
 /// Unique identifier for a player in the game
 pub type PlayerId = AccountOwner;
 
@@ -96,6 +100,11 @@ impl BuildingType {
             BuildingType::SubspaceRelay,
         ]
     }
+
+    /// Decode a building type from its storage discriminant
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::all().get(value as usize).copied()
+    }
 }
 
 /// Research technologies available in the tech tree
@@ -126,6 +135,30 @@ pub enum Technology {
     TemporalMechanics = 10,
 }
 
+impl Technology {
+    /// Get all technologies
+    pub fn all() -> &'static [Technology] {
+        &[
+            Technology::AdvancedMining,
+            Technology::ReinforcedHulls,
+            Technology::PlasmaWeapons,
+            Technology::IonDrives,
+            Technology::WarpTechnology,
+            Technology::ShieldHarmonics,
+            Technology::NanoConstruction,
+            Technology::ExpandedCargoBays,
+            Technology::LongRangeSensors,
+            Technology::StealthSystems,
+            Technology::TemporalMechanics,
+        ]
+    }
+
+    /// Decode a technology from its storage discriminant
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::all().get(value as usize).copied()
+    }
+}
+
 /// A building instance on a player's chain
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Building {
@@ -173,6 +206,39 @@ pub enum PlanetType {
     Volcanic,
 }
 
+impl PlanetType {
+    pub fn all() -> &'static [PlanetType] {
+        &[
+            PlanetType::Metallic,
+            PlanetType::GasGiant,
+            PlanetType::Temporal,
+            PlanetType::Terrestrial,
+            PlanetType::Barren,
+            PlanetType::Volcanic,
+        ]
+    }
+
+    /// Decode a planet type from its storage discriminant
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::all().get(value as usize).copied()
+    }
+
+    /// Resources a claimed planet yields to its owner per hour
+    ///
+    /// Temporal planets are the only source of Chronos Crystals besides the
+    /// Chronos Collider.
+    pub fn hourly_yield(&self) -> Resources {
+        match self {
+            PlanetType::Metallic => Resources::new(30, 0, 0),
+            PlanetType::GasGiant => Resources::new(0, 20, 0),
+            PlanetType::Temporal => Resources::new(0, 0, 3),
+            PlanetType::Terrestrial => Resources::new(10, 5, 0),
+            PlanetType::Barren => Resources::new(5, 0, 0),
+            PlanetType::Volcanic => Resources::new(20, 10, 0),
+        }
+    }
+}
+
 /// A planet in the game universe
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Planet {
@@ -209,7 +275,7 @@ pub struct TradeOffer {
 }
 
 /// Alliance membership status
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Enum)]
 pub enum AllianceMemberRole {
     /// Full control over alliance
     Leader,
@@ -221,8 +287,20 @@ pub enum AllianceMemberRole {
     Applicant,
 }
 
+impl AllianceMemberRole {
+    /// Authority of the role; a higher rank outranks a lower one
+    pub fn rank(&self) -> u8 {
+        match self {
+            AllianceMemberRole::Leader => 3,
+            AllianceMemberRole::Officer => 2,
+            AllianceMemberRole::Member => 1,
+            AllianceMemberRole::Applicant => 0,
+        }
+    }
+}
+
 /// Treaty types between alliances
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Enum)]
 pub enum TreatyType {
     /// No hostility allowed
     NonAggressionPact,
@@ -247,3 +325,148 @@ pub struct Treaty {
     pub expires_at: Option<Timestamp>,
     pub penalty_crystals: u128,
 }
+
+impl Treaty {
+    /// Whether the treaty is in force at `now`
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        self.created_at <= now && self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+
+    /// Whether the treaty is between alliances `a` and `b`
+    pub fn binds(&self, a: AllianceId, b: AllianceId) -> bool {
+        (self.party_a == a && self.party_b == b) || (self.party_a == b && self.party_b == a)
+    }
+}
+
+/// The non-aggression pact in force between two alliances at `now`, if any
+pub fn non_aggression_pact(
+    treaties: &[Treaty],
+    a: AllianceId,
+    b: AllianceId,
+    now: Timestamp,
+) -> Option<&Treaty> {
+    treaties.iter().find(|treaty| {
+        treaty.treaty_type == TreatyType::NonAggressionPact
+            && treaty.binds(a, b)
+            && treaty.is_active(now)
+    })
+}
+
+/// An action an alliance votes on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AllianceProposalKind {
+    /// Pay resources from the treasury to a chain
+    TreasurySpend {
+        recipient: ChainId,
+        resources: Resources,
+    },
+    /// Remove a member
+    KickMember {
+        member_chain: ChainId,
+    },
+    /// Offer a treaty to another alliance
+    DeclareTreaty {
+        treaty_type: TreatyType,
+        other_alliance: AllianceId,
+        penalty_crystals: u128,
+        duration_secs: Option<u64>,
+    },
+    /// Give a member another role
+    ChangeRank {
+        member_chain: ChainId,
+        role: AllianceMemberRole,
+    },
+}
+
+/// Voting power cast for and against an alliance proposal
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, SimpleObject)]
+pub struct VoteTally {
+    pub votes_for: u64,
+    pub votes_against: u64,
+}
+
+impl VoteTally {
+    pub fn add(&mut self, approve: bool, power: u64) {
+        if approve {
+            self.votes_for = self.votes_for.saturating_add(power);
+        } else {
+            self.votes_against = self.votes_against.saturating_add(power);
+        }
+    }
+
+    /// Total power cast
+    pub fn cast(&self) -> u64 {
+        self.votes_for.saturating_add(self.votes_against)
+    }
+
+    /// Whether the votes reach quorum out of `total_power` and the approval threshold
+    pub fn passes(&self, total_power: u64) -> bool {
+        let cast = self.cast() as u128;
+        cast > 0
+            && cast * 100 >= total_power as u128 * QUORUM_PERCENTAGE as u128
+            && self.votes_for as u128 * 100 >= cast * APPROVAL_THRESHOLD as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vote_tally() {
+        let mut tally = VoteTally::default();
+        assert!(!tally.passes(1000));
+
+        // Below quorum
+        tally.add(true, 50);
+        assert!(!tally.passes(1000));
+
+        // Quorum reached and approved
+        tally.add(true, 60);
+        assert!(tally.passes(1000));
+
+        // Quorum reached but short of the approval threshold
+        tally.add(false, 110);
+        assert_eq!(tally.cast(), 220);
+        assert!(!tally.passes(1000));
+    }
+
+    #[test]
+    fn test_planet_yield() {
+        for planet_type in PlanetType::all() {
+            assert_eq!(PlanetType::from_u8(*planet_type as u8), Some(*planet_type));
+            let crystals = planet_type.hourly_yield().chronos_crystals.0;
+            assert_eq!(crystals > 0, *planet_type == PlanetType::Temporal);
+        }
+        assert_eq!(PlanetType::from_u8(6), None);
+    }
+
+    #[test]
+    fn test_non_aggression_pact() {
+        use linera_sdk::linera_base_types::CryptoHash;
+
+        let a = ChainId(CryptoHash::from([1u8; 32]));
+        let b = ChainId(CryptoHash::from([2u8; 32]));
+        let c = ChainId(CryptoHash::from([3u8; 32]));
+        let treaty = |treaty_type, expires_at| Treaty {
+            id: 0,
+            treaty_type,
+            party_a: a,
+            party_b: b,
+            created_at: Timestamp::from(100),
+            expires_at,
+            penalty_crystals: 500,
+        };
+        let treaties = vec![
+            treaty(TreatyType::TradeAgreement, None),
+            treaty(TreatyType::NonAggressionPact, Some(Timestamp::from(1_000))),
+        ];
+
+        // Either party may be the aggressor
+        assert!(non_aggression_pact(&treaties, b, a, Timestamp::from(500)).is_some());
+        assert!(non_aggression_pact(&treaties, a, c, Timestamp::from(500)).is_none());
+        // Not yet signed, then expired
+        assert!(non_aggression_pact(&treaties, a, b, Timestamp::from(50)).is_none());
+        assert!(non_aggression_pact(&treaties, a, b, Timestamp::from(1_000)).is_none());
+    }
+}
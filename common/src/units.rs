@@ -3,8 +3,11 @@
 //! Defines all ship types, their stats, and fleet composition rules.
 
 use linera_sdk::linera_base_types::{AccountOwner, ChainId, Timestamp};
+use async_graphql::SimpleObject;
 use serde::{Deserialize, Serialize};
 
+use crate::config::GameConfig;
+use crate::research::TechModifiers;
 use crate::types::{FleetId, FleetState, CommitHash, Salt};
 use crate::resources::Resources;
 use crate::coordinates::Coordinate;
@@ -52,6 +55,11 @@ impl ShipType {
         ]
     }
 
+    /// Decode a ship type from its storage discriminant
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::all().get(value as usize).copied()
+    }
+
     /// Get base stats for this ship type
     pub fn base_stats(&self) -> ShipStats {
         match self {
@@ -172,7 +180,7 @@ impl ShipType {
 }
 
 /// Base stats for a ship type
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, SimpleObject)]
 pub struct ShipStats {
     pub max_health: u32,
     pub attack: u32,
@@ -185,6 +193,128 @@ pub struct ShipStats {
     pub fuel_consumption: u32,
 }
 
+impl ShipStats {
+    /// Stats after applying research modifiers
+    pub fn with_modifiers(self, modifiers: &TechModifiers) -> Self {
+        let apply = |percent: u32, value: u32| {
+            u32::try_from(TechModifiers::apply(percent, value as u64)).unwrap_or(u32::MAX)
+        };
+        Self {
+            max_health: apply(modifiers.hull, self.max_health),
+            attack: apply(modifiers.attack, self.attack),
+            defense: apply(modifiers.defense, self.defense),
+            speed: apply(modifiers.speed, self.speed),
+            cargo_capacity: apply(modifiers.cargo, self.cargo_capacity),
+            fuel_consumption: self.fuel_consumption,
+        }
+    }
+}
+
+/// Aggregate stats of a fleet described by ship counts
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FleetStats {
+    pub health: u64,
+    pub attack: u64,
+    pub defense: u64,
+    /// Speed of the slowest ship
+    pub speed: u32,
+    pub cargo_capacity: u64,
+    pub fuel_consumption: u64,
+}
+
+impl FleetStats {
+    /// Compute stats from ship counts indexed by `ShipType` discriminant
+    pub fn from_counts(ship_counts: &[u32], modifiers: &TechModifiers, config: &GameConfig) -> Self {
+        let mut stats = FleetStats::default();
+        let mut slowest: Option<u32> = None;
+
+        for (ship_type, &count) in ShipType::all().iter().zip(ship_counts) {
+            if count == 0 {
+                continue;
+            }
+            let ship = config.ship_stats(*ship_type).with_modifiers(modifiers);
+            let count = count as u64;
+            stats.health = stats.health.saturating_add(ship.max_health as u64 * count);
+            stats.attack = stats.attack.saturating_add(ship.attack as u64 * count);
+            stats.defense = stats.defense.saturating_add(ship.defense as u64 * count);
+            stats.cargo_capacity = stats.cargo_capacity.saturating_add(ship.cargo_capacity as u64 * count);
+            stats.fuel_consumption = stats.fuel_consumption.saturating_add(ship.fuel_consumption as u64 * count);
+            slowest = Some(slowest.map_or(ship.speed, |speed| speed.min(ship.speed)));
+        }
+
+        stats.speed = slowest.unwrap_or(0);
+        stats
+    }
+}
+
+/// Ships each side lost in an engagement, indexed by `ShipType` discriminant
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EngagementOutcome {
+    pub attacker_won: bool,
+    pub attacker_losses: Vec<u32>,
+    pub defender_losses: Vec<u32>,
+}
+
+/// Resolve an assault on a defended position
+///
+/// Each round both sides fire at once with their attack minus half the other
+/// side's defense. Damage to the defender is soaked by `shield` hit points
+/// first, then destroys ships weakest type first. The attacker wins only if
+/// it has ships left once the defenders and shield are gone.
+pub fn resolve_engagement(
+    attacker: &[u32],
+    attacker_modifiers: &TechModifiers,
+    defender: &[u32],
+    defender_modifiers: &TechModifiers,
+    shield: u64,
+    config: &GameConfig,
+) -> EngagementOutcome {
+    let mut attackers = attacker.to_vec();
+    let mut defenders = defender.to_vec();
+    let mut shield = shield;
+
+    for _ in 0..config.invasion_max_rounds {
+        let defenders_left = defenders.iter().any(|&count| count > 0) || shield > 0;
+        if !defenders_left || attackers.iter().all(|&count| count == 0) {
+            break;
+        }
+        let attack = FleetStats::from_counts(&attackers, attacker_modifiers, config);
+        let defense = FleetStats::from_counts(&defenders, defender_modifiers, config);
+        let to_defender = attack.attack.saturating_sub(defense.defense / 2);
+        let to_attacker = defense.attack.saturating_sub(attack.defense / 2);
+
+        let absorbed = to_defender.min(shield);
+        shield -= absorbed;
+        take_hits(&mut defenders, to_defender - absorbed, defender_modifiers, config);
+        take_hits(&mut attackers, to_attacker, attacker_modifiers, config);
+    }
+
+    let losses = |before: &[u32], after: &[u32]| {
+        before.iter().zip(after).map(|(b, a)| b - a).collect::<Vec<_>>()
+    };
+    EngagementOutcome {
+        attacker_won: attackers.iter().any(|&count| count > 0)
+            && defenders.iter().all(|&count| count == 0)
+            && shield == 0,
+        attacker_losses: losses(attacker, &attackers),
+        defender_losses: losses(defender, &defenders),
+    }
+}
+
+/// Destroy whole ships worth `damage` health, weakest type first
+fn take_hits(ships: &mut [u32], damage: u64, modifiers: &TechModifiers, config: &GameConfig) {
+    let mut remaining = damage;
+    for (ship_type, count) in ShipType::all().iter().zip(ships.iter_mut()) {
+        if *count == 0 || remaining == 0 {
+            continue;
+        }
+        let health = (config.ship_stats(*ship_type).with_modifiers(modifiers).max_health as u64).max(1);
+        let lost = (remaining / health).min(*count as u64);
+        *count -= lost as u32;
+        remaining -= lost * health;
+    }
+}
+
 /// An individual ship instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ship {
@@ -233,6 +363,8 @@ pub struct Fleet {
     pub position: Coordinate,
     pub state: FleetState,
     pub last_update: Timestamp,
+    /// Research modifiers of the owner when the fleet was dispatched
+    pub modifiers: TechModifiers,
 }
 
 impl Fleet {
@@ -253,28 +385,35 @@ impl Fleet {
             position,
             state: FleetState::Idle,
             last_update: timestamp,
+            modifiers: TechModifiers::default(),
         }
     }
 
+    /// Attach the owner's research modifiers
+    pub fn with_modifiers(mut self, modifiers: TechModifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
     /// Calculate total fleet power
     pub fn total_attack(&self) -> u32 {
         self.ships.iter()
             .filter(|s| s.is_alive())
-            .map(|s| s.effective_attack())
+            .map(|s| self.modified(self.modifiers.attack, s.effective_attack()))
             .sum()
     }
 
     pub fn total_defense(&self) -> u32 {
         self.ships.iter()
             .filter(|s| s.is_alive())
-            .map(|s| s.effective_defense())
+            .map(|s| self.modified(self.modifiers.defense, s.effective_defense()))
             .sum()
     }
 
     pub fn total_health(&self) -> u32 {
         self.ships.iter()
             .filter(|s| s.is_alive())
-            .map(|s| s.health)
+            .map(|s| self.modified(self.modifiers.hull, s.health))
             .sum()
     }
 
@@ -282,7 +421,7 @@ impl Fleet {
     pub fn speed(&self) -> u32 {
         self.ships.iter()
             .filter(|s| s.is_alive())
-            .map(|s| s.ship_type.base_stats().speed)
+            .map(|s| s.ship_type.base_stats().with_modifiers(&self.modifiers).speed)
             .min()
             .unwrap_or(0)
     }
@@ -291,10 +430,14 @@ impl Fleet {
     pub fn cargo_capacity(&self) -> u32 {
         self.ships.iter()
             .filter(|s| s.is_alive())
-            .map(|s| s.ship_type.base_stats().cargo_capacity)
+            .map(|s| s.ship_type.base_stats().with_modifiers(&self.modifiers).cargo_capacity)
             .sum()
     }
 
+    fn modified(&self, percent: u32, value: u32) -> u32 {
+        u32::try_from(TechModifiers::apply(percent, value as u64)).unwrap_or(u32::MAX)
+    }
+
     /// Calculate fuel consumption for traveling one sector
     pub fn fuel_consumption(&self) -> u32 {
         self.ships.iter()
@@ -349,10 +492,56 @@ impl FleetCommitment {
 }
 
 /// Ship construction queue entry
+///
+/// All ships of an order are built in parallel, so an order occupies
+/// `quantity` units of shipyard capacity from `started_at` until `completes_at`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShipConstructionOrder {
+    pub id: u64,
     pub ship_type: ShipType,
     pub quantity: u32,
-    pub started_at: Timestamp,
-    pub completes_at: Timestamp,
+    /// Set once the order has started (it waits in the queue until capacity frees up)
+    pub started_at: Option<Timestamp>,
+    pub completes_at: Option<Timestamp>,
+}
+
+impl ShipConstructionOrder {
+    pub fn new(id: u64, ship_type: ShipType, quantity: u32) -> Self {
+        Self {
+            id,
+            ship_type,
+            quantity,
+            started_at: None,
+            completes_at: None,
+        }
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_engagement() {
+        let modifiers = TechModifiers::default();
+        let config = GameConfig::default();
+        let mut strong = vec![0; ShipType::all().len()];
+        strong[ShipType::Battleship as usize] = 20;
+        let mut weak = vec![0; ShipType::all().len()];
+        weak[ShipType::Fighter as usize] = 5;
+
+        let outcome = resolve_engagement(&strong, &modifiers, &weak, &modifiers, 0, &config);
+        assert!(outcome.attacker_won);
+        assert_eq!(outcome.defender_losses, weak);
+        assert_eq!(outcome, resolve_engagement(&strong, &modifiers, &weak, &modifiers, 0, &config));
+
+        // A shield the attacker cannot break holds the position
+        let outcome = resolve_engagement(&weak, &modifiers, &[], &modifiers, u64::MAX, &config);
+        assert!(!outcome.attacker_won);
+        assert!(outcome.defender_losses.is_empty());
+    }
 }
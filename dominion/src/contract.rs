@@ -3,16 +3,42 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
 use linera_sdk::{
-    linera_base_types::{ChainId, WithContractAbi},
+    linera_base_types::{ApplicationId, ArithmeticError, ChainId, WithContractAbi},
     Contract, ContractRuntime,
-    views::{RootView, View},
+    views::{RootView, View, ViewError},
 };
 use linera_dominion::{
-    DominionState, DominionAbi, DominionError, DominionParameters,
-    Operation, Message, BuildingType, ShipType, Technology,
-    state::{self, BuildingData, AllianceData},
+    DominionState, DominionAbi, DominionError, DominionInstantiationArgument, DominionParameters, DominionResponse,
+    Operation, Message, BuildingType, ShipOrder, ShipType, Technology,
+    state::{
+        BuildingData, DiplomacyData, FleetData, InvasionData, PlanetHoldingData, ProposalData,
+        ResearchData, TradeOfferData,
+    },
+};
+use linera_dominion_region::{
+    Operation as RegionOperation, RegionAbi, RegionResponse,
+};
+use linera_dominion_alliance::{
+    AllianceAbi, AllianceResponse, Operation as AllianceOperation,
+};
+use linera_dominion_senate::{
+    Operation as SenateOperation, SenateAbi, SenateResponse,
+};
+use linera_dominion_common::{
+    constants::{
+        MAX_BUILDINGS_PER_BASE, MAX_BUILDING_LEVEL, MAX_FLEETS_PER_PLAYER, MAX_SHIPS_PER_FLEET, MINIMUM_PLANET_STAKE,
+        OFFLINE_TIMEOUT, PLANETARY_SHIELD_HP_PER_LEVEL, RANGE_PER_WARP_GATE_LEVEL, SECTOR_SIZE,
+    },
+    config::GameConfig,
+    coordinates::{Coordinate, FlightPath},
+    crypto::{commit_ship_counts, generate_salt},
+    errors::AllianceError,
+    messages::DominionMessage,
+    research::{missing_prerequisite, research_time, TechModifiers},
+    resources::{ResourceError, Resources},
+    types as common_types,
+    units::{self as common_units, EngagementOutcome, FleetStats, ShipConstructionOrder},
 };
-use linera_dominion_common::coordinates::Coordinate;
 
 pub struct DominionContract {
     state: DominionState,
@@ -28,7 +54,7 @@ impl WithContractAbi for DominionContract {
 impl Contract for DominionContract {
     type Message = Message;
     type Parameters = DominionParameters;
-    type InstantiationArgument = ();
+    type InstantiationArgument = DominionInstantiationArgument;
     type EventValue = ();
 
     async fn load(runtime: ContractRuntime<Self>) -> Self {
@@ -38,277 +64,443 @@ impl Contract for DominionContract {
         Self { state, runtime }
     }
 
-    async fn instantiate(&mut self, _argument: Self::InstantiationArgument) {
+    async fn instantiate(&mut self, argument: Self::InstantiationArgument) {
         // Get the application parameters
         let params = self.runtime.application_parameters();
+        let now_micros = self.runtime.system_time().micros();
         
-        // Initialize the state with player parameters
+        // Initialize the state with player parameters; production starts accruing now
         self.state.name_mut().set(params.player_name);
-        self.state.home_x_mut().set(params.home_x);
-        self.state.home_y_mut().set(params.home_y);
-        
-        // Initialize wallet with starting resources from params
-        self.state.wallet_mut().set(state::WalletData {
-            iron: params.starting_iron,
-            deuterium: params.starting_deuterium,
-            crystals: params.starting_crystals,
-            last_update_micros: 0,
-        });
+        self.state.initialize(
+            Coordinate::new(params.home_x, params.home_y),
+            params.starting_resources,
+            now_micros,
+        );
+        assert!(params.config.is_valid(), "Invalid game configuration");
+        self.state.config_mut().set(params.config);
+        
+        // The applications trusted to send notifications are fixed here
+        self.state.alliance_app_mut().set(argument.alliance_app);
+        self.state.senate_app_mut().set(argument.senate_app);
+        if let Some(alliance_app) = argument.alliance_app {
+            self.runtime
+                .call_application(true, alliance_app.with_abi::<AllianceAbi>(), &AllianceOperation::RegisterDominion)
+                .expect("Failed to register with the alliance application");
+        }
+        // Parameter updates reach this chain even before the player stakes
+        if let Some(senate_app) = argument.senate_app {
+            self.runtime
+                .call_application(true, senate_app.with_abi::<SenateAbi>(), &SenateOperation::Subscribe)
+                .expect("Failed to subscribe to the senate");
+        }
+        // Each region relays this chain's fleets and stakes for us alone
+        for region in argument.regions {
+            self.runtime
+                .call_application(true, region.application_id.with_abi::<RegionAbi>(), &RegionOperation::RegisterDominion)
+                .expect("Failed to register with the region");
+            self.state.regions_mut()
+                .insert(&(region.sector_x, region.sector_y), region.application_id)
+                .expect("Failed to register region");
+        }
     }
 
-    async fn execute_operation(&mut self, operation: Self::Operation) -> Result<(), DominionError> {
+    async fn execute_operation(&mut self, operation: Self::Operation) -> Result<DominionResponse, DominionError> {
         let now = self.runtime.system_time();
         let now_micros = now.micros();
         
-        match operation {
+        self.state.settle(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to settle state".into()))?;
+        self.process_alliance_notifications(now_micros).await?;
+        self.process_senate_notifications(now_micros).await?;
+        self.expire_trades(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to expire trades".into()))?;
+        self.state.expire_proposals(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to expire proposals".into()))?;
+        self.resolve_invasions(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to resolve invasions".into()))?;
+        
+        let response = match operation {
             Operation::Build { building_type, x, y } => {
-                // Check resources
-                let (cost_iron, cost_deut) = building_cost(building_type);
-                self.state.debit_resources(cost_iron, cost_deut, 0, now_micros)
-                    .map_err(|e| DominionError::InsufficientResources(e))?;
-                
-                // Add building
-                let count = *self.state.building_count().get();
-                let building = BuildingData {
-                    building_type: building_type as u8,
-                    level: 1,
-                    x,
-                    y,
-                    construction_end_micros: Some(now_micros + 60_000_000), // 60 seconds
-                };
-                self.state.buildings_mut().insert(&count, building)
-                    .map_err(|_| DominionError::InvalidOperation("Failed to add building".into()))?;
-                self.state.building_count_mut().set(count + 1);
-                
-                Ok(())
+                self.build(building_type, x, y, now_micros).await
             }
             
             Operation::CancelConstruction { building_id } => {
-                // TODO: Implement construction cancellation
-                Err(DominionError::InvalidOperation("Not implemented".into()))
+                self.cancel_construction(building_id, now_micros).await
+            }
+            
+            Operation::DemolishBuilding { building_id } => {
+                self.demolish_building(building_id).await
             }
             
             Operation::Research { technology } => {
-                // TODO: Implement research
-                Err(DominionError::InvalidOperation("Not implemented".into()))
+                self.start_research(technology, now_micros).await
             }
             
             Operation::CancelResearch => {
-                Err(DominionError::InvalidOperation("Not implemented".into()))
+                self.cancel_research(now_micros)
             }
             
             Operation::BuildShips { ship_type, quantity } => {
-                // Check resources
-                let (cost_iron, cost_deut) = ship_cost(ship_type);
-                let total_iron = cost_iron * quantity as u64;
-                let total_deut = cost_deut * quantity as u64;
-                
-                self.state.debit_resources(total_iron, total_deut, 0, now_micros)
-                    .map_err(|e| DominionError::InsufficientResources(e))?;
-                
-                // Add ships to pool
-                let key = ship_type as u8;
-                let current = self.state.ship_pool().get(&key).await
-                    .map_err(|_| DominionError::InvalidOperation("Failed to get ship pool".into()))?
-                    .unwrap_or(0);
-                self.state.ship_pool_mut().insert(&key, current + quantity)
-                    .map_err(|_| DominionError::InvalidOperation("Failed to add ships".into()))?;
-                
-                Ok(())
+                self.build_ships(ship_type, quantity, now_micros).await
+            }
+            
+            Operation::CancelShipOrder { order_id } => {
+                self.cancel_ship_order(order_id, now_micros).await
+            }
+            
+            Operation::ReorderShipOrder { order_id, position } => {
+                self.reorder_ship_order(order_id, position, now_micros).await
+            }
+            
+            Operation::CreateFleet { ships, name } => {
+                self.create_fleet(ships, name).await
+            }
+            
+            Operation::SplitFleet { fleet_id, ships, name } => {
+                self.split_fleet(fleet_id, ships, name).await
             }
             
-            Operation::CreateFleet { ships, name: _ } => {
-                // TODO: Implement fleet creation
-                Err(DominionError::InvalidOperation("Not implemented".into()))
+            Operation::MergeFleets { fleet_id, other_fleet_id } => {
+                self.merge_fleets(fleet_id, other_fleet_id).await
             }
             
             Operation::DisbandFleet { fleet_id } => {
-                Err(DominionError::FleetNotFound(fleet_id))
+                self.disband_fleet(fleet_id).await
             }
             
             Operation::SendFleet { fleet_id, destination_x, destination_y, cargo } => {
-                Err(DominionError::FleetNotFound(fleet_id))
+                let destination = Coordinate::new(destination_x, destination_y);
+                self.send_fleet(fleet_id, destination, cargo, now_micros).await
             }
             
             Operation::RecallFleet { fleet_id } => {
-                Err(DominionError::FleetNotFound(fleet_id))
+                self.recall_fleet(fleet_id, now_micros).await
+            }
+            
+            Operation::ClaimPlanet { fleet_id, planet_id, stake } => {
+                self.claim_planet(fleet_id, planet_id, stake, now_micros).await
+            }
+            
+            Operation::ResupplyPlanet { x, y, additional_stake } => {
+                self.resupply_planet(Coordinate::new(x, y), additional_stake).await
+            }
+            
+            Operation::AbandonPlanet { x, y } => {
+                self.abandon_planet(Coordinate::new(x, y)).await
+            }
+            
+            Operation::HarvestPlanet { x, y } => {
+                self.harvest_planet(Coordinate::new(x, y)).await
+            }
+            
+            Operation::CollectDebris { fleet_id, debris_id } => {
+                self.collect_debris(fleet_id, debris_id).await
+            }
+            
+            Operation::SyncRegion { sector_x, sector_y } => {
+                self.sync_region(sector_x, sector_y, now_micros).await
             }
             
             Operation::CreateTrade { target_chain, offering, requesting } => {
-                // TODO: Implement trade
-                Err(DominionError::InvalidOperation("Not implemented".into()))
+                self.create_trade(target_chain, offering, requesting, now_micros)
             }
             
             Operation::AcceptTrade { offer_id } => {
-                Err(DominionError::TradeNotFound(offer_id))
+                self.accept_trade(offer_id, now_micros).await
             }
             
             Operation::CancelTrade { offer_id } => {
-                Err(DominionError::TradeNotFound(offer_id))
+                self.cancel_trade(offer_id).await
             }
             
             Operation::JoinAlliance { alliance_chain } => {
-                if self.state.alliance().get().is_some() {
-                    return Err(DominionError::AlreadyInAlliance);
-                }
-                Err(DominionError::InvalidOperation("Not implemented".into()))
+                self.call_alliance(&AllianceOperation::RequestJoin { alliance_chain })
+                    .map(|()| DominionResponse::Ok)
             }
             
             Operation::LeaveAlliance => {
-                if self.state.alliance().get().is_none() {
-                    return Err(DominionError::NotInAlliance);
-                }
-                self.state.alliance_mut().set(None);
-                Ok(())
+                self.call_alliance(&AllianceOperation::Leave)
+                    .map(|()| DominionResponse::Ok)
+            }
+            
+            Operation::DepositToAlliance { resources } => {
+                self.state.debit_resources(&resources)?;
+                self.call_alliance(&AllianceOperation::Deposit { resources })
+                    .map(|()| DominionResponse::DepositedToAlliance { resources })
+            }
+            
+            // Senate Operations
+            Operation::StakeInSenate { crystals } => {
+                self.state.debit_resources(&Resources::crystals(crystals as u128))?;
+                let senate_app = (*self.state.senate_app().get())
+                    .ok_or_else(|| DominionError::InvalidOperation("No senate application configured".into()))?;
+                self.runtime
+                    .call_application(true, senate_app.with_abi::<SenateAbi>(), &SenateOperation::Stake { crystals })
+                    .map(|_| DominionResponse::StakedInSenate { crystals })
+                    .map_err(|error| DominionError::SenateCallFailed(error.to_string()))
             }
             
             // Diplomacy Operations
             Operation::ProposeAlliance { target_chain, alliance_name } => {
-                // Send alliance proposal to target chain
-                let owner = self.runtime.authenticated_signer()
-                    .ok_or(DominionError::InvalidOperation("Not authenticated".into()))?;
-                let my_chain = self.runtime.chain_id();
-                self.runtime.prepare_message(Message::AllianceProposal {
-                    proposal_id: now_micros,
-                    sender: owner,
-                    sender_chain: my_chain,
-                    alliance_name,
-                }).send_to(target_chain);
-                Ok(())
+                self.propose(target_chain, ProposalData::ALLIANCE, Some(alliance_name), now_micros).await
             }
             
             Operation::AcceptAllianceProposal { proposal_id } => {
-                // Accept alliance - set alliance state
-                self.state.alliance_mut().set(Some(AllianceData {
-                    name: format!("Alliance-{}", proposal_id),
-                    role: 0,
-                }));
-                Ok(())
+                self.answer_proposal(proposal_id, ProposalData::ALLIANCE, true, now_micros).await
             }
             
             Operation::RejectAllianceProposal { proposal_id } => {
-                // Just acknowledge rejection - no state change needed
-                let _ = proposal_id;
-                Ok(())
+                self.answer_proposal(proposal_id, ProposalData::ALLIANCE, false, now_micros).await
             }
             
             Operation::DeclareWar { target_chain } => {
-                // Send war declaration to target
-                let owner = self.runtime.authenticated_signer()
-                    .ok_or(DominionError::InvalidOperation("Not authenticated".into()))?;
-                let my_chain = self.runtime.chain_id();
-                self.runtime.prepare_message(Message::WarDeclared {
-                    aggressor: owner,
-                    aggressor_chain: my_chain,
-                }).send_to(target_chain);
-                Ok(())
+                self.declare_war(target_chain, now_micros).await
             }
             
             Operation::ProposePeace { target_chain } => {
-                let owner = self.runtime.authenticated_signer()
-                    .ok_or(DominionError::InvalidOperation("Not authenticated".into()))?;
-                let my_chain = self.runtime.chain_id();
-                self.runtime.prepare_message(Message::PeaceProposal {
-                    proposal_id: now_micros,
-                    sender: owner,
-                    sender_chain: my_chain,
-                }).send_to(target_chain);
-                Ok(())
+                self.propose(target_chain, ProposalData::PEACE, None, now_micros).await
             }
             
             Operation::AcceptPeace { proposal_id } => {
-                // Accept peace treaty
-                let _ = proposal_id;
-                Ok(())
+                self.answer_proposal(proposal_id, ProposalData::PEACE, true, now_micros).await
             }
             
             // Invasion Operations
             Operation::LaunchInvasion { target_chain, fleet_id, target_x, target_y } => {
-                let owner = self.runtime.authenticated_signer()
-                    .ok_or(DominionError::InvalidOperation("Not authenticated".into()))?;
-                let my_chain = self.runtime.chain_id();
-                self.runtime.prepare_message(Message::InvasionLaunched {
-                    invasion_id: fleet_id, // Use fleet_id as invasion_id
-                    attacker: owner,
-                    attacker_chain: my_chain,
-                    fleet_strength: fleet_id, // Placeholder, should calculate from fleet
-                    target_x,
-                    target_y,
-                }).send_to(target_chain);
-                Ok(())
+                self.launch_invasion(target_chain, fleet_id, target_x, target_y, now_micros).await
             }
             
             Operation::DefendInvasion { invasion_id, defender_fleet_id } => {
-                // Acknowledge defense with the defender's fleet
-                let _ = defender_fleet_id; // Will be used for battle calculation
-                Ok(())
+                self.defend_invasion(invasion_id, defender_fleet_id, now_micros).await
             }
             
             Operation::ClaimInvasionRewards { invasion_id } => {
-                // Claim rewards after successful invasion
-                Ok(())
+                self.claim_invasion_rewards(invasion_id).await
             }
-        }
+            
+            Operation::WithdrawInvasion { invasion_id } => {
+                self.withdraw_invasion(invasion_id, now_micros).await
+            }
+        }?;
+        
+        self.state.record_response(response.clone())
+            .map_err(|_| DominionError::InvalidOperation("Failed to record response".into()))?;
+        Ok(response)
     }
 
     async fn execute_message(&mut self, message: Self::Message) {
-        let _now = self.runtime.system_time();
+        let now = self.runtime.system_time();
+        
+        self.state.settle(now.micros()).await
+            .expect("Failed to settle state");
+        self.process_alliance_notifications(now.micros()).await
+            .expect("Failed to process alliance notifications");
+        self.process_senate_notifications(now.micros()).await
+            .expect("Failed to process senate notifications");
+        self.expire_trades(now.micros()).await
+            .expect("Failed to expire trades");
+        self.state.expire_proposals(now.micros()).await
+            .expect("Failed to expire proposals");
+        self.resolve_invasions(now.micros()).await
+            .expect("Failed to resolve invasions");
+        
+        let origin = self.runtime.message_origin_chain_id()
+            .expect("Incoming message must have an origin");
+        let bouncing = self.runtime.message_is_bouncing() == Some(true);
         
         match message {
-            Message::TradeOffer { .. } => {
-                // TODO: Handle incoming trade offer
+            Message::TradeOffer {
+                offer_id,
+                offering,
+                requesting,
+                expires_micros,
+                ..
+            } => {
+                if bouncing {
+                    // The taker refused the offer: release our escrow
+                    self.release_outgoing_trade(offer_id, origin)
+                        .await
+                        .expect("Failed to release trade");
+                    return;
+                }
+                self.state.add_trade(TradeOfferData {
+                    counterparty_chain: origin.to_string(),
+                    outgoing: false,
+                    remote_offer_id: offer_id,
+                    status: TradeOfferData::OPEN,
+                    offer: offering,
+                    request: requesting,
+                    expires_micros,
+                })
+                .expect("Failed to store trade offer");
+            }
+            Message::TradeAccepted { offer_id } => {
+                if bouncing {
+                    self.release_incoming_trade(offer_id, origin)
+                        .await
+                        .expect("Failed to release trade");
+                    return;
+                }
+                self.settle_trade(offer_id, origin, now.micros())
+                    .await
+                    .expect("Failed to settle trade");
             }
-            Message::TradeAccepted { .. } => {
-                // TODO: Handle trade acceptance
+            Message::TradeCompleted { offer_id } => {
+                if bouncing {
+                    self.unwind_trade(offer_id, origin)
+                        .await
+                        .expect("Failed to unwind trade");
+                    return;
+                }
+                self.receive_trade(offer_id, origin)
+                    .await
+                    .expect("Failed to receive trade");
             }
-            Message::TradeCancelled { .. } => {
-                // TODO: Handle trade cancellation
+            Message::TradeReceived { offer_id } => {
+                if !bouncing {
+                    self.complete_trade(offer_id, origin)
+                        .await
+                        .expect("Failed to complete trade");
+                }
             }
-            Message::FleetArrival { .. } => {
-                // TODO: Handle fleet arrival notification
+            Message::TradeCancelled { offer_id } => {
+                if !bouncing {
+                    self.release_incoming_trade(offer_id, origin)
+                        .await
+                        .expect("Failed to release trade");
+                }
             }
-            Message::BattleResult { .. } => {
-                // TODO: Handle battle result
+            Message::TradeDeclined { offer_id } => {
+                if !bouncing {
+                    self.release_outgoing_trade(offer_id, origin)
+                        .await
+                        .expect("Failed to release trade");
+                }
             }
-            Message::AllianceInvite { alliance_name, .. } => {
-                // Store as pending proposal
+            Message::AllianceInvite { .. } => {
+                // Players join by asking the alliance application
             }
             
             // Diplomacy Messages
-            Message::AllianceProposal { alliance_name, .. } => {
-                // Store incoming alliance proposal for user to accept/reject
+            Message::AllianceProposal { proposal_id, alliance_name, expires_micros, .. } => {
+                self.receive_proposal(
+                    origin,
+                    ProposalData::ALLIANCE,
+                    proposal_id,
+                    Some(alliance_name),
+                    expires_micros,
+                    now.micros(),
+                )
+                .await
+                .expect("Failed to store proposal");
             }
             Message::AllianceAccepted { alliance_name, .. } => {
-                // Alliance was accepted, update state
-                self.state.alliance_mut().set(Some(AllianceData {
-                    name: alliance_name,
-                    role: 0, // member
-                }));
+                self.confirm_proposal(origin, ProposalData::ALLIANCE, Some(alliance_name), now.micros())
+                    .await
+                    .expect("Failed to update diplomacy");
             }
-            Message::AllianceRejected { .. } => {
-                // Alliance was rejected, no action needed
+            Message::AllianceRejected { proposal_id } => {
+                let origin = origin.to_string();
+                let outgoing = self.state.find_proposal(&origin, ProposalData::ALLIANCE, true, None).await
+                    .expect("Failed to read proposals");
+                if outgoing == Some(proposal_id) {
+                    self.state.proposals_mut().remove(&proposal_id)
+                        .expect("Failed to remove proposal");
+                }
             }
             Message::WarDeclared { .. } => {
-                // War has been declared on us
+                let origin = origin.to_string();
+                self.state.remove_proposals_with(&origin).await
+                    .expect("Failed to remove proposals");
+                self.state.set_relation(&origin, DiplomacyData::AT_WAR, None, now.micros())
+                    .expect("Failed to update diplomacy");
             }
-            Message::PeaceProposal { .. } => {
-                // Peace proposal received
+            Message::PeaceProposal { proposal_id, expires_micros, .. } => {
+                self.receive_proposal(origin, ProposalData::PEACE, proposal_id, None, expires_micros, now.micros())
+                    .await
+                    .expect("Failed to store proposal");
             }
             Message::PeaceAccepted { .. } => {
-                // Peace was accepted
+                self.confirm_proposal(origin, ProposalData::PEACE, None, now.micros())
+                    .await
+                    .expect("Failed to update diplomacy");
             }
             
             // Invasion Messages
-            Message::InvasionLaunched { .. } => {
-                // We are being invaded! Store for defense
-            }
-            Message::InvasionResult { attacker_won, .. } => {
-                // Invasion result received
-                if attacker_won {
-                    // We lost, resources were taken
-                } else {
-                    // We defended successfully
+            Message::InvasionLaunched {
+                invasion_id,
+                fleet_strength,
+                ship_counts,
+                modifiers,
+                target_x,
+                target_y,
+                arrival_micros,
+                ..
+            } => {
+                if bouncing {
+                    self.abort_invasion(invasion_id, origin)
+                        .await
+                        .expect("Failed to abort invasion");
+                    return;
+                }
+                if (target_x, target_y) != (*self.state.home_x().get(), *self.state.home_y().get()) {
+                    // Nothing to invade there: report a bloodless defeat so the fleet is released
+                    self.runtime
+                        .prepare_message(Message::InvasionResult {
+                            invasion_id,
+                            attacker_won: false,
+                            attacker_losses: Vec::new(),
+                            defender_losses: Vec::new(),
+                            loot: Resources::zero(),
+                        })
+                        .with_tracking()
+                        .send_to(origin);
+                    return;
                 }
+                let defender_chain = self.runtime.chain_id();
+                let defense_window_secs = self.state.config().get().invasion_defense_window;
+                self.state.add_invasion(InvasionData {
+                    attacker_chain: origin.to_string(),
+                    defender_chain: defender_chain.to_string(),
+                    attacking: false,
+                    remote_invasion_id: invasion_id,
+                    attacker_ships: ship_counts,
+                    attacker_modifiers: modifiers,
+                    target_x,
+                    target_y,
+                    status: InvasionData::PENDING,
+                    attacker_strength: fleet_strength,
+                    started_micros: now.micros(),
+                    defense_ends_micros: arrival_micros
+                        .max(now.micros())
+                        .saturating_add(defense_window_secs.saturating_mul(1_000_000)),
+                    ..InvasionData::default()
+                })
+                .expect("Failed to store invasion");
+            }
+            Message::InvasionResult {
+                invasion_id,
+                attacker_won,
+                attacker_losses,
+                defender_losses,
+                loot,
+            } => {
+                if bouncing {
+                    // The attacker refused the result: the loot stays with us
+                    self.state.credit_resources(&loot)
+                        .expect("Failed to return loot");
+                    return;
+                }
+                let outcome = EngagementOutcome { attacker_won, attacker_losses, defender_losses };
+                self.record_invasion_result(
+                    invasion_id,
+                    origin,
+                    outcome,
+                    loot,
+                    now.micros(),
+                )
+                .await
+                .expect("Failed to record invasion result");
             }
         }
     }
@@ -318,34 +510,1915 @@ impl Contract for DominionContract {
     }
 }
 
-/// Get building cost
-fn building_cost(building_type: BuildingType) -> (u64, u64) {
-    match building_type {
-        BuildingType::MinerDrone => (100, 25),
-        BuildingType::GasSiphon => (75, 100),
-        BuildingType::ChronosCollider => (1000, 500),
-        BuildingType::Shipyard => (400, 200),
-        BuildingType::WarpGate => (2000, 1000),
-        BuildingType::PlanetaryShield => (500, 250),
-        BuildingType::ResearchLab => (200, 100),
-        BuildingType::Warehouse => (150, 50),
-        BuildingType::OrbitalCannon => (800, 400),
-        BuildingType::SubspaceRelay => (300, 150),
+impl DominionContract {
+    /// Start a new level-1 building on an empty tile, or upgrade the building on it
+    async fn build(
+        &mut self,
+        building_type: BuildingType,
+        x: i64,
+        y: i64,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        let existing = self.state.building_at(x, y).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read building".into()))?;
+        
+        let level = match &existing {
+            Some((_, building)) => {
+                if building.building_type != building_type as u8 {
+                    return Err(DominionError::TileOccupied);
+                }
+                if building.construction_end_micros.is_some() {
+                    return Err(DominionError::ConstructionInProgress);
+                }
+                if building.level >= MAX_BUILDING_LEVEL {
+                    return Err(DominionError::MaxBuildingLevel);
+                }
+                building.level + 1
+            }
+            None => {
+                let count = self.state.buildings().count().await
+                    .map_err(|_| DominionError::InvalidOperation("Failed to count buildings".into()))?;
+                if count >= MAX_BUILDINGS_PER_BASE {
+                    return Err(DominionError::BuildingLimitReached(MAX_BUILDINGS_PER_BASE));
+                }
+                1
+            }
+        };
+        
+        let cost = self.state.config().get().building_cost(building_type.to_common(), level);
+        self.state.debit_resources(&cost)?;
+        
+        let modifiers = self.state.tech_modifiers(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read research".into()))?;
+        
+        // The building keeps producing at its previous level until construction ends
+        let construction_time =
+            building_time(self.state.config().get(), building_type, level, modifiers.construction_time);
+        let construction_micros = construction_time * 1_000_000;
+        let completes_micros = now_micros + construction_micros;
+        let construction_end_micros = Some(completes_micros);
+        
+        let building_id = match existing {
+            Some((building_id, mut building)) => {
+                building.level = level;
+                building.construction_end_micros = construction_end_micros;
+                building.construction_micros = construction_micros;
+                self.state.buildings_mut().insert(&building_id, building)
+                    .map_err(|_| DominionError::InvalidOperation("Failed to upgrade building".into()))?;
+                building_id
+            }
+            None => {
+                self.state.add_building(BuildingData {
+                    building_type: building_type as u8,
+                    level,
+                    x,
+                    y,
+                    construction_end_micros,
+                    construction_micros,
+                })
+                .map_err(|_| DominionError::InvalidOperation("Failed to add building".into()))?
+            }
+        };
+        
+        Ok(DominionResponse::ConstructionStarted { building_id, level, completes_micros, cost })
+    }
+    
+    /// Cancel a construction or upgrade, refunding the unspent share of its cost
+    ///
+    /// A cancelled new building disappears; a cancelled upgrade keeps the previous level.
+    async fn cancel_construction(
+        &mut self,
+        building_id: u64,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        let (building_type, mut building) = self.load_building(building_id).await?;
+        let end_micros = building.construction_end_micros
+            .ok_or(DominionError::NotUnderConstruction)?;
+        
+        // Research finished since the start does not change the refund
+        let total_micros = building.construction_micros;
+        let remaining_micros = end_micros.saturating_sub(now_micros).min(total_micros);
+        let percentage = self.state.config().get().construction_cancel_refund_percentage as u128;
+        let refund_share = |amount: u128| {
+            let unspent = amount * remaining_micros as u128 / total_micros.max(1) as u128;
+            unspent * percentage / 100
+        };
+        
+        let cost = self.state.config().get().building_cost(building_type.to_common(), building.level);
+        let refund = cost.map(refund_share);
+        self.state.credit_resources(&refund)?;
+        
+        if building.level <= 1 {
+            self.state.remove_building(building_id, &building)
+                .map_err(|_| DominionError::InvalidOperation("Failed to remove building".into()))?;
+        } else {
+            building.level -= 1;
+            building.construction_end_micros = None;
+            self.state.buildings_mut().insert(&building_id, building)
+                .map_err(|_| DominionError::InvalidOperation("Failed to update building".into()))?;
+        }
+        
+        Ok(DominionResponse::ConstructionCancelled { building_id, refund })
+    }
+    
+    /// Demolish a finished building, salvaging part of everything invested in it
+    async fn demolish_building(&mut self, building_id: u64) -> Result<DominionResponse, DominionError> {
+        let (building_type, building) = self.load_building(building_id).await?;
+        if building.construction_end_micros.is_some() {
+            return Err(DominionError::ConstructionInProgress);
+        }
+        
+        let config = self.state.config().get();
+        let mut invested = Resources::zero();
+        for level in 1..=building.level {
+            invested = invested.checked_add(&config.building_cost(building_type.to_common(), level))?;
+        }
+        let percentage = config.demolition_salvage_percentage as u128;
+        let salvage = invested.map(|amount| amount * percentage / 100);
+        
+        self.state.remove_building(building_id, &building)
+            .map_err(|_| DominionError::InvalidOperation("Failed to remove building".into()))?;
+        self.state.credit_resources(&salvage)?;
+        
+        Ok(DominionResponse::BuildingDemolished { building_id, salvage })
+    }
+    
+    /// Start researching the next level of `technology` in the research slot
+    async fn start_research(
+        &mut self,
+        technology: Technology,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        if self.state.current_research().get().is_some() {
+            return Err(DominionError::ResearchInProgress);
+        }
+        
+        let lab_level = self.state
+            .operational_building_level(BuildingType::ResearchLab as u8, now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read buildings".into()))?;
+        if lab_level == 0 {
+            return Err(DominionError::NoResearchLab);
+        }
+        
+        let tech = common_types::Technology::from_u8(technology as u8)
+            .ok_or(DominionError::InvalidOperation("Unknown technology".into()))?;
+        
+        let mut known_levels = Vec::new();
+        for requirement in tech.prerequisites() {
+            let level = self.state.tech_level(requirement.technology as u8).await
+                .map_err(|_| DominionError::InvalidOperation("Failed to read research".into()))?;
+            known_levels.push((requirement.technology, level));
+        }
+        let level_of = |prerequisite: common_types::Technology| {
+            known_levels.iter()
+                .find(|(known, _)| *known == prerequisite)
+                .map_or(0, |(_, level)| *level)
+        };
+        if let Some(missing) = missing_prerequisite(tech, level_of) {
+            return Err(DominionError::TechPrerequisiteNotMet {
+                prerequisite: Technology::from_u8(missing.technology as u8)
+                    .ok_or(DominionError::InvalidOperation("Unknown technology".into()))?,
+                level: missing.level,
+            });
+        }
+        
+        let level = self.state.tech_level(technology as u8).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read research".into()))? + 1;
+        let cost = self.state.config().get().research_cost(tech, level);
+        self.state.debit_resources(&cost)?;
+        
+        let completes_micros = now_micros + research_time(tech, level, lab_level) * 1_000_000;
+        self.state.current_research_mut().set(Some(ResearchData {
+            technology_type: technology as u8,
+            level,
+            started_micros: now_micros,
+            completion_micros: Some(completes_micros),
+        }));
+        
+        Ok(DominionResponse::ResearchStarted { technology, level, completes_micros, cost })
+    }
+    
+    /// Abort the current research project, refunding the unspent share of its cost
+    fn cancel_research(&mut self, now_micros: u64) -> Result<DominionResponse, DominionError> {
+        let project = self.state.current_research().get().clone()
+            .ok_or(DominionError::NoResearchInProgress)?;
+        let tech = common_types::Technology::from_u8(project.technology_type)
+            .ok_or(DominionError::InvalidOperation("Unknown technology".into()))?;
+        let technology = Technology::from_u8(project.technology_type)
+            .ok_or(DominionError::InvalidOperation("Unknown technology".into()))?;
+        
+        let end_micros = project.completion_micros.unwrap_or(now_micros);
+        let total_micros = end_micros.saturating_sub(project.started_micros).max(1);
+        let remaining_micros = end_micros.saturating_sub(now_micros).min(total_micros);
+        let percentage = self.state.config().get().research_cancel_refund_percentage as u128;
+        let refund_share = |amount: u128| {
+            let unspent = amount * remaining_micros as u128 / total_micros as u128;
+            unspent * percentage / 100
+        };
+        
+        let refund = self.state.config().get().research_cost(tech, project.level).map(refund_share);
+        self.state.credit_resources(&refund)?;
+        self.state.current_research_mut().set(None);
+        
+        Ok(DominionResponse::ResearchCancelled { technology, refund })
+    }
+    
+    /// Queue a ship order and start it right away if the shipyard has capacity
+    async fn build_ships(
+        &mut self,
+        ship_type: ShipType,
+        quantity: u32,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        if quantity == 0 {
+            return Err(DominionError::InvalidOperation("Quantity must be positive".into()));
+        }
+        
+        let capacity = self.state.shipyard_capacity(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read buildings".into()))?;
+        if capacity == 0 {
+            return Err(DominionError::NoShipyard);
+        }
+        if quantity > capacity {
+            return Err(DominionError::ShipyardCapacityExceeded(capacity));
+        }
+        
+        let common_type = common_units::ShipType::from_u8(ship_type as u8)
+            .ok_or(DominionError::InvalidOperation("Unknown ship type".into()))?;
+        let cost = self.state.config().get().ship_cost(common_type);
+        let total = cost.checked_mul(quantity as u128)?;
+        self.state.debit_resources(&total)?;
+        
+        let order_id = *self.state.ship_order_count().get();
+        let mut queue = self.state.ship_queue().get().clone();
+        queue.push(ShipConstructionOrder::new(order_id, common_type, quantity));
+        self.state.ship_queue_mut().set(queue);
+        self.state.ship_order_count_mut().set(order_id + 1);
+        
+        self.state.start_ship_orders(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to start ship order".into()))?;
+        Ok(DominionResponse::ShipOrderQueued {
+            order_id,
+            completes_micros: self.ship_order_completion(order_id),
+            cost: total,
+        })
+    }
+    
+    /// Cancel a ship order, refunding the unspent share of its cost
+    ///
+    /// A waiting order has spent nothing, so only the cancellation fee is kept.
+    async fn cancel_ship_order(&mut self, order_id: u64, now_micros: u64) -> Result<DominionResponse, DominionError> {
+        let mut queue = self.state.ship_queue().get().clone();
+        let index = queue.iter().position(|order| order.id == order_id)
+            .ok_or(DominionError::ShipOrderNotFound(order_id))?;
+        let order = queue.remove(index);
+        
+        let (total_micros, remaining_micros) = match (order.started_at, order.completes_at) {
+            (Some(start), Some(end)) => {
+                let total = end.micros().saturating_sub(start.micros()).max(1);
+                (total, end.micros().saturating_sub(now_micros).min(total))
+            }
+            _ => (1, 1),
+        };
+        let percentage = self.state.config().get().construction_cancel_refund_percentage as u128;
+        let refund_share = |amount: u128| {
+            let unspent = amount * remaining_micros as u128 / total_micros as u128;
+            unspent * percentage / 100
+        };
+        
+        let cost = self.state.config().get().ship_cost(order.ship_type);
+        let refund = cost.checked_mul(order.quantity as u128)?.map(refund_share);
+        self.state.credit_resources(&refund)?;
+        
+        self.state.ship_queue_mut().set(queue);
+        self.state.start_ship_orders(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to start ship order".into()))?;
+        Ok(DominionResponse::ShipOrderCancelled { order_id, refund })
+    }
+    
+    /// Move a waiting ship order within the waiting part of the queue
+    async fn reorder_ship_order(
+        &mut self,
+        order_id: u64,
+        position: u32,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        let mut queue = self.state.ship_queue().get().clone();
+        let index = queue.iter().position(|order| order.id == order_id)
+            .ok_or(DominionError::ShipOrderNotFound(order_id))?;
+        if queue[index].is_started() {
+            return Err(DominionError::ShipOrderStarted);
+        }
+        
+        let order = queue.remove(index);
+        let started = queue.iter().filter(|order| order.is_started()).count();
+        let waiting = queue.len() - started;
+        queue.insert(started + (position as usize).min(waiting), order);
+        self.state.ship_queue_mut().set(queue);
+        
+        // A smaller order moved to the front may fit in the free capacity
+        self.state.start_ship_orders(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to start ship order".into()))?;
+        Ok(DominionResponse::ShipOrderMoved {
+            order_id,
+            completes_micros: self.ship_order_completion(order_id),
+        })
+    }
+    
+    /// Assemble an idle fleet at home from ships in the pool
+    async fn create_fleet(&mut self, ships: Vec<ShipOrder>, name: Option<String>) -> Result<DominionResponse, DominionError> {
+        let ship_counts = ship_counts(&ships)?;
+        self.check_fleet_limit().await?;
+        
+        if let Err(ship_type) = self.state.debit_ships(&ship_counts).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read ship pool".into()))?
+        {
+            return Err(DominionError::InsufficientShips(
+                ShipType::from_u8(ship_type)
+                    .ok_or(DominionError::InvalidOperation("Unknown ship type".into()))?,
+            ));
+        }
+        
+        let home_x = *self.state.home_x().get();
+        let home_y = *self.state.home_y().get();
+        let fleet_id = self.add_fleet(name, ship_counts, home_x, home_y)?;
+        Ok(DominionResponse::FleetCreated { fleet_id })
+    }
+    
+    /// Move some ships of an idle fleet into a new fleet at the same position
+    async fn split_fleet(
+        &mut self,
+        fleet_id: u64,
+        ships: Vec<ShipOrder>,
+        name: Option<String>,
+    ) -> Result<DominionResponse, DominionError> {
+        let mut fleet = self.load_idle_fleet(fleet_id).await?;
+        let detached = ship_counts(&ships)?;
+        self.check_fleet_limit().await?;
+        
+        for (ship_type, count) in detached.iter().enumerate() {
+            let available = fleet.ships.get(ship_type).copied().unwrap_or(0);
+            if available < *count {
+                return Err(DominionError::InsufficientShips(
+                    ShipType::from_u8(ship_type as u8)
+                        .ok_or(DominionError::InvalidOperation("Unknown ship type".into()))?,
+                ));
+            }
+        }
+        fleet.ships.resize(detached.len(), 0);
+        for (remaining, count) in fleet.ships.iter_mut().zip(&detached) {
+            *remaining -= count;
+        }
+        if fleet.total_ships() == 0 {
+            return Err(DominionError::EmptyFleet);
+        }
+        
+        let (x, y) = (fleet.current_x, fleet.current_y);
+        self.state.fleets_mut().insert(&fleet_id, fleet)
+            .map_err(|_| DominionError::InvalidOperation("Failed to update fleet".into()))?;
+        let fleet_id = self.add_fleet(name, detached, x, y)?;
+        Ok(DominionResponse::FleetSplit { fleet_id })
+    }
+    
+    /// Merge `other_fleet_id` into `fleet_id`; both must be idle at the same position
+    async fn merge_fleets(&mut self, fleet_id: u64, other_fleet_id: u64) -> Result<DominionResponse, DominionError> {
+        if fleet_id == other_fleet_id {
+            return Err(DominionError::InvalidOperation("Cannot merge a fleet with itself".into()));
+        }
+        let mut fleet = self.load_idle_fleet(fleet_id).await?;
+        let other = self.load_idle_fleet(other_fleet_id).await?;
+        if (fleet.current_x, fleet.current_y) != (other.current_x, other.current_y) {
+            return Err(DominionError::FleetsNotColocated);
+        }
+        if fleet.total_ships() + other.total_ships() > MAX_SHIPS_PER_FLEET as u64 {
+            return Err(DominionError::FleetTooLarge(MAX_SHIPS_PER_FLEET));
+        }
+        
+        if fleet.ships.len() < other.ships.len() {
+            fleet.ships.resize(other.ships.len(), 0);
+        }
+        for (count, added) in fleet.ships.iter_mut().zip(&other.ships) {
+            *count += added;
+        }
+        fleet.cargo = fleet.cargo.checked_add(&other.cargo)?;
+        
+        self.state.fleets_mut().insert(&fleet_id, fleet)
+            .map_err(|_| DominionError::InvalidOperation("Failed to update fleet".into()))?;
+        self.state.fleets_mut().remove(&other_fleet_id)
+            .map_err(|_| DominionError::InvalidOperation("Failed to remove fleet".into()))?;
+        Ok(DominionResponse::FleetsMerged { fleet_id })
+    }
+    
+    /// Return the ships of an idle fleet at home to the pool
+    async fn disband_fleet(&mut self, fleet_id: u64) -> Result<DominionResponse, DominionError> {
+        let fleet = self.load_idle_fleet(fleet_id).await?;
+        if (fleet.current_x, fleet.current_y) != (*self.state.home_x().get(), *self.state.home_y().get()) {
+            return Err(DominionError::FleetNotAtHome(fleet_id));
+        }
+        
+        for (ship_type, count) in fleet.ships.iter().enumerate() {
+            if *count > 0 {
+                self.state.credit_ships(ship_type as u8, *count).await
+                    .map_err(|_| DominionError::InvalidOperation("Failed to update ship pool".into()))?;
+            }
+        }
+        self.state.credit_resources(&fleet.cargo)?;
+        self.state.fleets_mut().remove(&fleet_id)
+            .map_err(|_| DominionError::InvalidOperation("Failed to remove fleet".into()))?;
+        Ok(DominionResponse::FleetDisbanded { fleet_id, cargo: fleet.cargo })
+    }
+    
+    /// Dispatch an idle fleet toward `destination`, announcing it to the Region Chain there
+    ///
+    /// The Region Chain answers with an arrival confirmation, or turns the fleet back.
+    /// A fleet stationed away from home first leaves the region it is in.
+    /// Besides fuel and cargo, the region entry fee is debited.
+    async fn send_fleet(
+        &mut self,
+        fleet_id: u64,
+        destination: Coordinate,
+        cargo: Option<Resources>,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        let mut fleet = self.load_idle_fleet(fleet_id).await?;
+        let origin = Coordinate::new(fleet.current_x, fleet.current_y);
+        if origin == destination {
+            return Err(DominionError::AlreadyAtDestination);
+        }
+        
+        let region_app = self.region_app(destination).await?;
+        let home = Coordinate::new(*self.state.home_x().get(), *self.state.home_y().get());
+        let departed_region = if origin != home {
+            Some(self.region_app(origin).await?)
+        } else {
+            None
+        };
+        self.runtime.authenticated_signer()
+            .ok_or(DominionError::NotAuthorized)?;
+        
+        let modifiers = self.state.tech_modifiers(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read research".into()))?;
+        let stats = FleetStats::from_counts(&fleet.ships, &modifiers, self.state.config().get());
+        if stats.speed == 0 {
+            return Err(DominionError::InvalidOperation("Fleet cannot move".into()));
+        }
+        let path = FlightPath::direct(
+            origin,
+            destination,
+            stats.speed,
+            u32::try_from(stats.fuel_consumption).unwrap_or(u32::MAX),
+            SECTOR_SIZE,
+        );
+        
+        let cargo = cargo.unwrap_or_default();
+        let cargo_total = cargo.iron.0
+            .checked_add(cargo.deuterium.0)
+            .and_then(|total| total.checked_add(cargo.chronos_crystals.0));
+        if cargo_total.is_none_or(|total| total > stats.cargo_capacity as u128) {
+            return Err(DominionError::CargoExceedsCapacity(stats.cargo_capacity));
+        }
+        let fuel = saturate_u64(path.fuel_required);
+        let entry_fee = self.state.config().get().entry_fee();
+        let debited = cargo
+            .checked_add(&Resources::new(0, fuel as u128, 0))?
+            .checked_add(&entry_fee)?;
+        self.state.debit_resources(&debited)?;
+        
+        let arrival_micros = now_micros
+            .saturating_add(path.estimated_time.max(1).saturating_mul(1_000_000));
+        
+        // Region Chains only see a commitment to the composition until it is revealed
+        let chain_id = self.runtime.chain_id();
+        let salt = generate_salt(&bcs::to_bytes(&(chain_id, fleet_id, now_micros)).unwrap_or_default());
+        let commitment_hash = hex::encode(commit_ship_counts(fleet_id, &fleet.ships, &salt));
+        
+        fleet.status = FleetData::TRAVELING;
+        fleet.dest_x = destination.x;
+        fleet.dest_y = destination.y;
+        fleet.departure_micros = Some(now_micros);
+        fleet.arrival_micros = Some(arrival_micros);
+        fleet.speed = stats.speed;
+        fleet.fuel = fuel;
+        fleet.arrival_confirmed = false;
+        fleet.cargo = cargo;
+        fleet.commitment_hash = commitment_hash.clone();
+        fleet.salt = hex::encode(salt);
+        self.state.fleets_mut().insert(&fleet_id, fleet)
+            .map_err(|_| DominionError::InvalidOperation("Failed to update fleet".into()))?;
+        
+        if let Some(departed_region) = departed_region {
+            self.runtime
+                .call_application(
+                    true,
+                    departed_region.with_abi::<RegionAbi>(),
+                    &RegionOperation::LeaveFleet { fleet_id },
+                )
+                .map_err(|error| DominionError::RegionCallFailed(error.to_string()))?;
+        }
+        self.runtime
+            .call_application(
+                true,
+                region_app.with_abi::<RegionAbi>(),
+                &RegionOperation::EnterFleet {
+                    fleet_id,
+                    position: destination,
+                    commitment_hash,
+                    arrival_micros,
+                    modifiers,
+                },
+            )
+            .map_err(|error| DominionError::RegionCallFailed(error.to_string()))?;
+        Ok(DominionResponse::FleetSent { fleet_id, arrival_micros, debited })
+    }
+    
+    /// Turn a fleet back home from wherever it is now
+    ///
+    /// A fleet still in flight gets back the unspent share of its fuel. Either way
+    /// the Region Chain it was heading to or stationed at is told it left.
+    async fn recall_fleet(&mut self, fleet_id: u64, now_micros: u64) -> Result<DominionResponse, DominionError> {
+        let fleet = self.state.fleets().get(&fleet_id).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read fleet".into()))?
+            .ok_or(DominionError::FleetNotFound(fleet_id))?;
+        let home = Coordinate::new(*self.state.home_x().get(), *self.state.home_y().get());
+        
+        let mut refund = Resources::zero();
+        let region_position = match fleet.status {
+            FleetData::TRAVELING => {
+                let (start_micros, end_micros) = fleet.departure_micros.zip(fleet.arrival_micros)
+                    .unwrap_or((now_micros, now_micros));
+                let total_micros = end_micros.saturating_sub(start_micros).max(1);
+                let remaining_micros = end_micros.saturating_sub(now_micros).min(total_micros);
+                let unspent = fleet.fuel as u128 * remaining_micros as u128 / total_micros as u128;
+                refund = Resources::new(0, unspent, 0);
+                self.state.credit_resources(&refund)?;
+                Coordinate::new(fleet.dest_x, fleet.dest_y)
+            }
+            FleetData::IDLE if (fleet.current_x, fleet.current_y) != (home.x, home.y) => {
+                Coordinate::new(fleet.current_x, fleet.current_y)
+            }
+            FleetData::IDLE | FleetData::RETURNING => {
+                return Err(DominionError::FleetAlreadyReturning(fleet_id));
+            }
+            _ => return Err(DominionError::FleetNotIdle(fleet_id)),
+        };
+        
+        self.state.return_fleet_home(fleet_id, now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to update fleet".into()))?;
+        let mut arrival_micros = None;
+        if let Some(fleet) = self.state.fleets_mut().get_mut(&fleet_id).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read fleet".into()))? {
+            fleet.fuel = 0;
+            arrival_micros = fleet.arrival_micros.filter(|_| fleet.status == FleetData::RETURNING);
+        }
+        
+        let region_app = self.region_app(region_position).await?;
+        self.runtime
+            .call_application(
+                true,
+                region_app.with_abi::<RegionAbi>(),
+                &RegionOperation::LeaveFleet { fleet_id },
+            )
+            .map_err(|error| DominionError::RegionCallFailed(error.to_string()))?;
+        Ok(DominionResponse::FleetRecalled { fleet_id, arrival_micros, refund })
+    }
+    
+    /// Stake a claim on the planet where a fleet with a Colonizer is stationed
+    ///
+    /// The Region Chain checks the claim again, confirming it with
+    /// `PlanetStaked` or handing the stake back with `StakeReturn`.
+    async fn claim_planet(
+        &mut self,
+        fleet_id: u64,
+        planet_id: u64,
+        stake: Resources,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        let fleet = self.load_idle_fleet(fleet_id).await?;
+        if fleet.ships.get(ShipType::Colonizer as usize).is_none_or(|count| *count == 0) {
+            return Err(DominionError::NoColonizer(fleet_id));
+        }
+        if stake.total().is_none_or(|total| total < MINIMUM_PLANET_STAKE) {
+            return Err(DominionError::InsufficientStake(MINIMUM_PLANET_STAKE));
+        }
+        let position = Coordinate::new(fleet.current_x, fleet.current_y);
+        if self.state.planets().contains_key(&(position.x, position.y)).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read planets".into()))?
+        {
+            return Err(DominionError::PlanetAlreadyHeld { x: position.x, y: position.y });
+        }
+        let region_app = self.region_app(position).await?;
+        
+        self.state.debit_resources(&stake)?;
+        self.state.planets_mut()
+            .insert(&(position.x, position.y), PlanetHoldingData {
+                planet_id,
+                status: PlanetHoldingData::PENDING,
+                stake,
+                updated_micros: now_micros,
+            })
+            .map_err(|_| DominionError::InvalidOperation("Failed to record planet".into()))?;
+        
+        self.runtime
+            .call_application(
+                true,
+                region_app.with_abi::<RegionAbi>(),
+                &RegionOperation::ClaimPlanet {
+                    planet_id,
+                    position,
+                    fleet_id,
+                    ship_counts: fleet.ships,
+                    salt: fleet.salt,
+                    stake,
+                },
+            )
+            .map_err(|error| DominionError::RegionCallFailed(error.to_string()))?;
+        Ok(DominionResponse::PlanetClaimRequested { planet_id, stake })
+    }
+    
+    async fn resupply_planet(
+        &mut self,
+        position: Coordinate,
+        additional_stake: Resources,
+    ) -> Result<DominionResponse, DominionError> {
+        let planet_id = self.load_held_planet(position).await?.planet_id;
+        let region_app = self.region_app(position).await?;
+        self.state.debit_resources(&additional_stake)?;
+        self.runtime
+            .call_application(
+                true,
+                region_app.with_abi::<RegionAbi>(),
+                &RegionOperation::ResupplyPlanet { planet_id, position, additional_stake },
+            )
+            .map_err(|error| DominionError::RegionCallFailed(error.to_string()))?;
+        Ok(DominionResponse::PlanetResupplied { planet_id, additional_stake })
+    }
+    
+    async fn abandon_planet(&mut self, position: Coordinate) -> Result<DominionResponse, DominionError> {
+        let planet_id = self.load_held_planet(position).await?.planet_id;
+        let region_app = self.region_app(position).await?;
+        self.runtime
+            .call_application(
+                true,
+                region_app.with_abi::<RegionAbi>(),
+                &RegionOperation::AbandonPlanet { planet_id },
+            )
+            .map_err(|error| DominionError::RegionCallFailed(error.to_string()))?;
+        Ok(DominionResponse::PlanetAbandoned { planet_id })
+    }
+    
+    async fn harvest_planet(&mut self, position: Coordinate) -> Result<DominionResponse, DominionError> {
+        let planet_id = self.load_held_planet(position).await?.planet_id;
+        let region_app = self.region_app(position).await?;
+        self.runtime
+            .call_application(
+                true,
+                region_app.with_abi::<RegionAbi>(),
+                &RegionOperation::HarvestPlanet { planet_id },
+            )
+            .map_err(|error| DominionError::RegionCallFailed(error.to_string()))?;
+        Ok(DominionResponse::PlanetHarvestRequested { planet_id })
+    }
+    
+    /// Ask the Region Chain where an idle fleet is stationed for a debris field there
+    async fn collect_debris(&mut self, fleet_id: u64, debris_id: u64) -> Result<DominionResponse, DominionError> {
+        let fleet = self.load_idle_fleet(fleet_id).await?;
+        let position = Coordinate::new(fleet.current_x, fleet.current_y);
+        let region_app = self.region_app(position).await?;
+        self.runtime
+            .call_application(
+                true,
+                region_app.with_abi::<RegionAbi>(),
+                &RegionOperation::CollectDebris { fleet_id, debris_id },
+            )
+            .map_err(|error| DominionError::RegionCallFailed(error.to_string()))?;
+        Ok(DominionResponse::DebrisCollectionRequested { debris_id })
+    }
+    
+    async fn load_held_planet(&self, position: Coordinate) -> Result<PlanetHoldingData, DominionError> {
+        self.state.planets().get(&(position.x, position.y)).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read planets".into()))?
+            .filter(|planet| planet.status == PlanetHoldingData::HELD)
+            .ok_or(DominionError::PlanetNotHeld { x: position.x, y: position.y })
+    }
+    
+    /// Region application of the sector containing `position`
+    async fn region_app(&self, position: Coordinate) -> Result<ApplicationId, DominionError> {
+        let sector = position.to_sector(SECTOR_SIZE);
+        self.state.regions().get(&(sector.x, sector.y)).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read regions".into()))?
+            .ok_or(DominionError::UnknownRegion { sector_x: sector.x, sector_y: sector.y })
+    }
+    
+    /// Relay an operation to the alliance application on this chain
+    ///
+    /// The alliance application keeps this chain's membership and forwards
+    /// requests to the Alliance Chain on behalf of the signer.
+    fn call_alliance(&mut self, operation: &AllianceOperation) -> Result<(), DominionError> {
+        let alliance_app = (*self.state.alliance_app().get())
+            .ok_or_else(|| DominionError::InvalidOperation("No alliance application configured".into()))?;
+        self.runtime
+            .call_application(true, alliance_app.with_abi::<AllianceAbi>(), operation)
+            .map(|_| ())
+            .map_err(|error| match error {
+                AllianceError::AlreadyMember => DominionError::AlreadyInAlliance,
+                AllianceError::NotMember => DominionError::NotInAlliance,
+                error => DominionError::AllianceCallFailed(error.to_string()),
+            })
+    }
+    
+    /// Apply the notifications the Region Chain of a sector queued for this chain
+    ///
+    /// Draining is explicit so that an operation only pays for the region the
+    /// player asks about, not for every region this chain knows.
+    async fn sync_region(&mut self, sector_x: i64, sector_y: i64, now_micros: u64) -> Result<DominionResponse, DominionError> {
+        let application_id = self.state.regions().get(&(sector_x, sector_y)).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read regions".into()))?
+            .ok_or(DominionError::UnknownRegion { sector_x, sector_y })?;
+        let response = self.runtime
+            .call_application(
+                true,
+                application_id.with_abi::<RegionAbi>(),
+                &RegionOperation::TakeNotifications,
+            )
+            .map_err(|error| DominionError::RegionCallFailed(error.to_string()))?;
+        let RegionResponse::Notifications(notifications) = response else {
+            return Ok(DominionResponse::RegionSynced { notifications: 0 });
+        };
+        let count = notifications.len() as u32;
+        for notification in notifications {
+            self.apply_notification(notification, now_micros).await?;
+        }
+        Ok(DominionResponse::RegionSynced { notifications: count })
+    }
+    
+    /// Apply the notifications the alliance application queued for this chain
+    async fn process_alliance_notifications(&mut self, now_micros: u64) -> Result<(), DominionError> {
+        let Some(alliance_app) = *self.state.alliance_app().get() else {
+            return Ok(());
+        };
+        let response = self.runtime.call_application(
+            true,
+            alliance_app.with_abi::<AllianceAbi>(),
+            &AllianceOperation::TakeNotifications,
+        );
+        if let Ok(AllianceResponse::Notifications(notifications)) = response {
+            for notification in notifications {
+                self.apply_notification(notification, now_micros).await?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Apply the notifications the senate application queued for this chain
+    async fn process_senate_notifications(&mut self, now_micros: u64) -> Result<(), DominionError> {
+        let Some(senate_app) = *self.state.senate_app().get() else {
+            return Ok(());
+        };
+        let response = self.runtime.call_application(
+            true,
+            senate_app.with_abi::<SenateAbi>(),
+            &SenateOperation::TakeNotifications,
+        );
+        if let Ok(SenateResponse::Notifications(notifications)) = response {
+            for notification in notifications {
+                match notification {
+                    // Only the Senate changes the game configuration
+                    DominionMessage::ParameterUpdate { parameter_name, new_value, effective_from } => {
+                        // Takes effect at the next settlement past `effective_from`
+                        self.state.config_mut().get_mut().schedule(&parameter_name, &new_value, effective_from);
+                    }
+                    notification => self.apply_notification(notification, now_micros).await?,
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    async fn apply_notification(
+        &mut self,
+        notification: DominionMessage,
+        now_micros: u64,
+    ) -> Result<(), DominionError> {
+        match notification {
+            DominionMessage::FleetArrivalConfirmed { fleet_id, .. } => {
+                if let Some(fleet) = self.state.fleets_mut().get_mut(&fleet_id).await
+                    .map_err(|_| DominionError::InvalidOperation("Failed to read fleet".into()))? {
+                    fleet.arrival_confirmed = true;
+                }
+            }
+            DominionMessage::FleetReturn { fleet, .. } => {
+                self.state.return_fleet_home(fleet.id, now_micros).await
+                    .map_err(|_| DominionError::InvalidOperation("Failed to update fleet".into()))?;
+            }
+            DominionMessage::ResourceTransfer { resources, .. } => {
+                self.state.credit_resources(&resources)?;
+            }
+            DominionMessage::PlanetStaked { planet_id, position, stake } => {
+                self.state.planets_mut()
+                    .insert(&(position.x, position.y), PlanetHoldingData {
+                        planet_id,
+                        status: PlanetHoldingData::HELD,
+                        stake,
+                        updated_micros: now_micros,
+                    })
+                    .map_err(|_| DominionError::InvalidOperation("Failed to record planet".into()))?;
+            }
+            DominionMessage::StakeReturn { position, resources, .. } => {
+                // Refused claims and resupplies, and abandoned planets, are no longer held
+                self.state.credit_resources(&resources)?;
+                self.state.planets_mut().remove(&(position.x, position.y))
+                    .map_err(|_| DominionError::InvalidOperation("Failed to remove planet".into()))?;
+            }
+            DominionMessage::ParameterUpdate { parameter_name, .. } => {
+                // Parameter updates are only taken from the Senate
+                log::warn!("Ignoring update of {parameter_name} not sent by the Senate");
+            }
+            notification => {
+                log::warn!("Ignoring unsupported notification: {notification:?}");
+            }
+        }
+        Ok(())
+    }
+    
+    /// Propose an alliance or peace to `target_chain`
+    ///
+    /// Alliances need a neutral relation; peace needs a war. A peace proposal
+    /// leaves the relation pending until it is answered or expires.
+    async fn propose(
+        &mut self,
+        target_chain: ChainId,
+        proposal_type: u8,
+        alliance_name: Option<String>,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        if target_chain == self.runtime.chain_id() {
+            return Err(DominionError::InvalidOperation("Cannot propose to yourself".into()));
+        }
+        let sender = self.runtime.authenticated_signer()
+            .ok_or(DominionError::NotAuthorized)?;
+        let chain = target_chain.to_string();
+        let relation = self.state.relation(&chain).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read diplomacy".into()))?;
+        match (proposal_type, relation.status) {
+            (ProposalData::ALLIANCE, DiplomacyData::ALLIED) => return Err(DominionError::AlreadyAllied),
+            (ProposalData::ALLIANCE, DiplomacyData::NEUTRAL) => {}
+            (ProposalData::ALLIANCE, _) => return Err(DominionError::AlreadyAtWar),
+            (_, DiplomacyData::AT_WAR) => {}
+            (_, DiplomacyData::PEACE_PENDING) => return Err(DominionError::ProposalPending),
+            _ => return Err(DominionError::NotAtWar),
+        }
+        
+        // A live proposal blocks a new one; an expired one is replaced
+        let previous = self.state.find_proposal(&chain, proposal_type, true, None).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read proposals".into()))?;
+        if let Some(previous) = previous {
+            let proposal = self.load_proposal(previous).await?;
+            if proposal.expires_micros > now_micros {
+                return Err(DominionError::ProposalPending);
+            }
+            self.state.proposals_mut().remove(&previous)
+                .map_err(|_| DominionError::InvalidOperation("Failed to remove proposal".into()))?;
+        }
+        
+        let expiration_secs = self.state.config().get().diplomacy_proposal_expiration;
+        let expires_micros = now_micros.saturating_add(expiration_secs.saturating_mul(1_000_000));
+        let proposal_id = self.state.add_proposal(ProposalData {
+            proposal_type,
+            counterparty_chain: chain.clone(),
+            outgoing: true,
+            remote_proposal_id: 0,
+            alliance_name: alliance_name.clone(),
+            created_micros: now_micros,
+            expires_micros,
+        })
+        .map_err(|_| DominionError::InvalidOperation("Failed to store proposal".into()))?;
+        
+        let sender_chain = self.runtime.chain_id();
+        let message = match alliance_name {
+            Some(alliance_name) => Message::AllianceProposal {
+                proposal_id,
+                sender,
+                sender_chain,
+                alliance_name,
+                expires_micros,
+            },
+            None => {
+                self.state.set_relation(&chain, DiplomacyData::PEACE_PENDING, None, relation.started_micros)
+                    .map_err(|_| DominionError::InvalidOperation("Failed to update diplomacy".into()))?;
+                Message::PeaceProposal { proposal_id, sender, sender_chain, expires_micros }
+            }
+        };
+        self.runtime.prepare_message(message).send_to(target_chain);
+        Ok(DominionResponse::ProposalSent { proposal_id, expires_micros })
+    }
+    
+    /// Accept or reject an incoming proposal, confirming the answer to its proposer
+    async fn answer_proposal(
+        &mut self,
+        proposal_id: u64,
+        proposal_type: u8,
+        accept: bool,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        let proposal = self.load_proposal(proposal_id).await?;
+        if proposal.outgoing || proposal.proposal_type != proposal_type {
+            return Err(DominionError::ProposalNotFound(proposal_id));
+        }
+        if proposal.expires_micros <= now_micros {
+            return Err(DominionError::ProposalExpired(proposal_id));
+        }
+        let proposer = parse_chain_id(&proposal.counterparty_chain)?;
+        let chain = proposal.counterparty_chain;
+        let remote_proposal_id = proposal.remote_proposal_id;
+        
+        if !accept {
+            self.state.proposals_mut().remove(&proposal_id)
+                .map_err(|_| DominionError::InvalidOperation("Failed to remove proposal".into()))?;
+            self.runtime
+                .prepare_message(Message::AllianceRejected { proposal_id: remote_proposal_id })
+                .send_to(proposer);
+            return Ok(DominionResponse::ProposalAnswered { proposal_id, accepted: false });
+        }
+        
+        let relation = self.state.relation(&chain).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read diplomacy".into()))?;
+        let alliance_name = proposal.alliance_name;
+        let (status, message) = if proposal_type == ProposalData::ALLIANCE {
+            match relation.status {
+                DiplomacyData::NEUTRAL => {}
+                DiplomacyData::ALLIED => return Err(DominionError::AlreadyAllied),
+                _ => return Err(DominionError::AlreadyAtWar),
+            }
+            (
+                DiplomacyData::ALLIED,
+                Message::AllianceAccepted {
+                    proposal_id: remote_proposal_id,
+                    alliance_name: alliance_name.clone().unwrap_or_default(),
+                },
+            )
+        } else {
+            if !relation.is_at_war() {
+                return Err(DominionError::NotAtWar);
+            }
+            (DiplomacyData::NEUTRAL, Message::PeaceAccepted { proposal_id: remote_proposal_id })
+        };
+        
+        self.state.remove_proposals_with(&chain).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to remove proposals".into()))?;
+        self.state.set_relation(&chain, status, alliance_name, now_micros)
+            .map_err(|_| DominionError::InvalidOperation("Failed to update diplomacy".into()))?;
+        self.runtime.prepare_message(message).send_to(proposer);
+        Ok(DominionResponse::ProposalAnswered { proposal_id, accepted: true })
+    }
+    
+    /// Go to war with `target_chain`, even if allied, dropping pending proposals
+    ///
+    /// Costs the war bond set by the Senate.
+    async fn declare_war(&mut self, target_chain: ChainId, now_micros: u64) -> Result<DominionResponse, DominionError> {
+        if target_chain == self.runtime.chain_id() {
+            return Err(DominionError::InvalidOperation("Cannot declare war on yourself".into()));
+        }
+        let aggressor = self.runtime.authenticated_signer()
+            .ok_or(DominionError::NotAuthorized)?;
+        let chain = target_chain.to_string();
+        let relation = self.state.relation(&chain).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read diplomacy".into()))?;
+        if relation.is_at_war() {
+            return Err(DominionError::AlreadyAtWar);
+        }
+        let war_bond = self.state.config().get().war_bond();
+        self.state.debit_resources(&war_bond)?;
+        
+        self.state.remove_proposals_with(&chain).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to remove proposals".into()))?;
+        self.state.set_relation(&chain, DiplomacyData::AT_WAR, None, now_micros)
+            .map_err(|_| DominionError::InvalidOperation("Failed to update diplomacy".into()))?;
+        
+        let aggressor_chain = self.runtime.chain_id();
+        self.runtime
+            .prepare_message(Message::WarDeclared { aggressor, aggressor_chain })
+            .send_to(target_chain);
+        Ok(DominionResponse::Ok)
+    }
+    
+    /// Store an incoming proposal for the player to answer
+    ///
+    /// Proposals that no longer fit the relation (e.g. peace while not at war)
+    /// are dropped; they expire on the proposer's side.
+    async fn receive_proposal(
+        &mut self,
+        proposer: ChainId,
+        proposal_type: u8,
+        remote_proposal_id: u64,
+        alliance_name: Option<String>,
+        expires_micros: u64,
+        now_micros: u64,
+    ) -> Result<(), ViewError> {
+        let chain = proposer.to_string();
+        let relation = self.state.relation(&chain).await?;
+        let fits = match proposal_type {
+            ProposalData::ALLIANCE => relation.status == DiplomacyData::NEUTRAL,
+            _ => relation.is_at_war(),
+        };
+        if !fits || expires_micros <= now_micros {
+            return Ok(());
+        }
+        
+        self.state.add_proposal(ProposalData {
+            proposal_type,
+            counterparty_chain: chain.clone(),
+            outgoing: false,
+            remote_proposal_id,
+            alliance_name,
+            created_micros: now_micros,
+            expires_micros,
+        })?;
+        if proposal_type == ProposalData::PEACE {
+            self.state.set_relation(&chain, DiplomacyData::PEACE_PENDING, None, relation.started_micros)?;
+        }
+        Ok(())
+    }
+    
+    /// Apply the counterparty's acceptance of our proposal
+    ///
+    /// It only holds if the relation is still the one the proposal was made in;
+    /// otherwise the message that changed it is already on its way to them.
+    async fn confirm_proposal(
+        &mut self,
+        acceptor: ChainId,
+        proposal_type: u8,
+        alliance_name: Option<String>,
+        now_micros: u64,
+    ) -> Result<(), ViewError> {
+        let chain = acceptor.to_string();
+        if self.state.find_proposal(&chain, proposal_type, true, None).await?.is_none() {
+            return Ok(());
+        }
+        let relation = self.state.relation(&chain).await?;
+        let status = match proposal_type {
+            ProposalData::ALLIANCE if relation.status == DiplomacyData::NEUTRAL => DiplomacyData::ALLIED,
+            ProposalData::PEACE if relation.is_at_war() => DiplomacyData::NEUTRAL,
+            _ => return Ok(()),
+        };
+        self.state.remove_proposals_with(&chain).await?;
+        self.state.set_relation(&chain, status, alliance_name, now_micros)
+    }
+    
+    async fn load_proposal(&self, proposal_id: u64) -> Result<ProposalData, DominionError> {
+        self.state.proposals().get(&proposal_id).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read proposal".into()))?
+            .ok_or(DominionError::ProposalNotFound(proposal_id))
+    }
+    
+    /// Send an idle fleet against the home of a player we are at war with
+    ///
+    /// The fleet flies there like any other and stays locked until the defender
+    /// resolves the invasion, or until we withdraw it once the defender is overdue.
+    /// Targets outside the fleet's sector need Warp Gates to reach.
+    async fn launch_invasion(
+        &mut self,
+        target_chain: ChainId,
+        fleet_id: u64,
+        target_x: i64,
+        target_y: i64,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        let attacker_chain = self.runtime.chain_id();
+        if target_chain == attacker_chain {
+            return Err(DominionError::InvalidOperation("Cannot invade yourself".into()));
+        }
+        let attacker = self.runtime.authenticated_signer()
+            .ok_or(DominionError::NotAuthorized)?;
+        let relation = self.state.relation(&target_chain.to_string()).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read diplomacy".into()))?;
+        if !relation.is_at_war() {
+            return Err(DominionError::NotAtWar);
+        }
+        
+        let mut fleet = self.load_idle_fleet(fleet_id).await?;
+        let origin = Coordinate::new(fleet.current_x, fleet.current_y);
+        let target = Coordinate::new(target_x, target_y);
+        let warp_gate_level = self.state
+            .operational_building_level(BuildingType::WarpGate as u8, now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read buildings".into()))?;
+        let range = warp_gate_level as u64 * RANGE_PER_WARP_GATE_LEVEL as u64;
+        if origin.to_sector(SECTOR_SIZE).sector_distance(&target.to_sector(SECTOR_SIZE)) > range {
+            return Err(DominionError::TargetOutOfRange { x: target_x, y: target_y });
+        }
+        
+        let modifiers = self.state.tech_modifiers(now_micros).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read research".into()))?;
+        let stats = FleetStats::from_counts(&fleet.ships, &modifiers, self.state.config().get());
+        if stats.speed == 0 {
+            return Err(DominionError::InvalidOperation("Fleet cannot move".into()));
+        }
+        let path = FlightPath::direct(
+            origin,
+            target,
+            stats.speed,
+            u32::try_from(stats.fuel_consumption).unwrap_or(u32::MAX),
+            SECTOR_SIZE,
+        );
+        let debited = Resources::new(0, saturate_u64(path.fuel_required) as u128, 0);
+        self.state.debit_resources(&debited)?;
+        let arrival_micros = now_micros
+            .saturating_add(path.estimated_time.max(1).saturating_mul(1_000_000));
+        let defense_window_secs = self.state.config().get().invasion_defense_window;
+        
+        let fleet_strength = stats.attack;
+        let ship_counts = fleet.ships.clone();
+        
+        let invasion_id = self.state.add_invasion(InvasionData {
+            attacker_chain: attacker_chain.to_string(),
+            defender_chain: target_chain.to_string(),
+            attacking: true,
+            attacker_fleet_id: fleet_id,
+            attacker_ships: ship_counts.clone(),
+            attacker_modifiers: modifiers,
+            target_x,
+            target_y,
+            status: InvasionData::PENDING,
+            attacker_strength: fleet_strength,
+            started_micros: now_micros,
+            defense_ends_micros: arrival_micros.saturating_add(defense_window_secs.saturating_mul(1_000_000)),
+            ..InvasionData::default()
+        })
+        .map_err(|_| DominionError::InvalidOperation("Failed to store invasion".into()))?;
+        fleet.status = FleetData::ATTACKING;
+        self.state.fleets_mut().insert(&fleet_id, fleet)
+            .map_err(|_| DominionError::InvalidOperation("Failed to update fleet".into()))?;
+        
+        self.runtime
+            .prepare_message(Message::InvasionLaunched {
+                invasion_id,
+                attacker,
+                attacker_chain,
+                fleet_strength,
+                ship_counts,
+                modifiers,
+                target_x,
+                target_y,
+                arrival_micros,
+            })
+            .with_tracking()
+            .send_to(target_chain);
+        Ok(DominionResponse::InvasionLaunched { invasion_id, arrival_micros, debited })
+    }
+    
+    /// Commit an idle fleet at home to an incoming invasion
+    async fn defend_invasion(
+        &mut self,
+        invasion_id: u64,
+        fleet_id: u64,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        let mut invasion = self.load_invasion(invasion_id).await?;
+        if invasion.attacking {
+            return Err(DominionError::InvasionNotFound(invasion_id));
+        }
+        if invasion.status != InvasionData::PENDING || invasion.defense_ends_micros <= now_micros {
+            return Err(DominionError::DefenseWindowClosed(invasion_id));
+        }
+        let mut fleet = self.load_idle_fleet(fleet_id).await?;
+        if (fleet.current_x, fleet.current_y) != (*self.state.home_x().get(), *self.state.home_y().get()) {
+            return Err(DominionError::FleetNotAtHome(fleet_id));
+        }
+        
+        fleet.status = FleetData::DEFENDING;
+        self.state.fleets_mut().insert(&fleet_id, fleet)
+            .map_err(|_| DominionError::InvalidOperation("Failed to update fleet".into()))?;
+        invasion.defender_fleet_ids.push(fleet_id);
+        let defense_ends_micros = invasion.defense_ends_micros;
+        self.state.invasions_mut().insert(&invasion_id, invasion)
+            .map_err(|_| DominionError::InvalidOperation("Failed to update invasion".into()))?;
+        Ok(DominionResponse::InvasionDefended { invasion_id, defense_ends_micros })
+    }
+    
+    /// Move the loot of a victorious invasion into the wallet
+    async fn claim_invasion_rewards(&mut self, invasion_id: u64) -> Result<DominionResponse, DominionError> {
+        let invasion = self.load_invasion(invasion_id).await?;
+        if !invasion.attacking || invasion.status != InvasionData::VICTORY {
+            return Err(DominionError::NoInvasionRewards(invasion_id));
+        }
+        let loot = invasion.loot;
+        self.state.credit_resources(&loot)?;
+        self.state.invasions_mut().remove(&invasion_id)
+            .map_err(|_| DominionError::InvalidOperation("Failed to remove invasion".into()))?;
+        Ok(DominionResponse::InvasionRewardsClaimed { invasion_id, loot })
+    }
+    
+    /// Release the fleet of an invasion the defender left unresolved
+    ///
+    /// The defender gets `OFFLINE_TIMEOUT` past its window to resolve; a result
+    /// arriving after the withdrawal still hands over its loot.
+    async fn withdraw_invasion(&mut self, invasion_id: u64, now_micros: u64) -> Result<DominionResponse, DominionError> {
+        let invasion = self.load_invasion(invasion_id).await?;
+        if !invasion.attacking {
+            return Err(DominionError::InvasionNotFound(invasion_id));
+        }
+        if invasion.status != InvasionData::PENDING {
+            return Err(DominionError::InvalidOperation("Invasion is already resolved".into()));
+        }
+        let overdue_micros = invasion.defense_ends_micros.saturating_add(OFFLINE_TIMEOUT * 1_000_000);
+        if now_micros < overdue_micros {
+            return Err(DominionError::InvasionNotOverdue(invasion_id));
+        }
+        
+        let fleet_id = invasion.attacker_fleet_id;
+        self.state.invasions_mut().remove(&invasion_id)
+            .map_err(|_| DominionError::InvalidOperation("Failed to remove invasion".into()))?;
+        if let Some(fleet) = self.state.fleets_mut().get_mut(&fleet_id).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read fleet".into()))? {
+            fleet.status = FleetData::IDLE;
+        }
+        Ok(DominionResponse::InvasionWithdrawn { invasion_id })
+    }
+    
+    /// Resolve incoming invasions whose defense window has closed
+    async fn resolve_invasions(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        let mut due = Vec::new();
+        self.state.invasions()
+            .for_each_index_value(|invasion_id, invasion| {
+                if !invasion.attacking
+                    && invasion.status == InvasionData::PENDING
+                    && invasion.defense_ends_micros <= now_micros
+                {
+                    due.push(invasion_id);
+                }
+                Ok(())
+            })
+            .await?;
+        for invasion_id in due {
+            self.resolve_invasion(invasion_id, now_micros).await?;
+        }
+        Ok(())
+    }
+    
+    /// Fight an incoming invasion against the committed fleets and the Planetary
+    /// Shield, then report the outcome and any loot to the attacker
+    async fn resolve_invasion(&mut self, invasion_id: u64, now_micros: u64) -> Result<(), ViewError> {
+        let Some(invasion) = self.state.invasions().get(&invasion_id).await? else {
+            return Ok(());
+        };
+        
+        let mut defenders = Vec::new();
+        let mut fleets = Vec::new();
+        for fleet_id in &invasion.defender_fleet_ids {
+            let Some(fleet) = self.state.fleets().get(fleet_id).await? else {
+                continue;
+            };
+            if fleet.status != FleetData::DEFENDING {
+                continue;
+            }
+            if defenders.len() < fleet.ships.len() {
+                defenders.resize(fleet.ships.len(), 0);
+            }
+            for (total, count) in defenders.iter_mut().zip(&fleet.ships) {
+                *total += count;
+            }
+            fleets.push((*fleet_id, fleet));
+        }
+        
+        let modifiers = self.state.tech_modifiers(now_micros).await?;
+        let shield_level = self.state
+            .operational_building_level(BuildingType::PlanetaryShield as u8, now_micros).await?;
+        let shield = TechModifiers::apply(modifiers.hull, shield_level as u64 * PLANETARY_SHIELD_HP_PER_LEVEL);
+        let config = self.state.config().get().clone();
+        let outcome = common_units::resolve_engagement(
+            &invasion.attacker_ships,
+            &invasion.attacker_modifiers,
+            &defenders,
+            &modifiers,
+            shield,
+            &config,
+        );
+        
+        // Spread the losses over the committed fleets in the order they joined
+        let mut losses = outcome.defender_losses.clone();
+        for (fleet_id, mut fleet) in fleets {
+            for (count, lost) in fleet.ships.iter_mut().zip(losses.iter_mut()) {
+                let taken = (*count).min(*lost);
+                *count -= taken;
+                *lost -= taken;
+            }
+            if fleet.total_ships() == 0 {
+                self.state.fleets_mut().remove(&fleet_id)?;
+            } else {
+                fleet.status = FleetData::IDLE;
+                self.state.fleets_mut().insert(&fleet_id, fleet)?;
+            }
+        }
+        
+        let loot = if outcome.attacker_won {
+            let survivors = invasion.attacker_ships.iter()
+                .zip(&outcome.attacker_losses)
+                .map(|(count, lost)| count - lost)
+                .collect::<Vec<_>>();
+            let capacity = FleetStats::from_counts(&survivors, &invasion.attacker_modifiers, &config).cargo_capacity;
+            invasion_loot(&self.state.wallet().get().resources, capacity, config.invasion_loot_percentage)
+        } else {
+            Resources::zero()
+        };
+        self.state.debit_resources(&loot)
+            .expect("Loot is a share of the wallet");
+        
+        // The attacker keeps the record of the outcome
+        let attacker_chain = invasion.attacker_chain.parse::<ChainId>();
+        let remote_invasion_id = invasion.remote_invasion_id;
+        self.state.invasions_mut().remove(&invasion_id)?;
+        
+        if let Ok(attacker_chain) = attacker_chain {
+            self.runtime
+                .prepare_message(Message::InvasionResult {
+                    invasion_id: remote_invasion_id,
+                    attacker_won: outcome.attacker_won,
+                    attacker_losses: outcome.attacker_losses,
+                    defender_losses: outcome.defender_losses,
+                    loot,
+                })
+                .with_tracking()
+                .send_to(attacker_chain);
+        }
+        Ok(())
+    }
+    
+    /// Apply the defender's resolution to our invasion and its fleet
+    ///
+    /// A defeat closes the invasion; a victory is kept until its loot is claimed.
+    /// A withdrawn fleet has already left, so a late result only hands over its loot.
+    async fn record_invasion_result(
+        &mut self,
+        invasion_id: u64,
+        defender: ChainId,
+        outcome: EngagementOutcome,
+        loot: Resources,
+        now_micros: u64,
+    ) -> Result<(), ViewError> {
+        let Some(mut invasion) = self.state.invasions().get(&invasion_id).await? else {
+            return self.state.credit_resources(&loot).map_err(overflow);
+        };
+        if !invasion.attacking
+            || invasion.defender_chain != defender.to_string()
+            || invasion.status != InvasionData::PENDING
+        {
+            return Ok(());
+        }
+        
+        if let Some(mut fleet) = self.state.fleets().get(&invasion.attacker_fleet_id).await? {
+            for (count, lost) in fleet.ships.iter_mut().zip(&outcome.attacker_losses) {
+                *count = count.saturating_sub(*lost);
+            }
+            if fleet.total_ships() == 0 {
+                self.state.fleets_mut().remove(&invasion.attacker_fleet_id)?;
+            } else {
+                fleet.status = FleetData::IDLE;
+                self.state.fleets_mut().insert(&invasion.attacker_fleet_id, fleet)?;
+            }
+        }
+        
+        if !outcome.attacker_won {
+            return self.state.invasions_mut().remove(&invasion_id);
+        }
+        invasion.status = InvasionData::VICTORY;
+        invasion.attacker_losses = outcome.attacker_losses;
+        invasion.defender_losses = outcome.defender_losses;
+        invasion.loot = loot;
+        invasion.resolved_micros = Some(now_micros);
+        self.state.invasions_mut().insert(&invasion_id, invasion)
+    }
+    
+    /// Release the fleet of an invasion the defender's chain refused
+    async fn abort_invasion(&mut self, invasion_id: u64, defender: ChainId) -> Result<(), ViewError> {
+        let Some(invasion) = self.state.invasions().get(&invasion_id).await? else {
+            return Ok(());
+        };
+        if !invasion.attacking || invasion.defender_chain != defender.to_string() {
+            return Ok(());
+        }
+        self.state.invasions_mut().remove(&invasion_id)?;
+        let fleet_id = invasion.attacker_fleet_id;
+        if let Some(fleet) = self.state.fleets_mut().get_mut(&fleet_id).await? {
+            fleet.status = FleetData::IDLE;
+        }
+        Ok(())
+    }
+    
+    async fn load_invasion(&self, invasion_id: u64) -> Result<InvasionData, DominionError> {
+        self.state.invasions().get(&invasion_id).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read invasion".into()))?
+            .ok_or(DominionError::InvasionNotFound(invasion_id))
+    }
+    
+    /// Offer resources to another chain, locking them until the trade settles
+    fn create_trade(
+        &mut self,
+        target_chain: ChainId,
+        offering: Resources,
+        requesting: Resources,
+        now_micros: u64,
+    ) -> Result<DominionResponse, DominionError> {
+        if target_chain == self.runtime.chain_id() {
+            return Err(DominionError::InvalidOperation("Cannot trade with yourself".into()));
+        }
+        if offering.is_zero() && requesting.is_zero() {
+            return Err(DominionError::InvalidOperation("Trade is empty".into()));
+        }
+        let sender = self.runtime.authenticated_signer()
+            .ok_or(DominionError::NotAuthorized)?;
+        
+        self.state.debit_resources(&offering)?;
+        
+        let expiration_secs = self.state.config().get().trade_offer_expiration;
+        let expires_micros = now_micros.saturating_add(expiration_secs.saturating_mul(1_000_000));
+        let offer_id = self.state.add_trade(TradeOfferData {
+            counterparty_chain: target_chain.to_string(),
+            outgoing: true,
+            remote_offer_id: 0,
+            status: TradeOfferData::OPEN,
+            offer: offering,
+            request: requesting,
+            expires_micros,
+        })
+        .map_err(|_| DominionError::InvalidOperation("Failed to store trade".into()))?;
+        
+        let sender_chain = self.runtime.chain_id();
+        self.runtime
+            .prepare_message(Message::TradeOffer {
+                offer_id,
+                sender,
+                sender_chain,
+                offering,
+                requesting,
+                expires_micros,
+            })
+            .with_tracking()
+            .send_to(target_chain);
+        Ok(DominionResponse::TradeCreated { offer_id, expires_micros, escrow: offering })
+    }
+    
+    /// Accept an open incoming offer, locking the requested resources
+    ///
+    /// The maker completes the swap, or refuses it if the offer is gone by then.
+    async fn accept_trade(&mut self, offer_id: u64, now_micros: u64) -> Result<DominionResponse, DominionError> {
+        let trade = self.load_trade(offer_id).await?;
+        if trade.outgoing || !trade.is_open() {
+            return Err(DominionError::TradeNotOpen(offer_id));
+        }
+        if trade.expires_micros <= now_micros {
+            return Err(DominionError::TradeExpired(offer_id));
+        }
+        let maker_chain = parse_chain_id(&trade.counterparty_chain)?;
+        
+        let escrow = trade.request;
+        self.state.debit_resources(&escrow)?;
+        let remote_offer_id = trade.remote_offer_id;
+        self.state.trades_mut().insert(&offer_id, TradeOfferData {
+            status: TradeOfferData::ACCEPTED,
+            ..trade
+        })
+        .map_err(|_| DominionError::InvalidOperation("Failed to update trade".into()))?;
+        
+        self.runtime
+            .prepare_message(Message::TradeAccepted { offer_id: remote_offer_id })
+            .with_tracking()
+            .send_to(maker_chain);
+        Ok(DominionResponse::TradeAccepted { offer_id, escrow })
+    }
+    
+    /// Withdraw an open outgoing offer, refunding it, or decline an open incoming one
+    async fn cancel_trade(&mut self, offer_id: u64) -> Result<DominionResponse, DominionError> {
+        let trade = self.load_trade(offer_id).await?;
+        if !trade.is_open() {
+            return Err(DominionError::TradeNotOpen(offer_id));
+        }
+        let counterparty = parse_chain_id(&trade.counterparty_chain)?;
+        let maker_offer_id = trade.maker_offer_id(offer_id);
+        
+        let (message, refund) = if trade.outgoing {
+            self.state.credit_resources(&trade.offer)?;
+            (Message::TradeCancelled { offer_id: maker_offer_id }, trade.offer)
+        } else {
+            (Message::TradeDeclined { offer_id: maker_offer_id }, Resources::zero())
+        };
+        self.state.trades_mut().remove(&offer_id)
+            .map_err(|_| DominionError::InvalidOperation("Failed to remove trade".into()))?;
+        
+        self.runtime
+            .prepare_message(message)
+            .with_tracking()
+            .send_to(counterparty);
+        Ok(DominionResponse::TradeCancelled { offer_id, refund })
+    }
+    
+    /// Expire open offers past their deadline, refunding our escrow for outgoing ones
+    async fn expire_trades(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        let mut expired = Vec::new();
+        self.state.trades()
+            .for_each_index_value(|trade_id, trade| {
+                if trade.is_open() && trade.expires_micros <= now_micros {
+                    expired.push(trade_id);
+                }
+                Ok(())
+            })
+            .await?;
+        
+        for trade_id in expired {
+            let Some(trade) = self.state.trades().get(&trade_id).await? else {
+                continue;
+            };
+            self.state.trades_mut().remove(&trade_id)?;
+            if !trade.outgoing {
+                continue;
+            }
+            let counterparty = trade.counterparty_chain.parse::<ChainId>();
+            self.state.credit_resources(&trade.offer).map_err(overflow)?;
+            if let Ok(counterparty) = counterparty {
+                self.runtime
+                    .prepare_message(Message::TradeCancelled { offer_id: trade_id })
+                    .with_tracking()
+                    .send_to(counterparty);
+            }
+        }
+        Ok(())
+    }
+    
+    /// Settle an outgoing offer the taker accepted, or refuse it if no longer open
+    ///
+    /// The offer goes to the taker; we keep the record until the taker confirms,
+    /// and only then take the request.
+    async fn settle_trade(&mut self, offer_id: u64, taker: ChainId, now_micros: u64) -> Result<(), ViewError> {
+        let reply = match self.state.trades().get(&offer_id).await? {
+            Some(trade)
+                if trade.outgoing
+                    && trade.counterparty_chain == taker.to_string()
+                    && trade.is_open()
+                    && trade.expires_micros > now_micros =>
+            {
+                self.state.trades_mut().insert(&offer_id, TradeOfferData {
+                    status: TradeOfferData::SETTLING,
+                    ..trade
+                })?;
+                Message::TradeCompleted { offer_id }
+            }
+            _ => Message::TradeCancelled { offer_id },
+        };
+        self.runtime.prepare_message(reply).with_tracking().send_to(taker);
+        Ok(())
+    }
+    
+    /// Take the offer of an incoming trade the maker settled, and release the
+    /// maker's side
+    ///
+    /// Both sides pay the trade fee on what they receive.
+    async fn receive_trade(&mut self, offer_id: u64, maker: ChainId) -> Result<(), ViewError> {
+        let Some(trade_id) = self.state.find_incoming_trade(&maker.to_string(), offer_id).await? else {
+            return Ok(());
+        };
+        let Some(trade) = self.state.trades().get(&trade_id).await? else {
+            return Ok(());
+        };
+        if trade.status != TradeOfferData::ACCEPTED {
+            return Ok(());
+        }
+        let fee = self.state.config().get().trade_fee(&trade.offer);
+        let proceeds = trade.offer.checked_sub(&fee).map_err(overflow)?;
+        self.state.credit_resources(&proceeds).map_err(overflow)?;
+        self.state.trades_mut().remove(&trade_id)?;
+        self.runtime
+            .prepare_message(Message::TradeReceived { offer_id })
+            .with_tracking()
+            .send_to(maker);
+        Ok(())
+    }
+    
+    /// Take the request of a settled offer once the taker received the offer
+    ///
+    /// If the wallet cannot hold it, the record keeps it rather than losing it.
+    async fn complete_trade(&mut self, offer_id: u64, taker: ChainId) -> Result<(), ViewError> {
+        let Some(trade) = self.state.trades().get(&offer_id).await? else {
+            return Ok(());
+        };
+        if !trade.outgoing
+            || trade.counterparty_chain != taker.to_string()
+            || trade.status != TradeOfferData::SETTLING
+        {
+            return Ok(());
+        }
+        let fee = self.state.config().get().trade_fee(&trade.request);
+        let received = trade.request.checked_sub(&fee).map_err(overflow)?;
+        if self.state.credit_resources(&received).is_err() {
+            return Ok(());
+        }
+        self.state.trades_mut().remove(&offer_id)?;
+        Ok(())
+    }
+    
+    /// Undo a settlement the taker refused
+    ///
+    /// Nothing changed hands yet: our offer comes back and the taker gets its
+    /// locked request back. If the wallet cannot hold the offer, the record
+    /// keeps it rather than losing it.
+    async fn unwind_trade(&mut self, offer_id: u64, taker: ChainId) -> Result<(), ViewError> {
+        let Some(trade) = self.state.trades().get(&offer_id).await? else {
+            return Ok(());
+        };
+        if !trade.outgoing
+            || trade.counterparty_chain != taker.to_string()
+            || trade.status != TradeOfferData::SETTLING
+        {
+            return Ok(());
+        }
+        if self.state.credit_resources(&trade.offer).is_err() {
+            return Ok(());
+        }
+        self.state.trades_mut().remove(&offer_id)?;
+        self.runtime
+            .prepare_message(Message::TradeCancelled { offer_id })
+            .with_tracking()
+            .send_to(taker);
+        Ok(())
+    }
+    
+    /// Close an open outgoing offer, refunding our escrow
+    async fn release_outgoing_trade(&mut self, offer_id: u64, taker: ChainId) -> Result<(), ViewError> {
+        if let Some(trade) = self.state.trades().get(&offer_id).await? {
+            if trade.outgoing && trade.counterparty_chain == taker.to_string() && trade.is_open() {
+                self.state.trades_mut().remove(&offer_id)?;
+                self.state.credit_resources(&trade.offer).map_err(overflow)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Cancel an incoming offer the maker withdrew or refused, refunding our locked request
+    async fn release_incoming_trade(&mut self, offer_id: u64, maker: ChainId) -> Result<(), ViewError> {
+        let Some(trade_id) = self.state.find_incoming_trade(&maker.to_string(), offer_id).await? else {
+            return Ok(());
+        };
+        let Some(trade) = self.state.trades().get(&trade_id).await? else {
+            return Ok(());
+        };
+        self.state.trades_mut().remove(&trade_id)?;
+        if trade.status == TradeOfferData::ACCEPTED {
+            self.state.credit_resources(&trade.request).map_err(overflow)?;
+        }
+        Ok(())
+    }
+    
+    async fn load_trade(&self, offer_id: u64) -> Result<TradeOfferData, DominionError> {
+        self.state.trades().get(&offer_id).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read trade".into()))?
+            .ok_or(DominionError::TradeNotFound(offer_id))
+    }
+    
+    async fn check_fleet_limit(&self) -> Result<(), DominionError> {
+        let count = self.state.fleets().count().await
+            .map_err(|_| DominionError::InvalidOperation("Failed to count fleets".into()))?;
+        if count >= MAX_FLEETS_PER_PLAYER {
+            return Err(DominionError::FleetLimitReached(MAX_FLEETS_PER_PLAYER));
+        }
+        Ok(())
+    }
+    
+    fn add_fleet(&mut self, name: Option<String>, ships: Vec<u32>, x: i64, y: i64) -> Result<u64, DominionError> {
+        let fleet_id = *self.state.fleet_count().get();
+        self.state.add_fleet(FleetData {
+            name: name.unwrap_or_else(|| format!("Fleet {}", fleet_id + 1)),
+            ships,
+            status: FleetData::IDLE,
+            current_x: x,
+            current_y: y,
+            dest_x: x,
+            dest_y: y,
+            ..FleetData::default()
+        })
+        .map_err(|_| DominionError::InvalidOperation("Failed to add fleet".into()))
+    }
+    
+    /// Completion time of a started ship order
+    fn ship_order_completion(&self, order_id: u64) -> Option<u64> {
+        self.state.ship_queue().get()
+            .iter()
+            .find(|order| order.id == order_id)
+            .and_then(|order| order.completes_at)
+            .map(|completes_at| completes_at.micros())
+    }
+    
+    async fn load_idle_fleet(&self, fleet_id: u64) -> Result<FleetData, DominionError> {
+        let fleet = self.state.fleets().get(&fleet_id).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read fleet".into()))?
+            .ok_or(DominionError::FleetNotFound(fleet_id))?;
+        if !fleet.is_idle() {
+            return Err(DominionError::FleetNotIdle(fleet_id));
+        }
+        Ok(fleet)
+    }
+    
+    async fn load_building(&self, building_id: u64) -> Result<(BuildingType, BuildingData), DominionError> {
+        let building = self.state.buildings().get(&building_id).await
+            .map_err(|_| DominionError::InvalidOperation("Failed to read building".into()))?
+            .ok_or(DominionError::BuildingNotFound)?;
+        let building_type = BuildingType::from_u8(building.building_type)
+            .ok_or(DominionError::BuildingNotFound)?;
+        Ok((building_type, building))
     }
 }
 
-/// Get ship cost
-fn ship_cost(ship_type: ShipType) -> (u64, u64) {
-    match ship_type {
-        ShipType::Scout => (100, 20),
-        ShipType::Fighter => (200, 50),
-        ShipType::Cruiser => (500, 150),
-        ShipType::Battleship => (2000, 500),
-        ShipType::Carrier => (3000, 1000),
-        ShipType::Freighter => (400, 100),
-        ShipType::Colonizer => (5000, 2000),
-        ShipType::MineLay => (300, 100),
-        ShipType::Destroyer => (800, 200),
-        ShipType::Dreadnought => (10000, 5000),
+/// Loot a victorious invasion carries off: `percentage` of each resource,
+/// scaled down to fit the surviving cargo capacity
+fn invasion_loot(wallet: &Resources, capacity: u64, percentage: u64) -> Resources {
+    let share = wallet.map(|amount| amount * percentage as u128 / 100);
+    let total = share.iron.0
+        .saturating_add(share.deuterium.0)
+        .saturating_add(share.chronos_crystals.0);
+    if total <= capacity as u128 {
+        return share;
+    }
+    share.map(|amount| amount * capacity as u128 / total)
+}
+
+fn parse_chain_id(chain_id: &str) -> Result<ChainId, DominionError> {
+    chain_id.parse()
+        .map_err(|_| DominionError::InvalidOperation("Invalid chain id".into()))
+}
+
+/// Get construction time in seconds for reaching `level`, scaled by the
+/// research construction time modifier (percent)
+fn building_time(config: &GameConfig, building_type: BuildingType, level: u32, time_modifier: u32) -> u64 {
+    TechModifiers::apply(time_modifier, config.building_time(building_type.to_common(), level)).max(1)
+}
+
+fn saturate_u64(value: u128) -> u64 {
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+/// A resource overflow while settling a message, reported as a storage error
+fn overflow(_: ResourceError) -> ViewError {
+    ViewError::ArithmeticError(ArithmeticError::Overflow)
+}
+
+/// Ship counts indexed by ship type, validated against the fleet size limits
+fn ship_counts(ships: &[ShipOrder]) -> Result<Vec<u32>, DominionError> {
+    let mut counts = vec![0u32; common_units::ShipType::all().len()];
+    for order in ships {
+        let count = &mut counts[order.ship_type as usize];
+        *count = count.saturating_add(order.quantity);
+    }
+    
+    let total: u64 = counts.iter().map(|count| *count as u64).sum();
+    if total == 0 {
+        return Err(DominionError::EmptyFleet);
+    }
+    if total > MAX_SHIPS_PER_FLEET as u64 {
+        return Err(DominionError::FleetTooLarge(MAX_SHIPS_PER_FLEET));
+    }
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        linera_base_types::{AccountOwner, CryptoHash, Timestamp},
+        util::BlockingWait,
+    };
+
+    use super::*;
+
+    fn chain(name: &str) -> ChainId {
+        ChainId(CryptoHash::test_hash(name))
+    }
+
+    fn owner(name: &str) -> AccountOwner {
+        AccountOwner::from(CryptoHash::test_hash(name))
+    }
+
+    /// A dominion on `chain_id` trusting no alliance, senate or region
+    fn create_dominion(chain_id: ChainId) -> DominionContract {
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(DominionParameters::default())
+            .with_application_creator_chain_id(chain_id)
+            .with_chain_id(chain_id)
+            .with_authenticated_signer(owner("player"))
+            .with_system_time(Timestamp::from(1_000_000));
+        let mut contract = DominionContract {
+            state: DominionState::load(runtime.root_view_storage_context())
+                .blocking_wait()
+                .expect("Failed to read from mock key value store"),
+            runtime,
+        };
+        contract
+            .instantiate(DominionInstantiationArgument::default())
+            .now_or_never()
+            .expect("Instantiation should not await anything");
+        contract
+    }
+
+    fn execute(contract: &mut DominionContract, operation: Operation) -> Result<DominionResponse, DominionError> {
+        contract.execute_operation(operation).now_or_never().expect("Operation should not await anything")
+    }
+
+    fn receive(contract: &mut DominionContract, origin: ChainId, bouncing: bool, message: Message) {
+        contract.runtime.set_message_origin_chain_id(origin);
+        contract.runtime.set_message_is_bouncing(bouncing);
+        contract.execute_message(message).now_or_never().expect("Message should not await anything");
+    }
+
+    fn offering() -> Resources {
+        Resources::new(300, 0, 0)
+    }
+
+    fn requesting() -> Resources {
+        Resources::new(0, 100, 0)
+    }
+
+    fn wallet(contract: &DominionContract) -> Resources {
+        contract.state.wallet().get().resources
+    }
+
+    /// A maker with an outgoing offer the taker has accepted, and its wallet before settling
+    fn accepted_offer() -> (DominionContract, u64, Resources) {
+        let mut maker = create_dominion(chain("maker"));
+        let response = execute(&mut maker, Operation::CreateTrade {
+            target_chain: chain("taker"),
+            offering: offering(),
+            requesting: requesting(),
+        });
+        let Ok(DominionResponse::TradeCreated { offer_id, .. }) = response else {
+            panic!("The offer should be created: {response:?}");
+        };
+        let before = wallet(&maker);
+        receive(&mut maker, chain("taker"), false, Message::TradeAccepted { offer_id });
+        (maker, offer_id, before)
+    }
+
+    #[test]
+    fn test_maker_paid_once_taker_receives_offer() {
+        let (mut maker, offer_id, before) = accepted_offer();
+        let trade = maker.state.trades().get(&offer_id).blocking_wait().unwrap()
+            .expect("The settling offer should be kept");
+        assert_eq!(trade.status, TradeOfferData::SETTLING);
+        assert_eq!(wallet(&maker), before);
+        {
+            let sent = maker.runtime.created_send_message_requests();
+            assert!(sent.iter().any(|request| {
+                request.destination == chain("taker")
+                    && matches!(request.message, Message::TradeCompleted { offer_id: id } if id == offer_id)
+            }));
+        }
+
+        receive(&mut maker, chain("taker"), false, Message::TradeReceived { offer_id });
+        let fee = maker.state.config().get().trade_fee(&requesting());
+        let received = requesting().checked_sub(&fee).unwrap();
+        assert_eq!(wallet(&maker), before.checked_add(&received).unwrap());
+        assert!(maker.state.trades().get(&offer_id).blocking_wait().unwrap().is_none());
+
+        // A repeated confirmation pays nothing more
+        receive(&mut maker, chain("taker"), false, Message::TradeReceived { offer_id });
+        assert_eq!(wallet(&maker), before.checked_add(&received).unwrap());
+    }
+
+    #[test]
+    fn test_bounced_settlement_refunds_both_sides() {
+        let (mut maker, offer_id, before) = accepted_offer();
+        receive(&mut maker, chain("taker"), true, Message::TradeCompleted { offer_id });
+        assert_eq!(wallet(&maker), before.checked_add(&offering()).unwrap());
+        assert!(maker.state.trades().get(&offer_id).blocking_wait().unwrap().is_none());
+
+        let sent = maker.runtime.created_send_message_requests();
+        assert!(sent.iter().any(|request| {
+            request.destination == chain("taker")
+                && matches!(request.message, Message::TradeCancelled { offer_id: id } if id == offer_id)
+        }));
+    }
+
+    #[test]
+    fn test_taker_receives_offer_and_confirms() {
+        let mut taker = create_dominion(chain("taker"));
+        receive(&mut taker, chain("maker"), false, Message::TradeOffer {
+            offer_id: 4,
+            sender: owner("maker"),
+            sender_chain: chain("maker"),
+            offering: offering(),
+            requesting: requesting(),
+            expires_micros: u64::MAX,
+        });
+        let start = wallet(&taker);
+        execute(&mut taker, Operation::AcceptTrade { offer_id: 0 }).expect("The offer should be open");
+        assert_eq!(wallet(&taker), start.checked_sub(&requesting()).unwrap());
+
+        receive(&mut taker, chain("maker"), false, Message::TradeCompleted { offer_id: 4 });
+        let fee = taker.state.config().get().trade_fee(&offering());
+        let proceeds = offering().checked_sub(&fee).unwrap();
+        assert_eq!(
+            wallet(&taker),
+            start.checked_sub(&requesting()).unwrap().checked_add(&proceeds).unwrap()
+        );
+        assert!(taker.state.trades().get(&0).blocking_wait().unwrap().is_none());
+
+        let sent = taker.runtime.created_send_message_requests();
+        assert!(sent.iter().any(|request| {
+            request.destination == chain("maker") && matches!(request.message, Message::TradeReceived { offer_id: 4 })
+        }));
     }
 }
@@ -7,17 +7,22 @@ pub mod state;
 use async_graphql::{Request, Response, InputObject, SimpleObject, Enum};
 use linera_sdk::{
     graphql::GraphQLMutationRoot,
-    linera_base_types::{AccountOwner, ChainId, Timestamp},
+    linera_base_types::{AccountOwner, ApplicationId, ChainId, Timestamp},
     views::{linera_views, RootView, ViewStorageContext},
 };
+use linera_dominion_common::{
+    config::GameConfig,
+    research::TechModifiers,
+    resources::{ResourceError, Resources},
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 // Re-export state types
 pub use state::{
-    DominionState, WalletData, BuildingData, FleetData, 
-    TradeOfferData, ResearchData, AllianceData,
-    DiplomacyData, ProposalData, InvasionData,
+    DominionState, WalletData, BuildingData, FleetData,
+    TradeOfferData, ResearchData,
+    DiplomacyData, ProposalData, InvasionData, PlanetHoldingData,
 };
 
 // ==================== ENUMS ====================
@@ -37,6 +42,31 @@ pub enum BuildingType {
     SubspaceRelay,
 }
 
+impl BuildingType {
+    /// Decode a building type from its storage discriminant
+    pub fn from_u8(value: u8) -> Option<Self> {
+        const ALL: [BuildingType; 10] = [
+            BuildingType::MinerDrone,
+            BuildingType::GasSiphon,
+            BuildingType::ChronosCollider,
+            BuildingType::Shipyard,
+            BuildingType::WarpGate,
+            BuildingType::PlanetaryShield,
+            BuildingType::ResearchLab,
+            BuildingType::Warehouse,
+            BuildingType::OrbitalCannon,
+            BuildingType::SubspaceRelay,
+        ];
+        ALL.get(value as usize).copied()
+    }
+    
+    /// The shared building type with the same storage discriminant
+    pub fn to_common(self) -> linera_dominion_common::types::BuildingType {
+        linera_dominion_common::types::BuildingType::from_u8(self as u8)
+            .expect("Building types share their discriminants")
+    }
+}
+
 /// Ship types
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Enum, PartialEq, Eq)]
 pub enum ShipType {
@@ -52,6 +82,25 @@ pub enum ShipType {
     Dreadnought,
 }
 
+impl ShipType {
+    /// Decode a ship type from its storage discriminant
+    pub fn from_u8(value: u8) -> Option<Self> {
+        const ALL: [ShipType; 10] = [
+            ShipType::Scout,
+            ShipType::Fighter,
+            ShipType::Cruiser,
+            ShipType::Battleship,
+            ShipType::Carrier,
+            ShipType::Freighter,
+            ShipType::Colonizer,
+            ShipType::MineLay,
+            ShipType::Destroyer,
+            ShipType::Dreadnought,
+        ];
+        ALL.get(value as usize).copied()
+    }
+}
+
 /// Research technologies
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Enum, PartialEq, Eq)]
 pub enum Technology {
@@ -68,16 +117,28 @@ pub enum Technology {
     TemporalMechanics,
 }
 
-// ==================== INPUT TYPES ====================
-
-/// Resource amounts for operations
-#[derive(Debug, Clone, Serialize, Deserialize, InputObject)]
-pub struct ResourceAmount {
-    pub iron: u64,
-    pub deuterium: u64,
-    pub crystals: u64,
+impl Technology {
+    /// Decode a technology from its storage discriminant
+    pub fn from_u8(value: u8) -> Option<Self> {
+        const ALL: [Technology; 11] = [
+            Technology::AdvancedMining,
+            Technology::ReinforcedHulls,
+            Technology::PlasmaWeapons,
+            Technology::IonDrives,
+            Technology::WarpTechnology,
+            Technology::ShieldHarmonics,
+            Technology::NanoConstruction,
+            Technology::ExpandedCargoBays,
+            Technology::LongRangeSensors,
+            Technology::StealthSystems,
+            Technology::TemporalMechanics,
+        ];
+        ALL.get(value as usize).copied()
+    }
 }
 
+// ==================== INPUT TYPES ====================
+
 /// Coordinate input
 #[derive(Debug, Clone, Serialize, Deserialize, InputObject)]
 pub struct CoordinateInput {
@@ -98,72 +159,146 @@ pub struct ShipOrder {
 #[derive(Debug, Serialize, Deserialize, GraphQLMutationRoot)]
 pub enum Operation {
     // ===== Building Operations =====
-    /// Construct or upgrade a building
+    /// Construct a building on an empty tile, or upgrade the one already there
     Build {
         building_type: BuildingType,
         x: i64,
         y: i64,
     },
     
-    /// Cancel construction
+    /// Cancel construction, refunding the share of the cost for the remaining build time
     CancelConstruction {
         building_id: u64,
     },
     
+    /// Demolish a building, freeing its tile and salvaging part of its investment
+    DemolishBuilding {
+        building_id: u64,
+    },
+    
     // ===== Research Operations =====
-    /// Start research
+    /// Start researching the next level of a technology
     Research {
         technology: Technology,
     },
     
-    /// Cancel research
+    /// Cancel the current research project, refunding the unspent share of its cost
     CancelResearch,
     
     // ===== Fleet Operations =====
-    /// Build ships
+    /// Queue a ship order; its ships are built in parallel once the shipyard has capacity
     BuildShips {
         ship_type: ShipType,
         quantity: u32,
     },
     
-    /// Create a new fleet
+    /// Cancel a ship order, refunding the share of its cost for the remaining build time
+    CancelShipOrder {
+        order_id: u64,
+    },
+    
+    /// Move a waiting ship order to `position` among the waiting orders (0 = next)
+    ReorderShipOrder {
+        order_id: u64,
+        position: u32,
+    },
+    
+    /// Assemble a new fleet at home from ships in the pool
     CreateFleet {
         ships: Vec<ShipOrder>,
         name: Option<String>,
     },
     
-    /// Disband a fleet
+    /// Detach some ships of an idle fleet into a new fleet at the same position
+    SplitFleet {
+        fleet_id: u64,
+        ships: Vec<ShipOrder>,
+        name: Option<String>,
+    },
+    
+    /// Merge an idle fleet into another idle fleet at the same position
+    MergeFleets {
+        fleet_id: u64,
+        other_fleet_id: u64,
+    },
+    
+    /// Disband an idle fleet at home, returning its ships to the pool
     DisbandFleet {
         fleet_id: u64,
     },
     
-    /// Send fleet to destination
+    /// Send an idle fleet to a destination, announcing it to the Region Chain there
+    ///
+    /// Debits fuel, cargo and the region entry fee.
     SendFleet {
         fleet_id: u64,
         destination_x: i64,
         destination_y: i64,
-        cargo: Option<ResourceAmount>,
+        cargo: Option<Resources>,
     },
     
-    /// Recall fleet
+    /// Turn a fleet back home, from mid-flight or from the region it is stationed in
     RecallFleet {
         fleet_id: u64,
     },
     
+    // ===== Territory Operations =====
+    /// Claim the unowned planet where an idle fleet holding a Colonizer is stationed
+    ///
+    /// The stake leaves the wallet now and comes back if the Region Chain refuses it.
+    ClaimPlanet {
+        fleet_id: u64,
+        planet_id: u64,
+        stake: Resources,
+    },
+    
+    /// Add to the stake of a held planet
+    ResupplyPlanet {
+        x: i64,
+        y: i64,
+        additional_stake: Resources,
+    },
+    
+    /// Give up a held planet; its stake comes back once the Region Chain releases it
+    AbandonPlanet {
+        x: i64,
+        y: i64,
+    },
+    
+    /// Collect the yield of a held planet; it arrives once the Region Chain sends it
+    HarvestPlanet {
+        x: i64,
+        y: i64,
+    },
+    
+    /// Collect a debris field with an idle fleet stationed on it; the salvage
+    /// arrives once the Region Chain sends it
+    CollectDebris {
+        fleet_id: u64,
+        debris_id: u64,
+    },
+    
+    /// Apply what the Region Chain of a sector has sent back for this chain:
+    /// fleet arrivals and returns, claims, harvests and salvage
+    SyncRegion {
+        sector_x: i64,
+        sector_y: i64,
+    },
+    
     // ===== Trade Operations =====
-    /// Create trade offer
+    /// Offer resources to another player, locking them until the trade settles
     CreateTrade {
         target_chain: ChainId,
-        offering: ResourceAmount,
-        requesting: ResourceAmount,
+        offering: Resources,
+        requesting: Resources,
     },
     
-    /// Accept trade offer
+    /// Accept an incoming offer, locking the requested resources until it settles
     AcceptTrade {
         offer_id: u64,
     },
     
-    /// Cancel trade offer
+    /// Withdraw an open outgoing offer, or decline an open incoming one
     CancelTrade {
         offer_id: u64,
     },
@@ -177,6 +312,17 @@ pub enum Operation {
     /// Leave alliance
     LeaveAlliance,
     
+    /// Move resources from the wallet into the alliance treasury
+    DepositToAlliance {
+        resources: Resources,
+    },
+    
+    // ===== Senate Operations =====
+    /// Move crystals from the wallet into a stake in the Senate
+    StakeInSenate {
+        crystals: u64,
+    },
+    
     // ===== Diplomacy Operations =====
     /// Propose alliance with another player
     ProposeAlliance {
@@ -184,7 +330,7 @@ pub enum Operation {
         alliance_name: String,
     },
     
-    /// Accept alliance proposal
+    /// Accept an incoming alliance proposal, allying with its proposer
     AcceptAllianceProposal {
         proposal_id: u64,
     },
@@ -194,12 +340,14 @@ pub enum Operation {
         proposal_id: u64,
     },
     
-    /// Declare war on another player
+    /// Declare war on another player, dropping any pending proposals with them
+    ///
+    /// Costs the war bond, in crystals.
     DeclareWar {
         target_chain: ChainId,
     },
     
-    /// Propose peace treaty
+    /// Propose peace to a player we are at war with
     ProposePeace {
         target_chain: ChainId,
     },
@@ -210,7 +358,9 @@ pub enum Operation {
     },
     
     // ===== Invasion Operations =====
-    /// Launch invasion against another player's territory
+    /// Launch an idle fleet against a player we are at war with, locking it until resolved
+    ///
+    /// The target is the defender's home; the defense window opens when the fleet arrives.
     LaunchInvasion {
         target_chain: ChainId,
         fleet_id: u64,
@@ -218,16 +368,21 @@ pub enum Operation {
         target_y: i64,
     },
     
-    /// Defend against incoming invasion
+    /// Commit an idle fleet at home to an incoming invasion before its defense window ends
     DefendInvasion {
         invasion_id: u64,
         defender_fleet_id: u64,
     },
     
-    /// Claim victory resources after successful invasion
+    /// Move the loot of a victorious invasion into the wallet
     ClaimInvasionRewards {
         invasion_id: u64,
     },
+    
+    /// Release the fleet of an invasion the defender left unresolved past its window
+    WithdrawInvasion {
+        invasion_id: u64,
+    },
 }
 
 // ==================== MESSAGES ====================
@@ -235,44 +390,44 @@ pub enum Operation {
 /// Messages between chains
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Message {
-    /// Trade offer from another player
+    // Trade messages always carry the maker's offer id.
+    
+    /// Trade offer from another player (maker to taker)
     TradeOffer {
         offer_id: u64,
         sender: AccountOwner,
         sender_chain: ChainId,
-        offering_iron: u64,
-        offering_deuterium: u64,
-        offering_crystals: u64,
-        requesting_iron: u64,
-        requesting_deuterium: u64,
-        requesting_crystals: u64,
+        offering: Resources,
+        requesting: Resources,
+        expires_micros: u64,
     },
     
-    /// Trade accepted
+    /// Trade accepted; the requested resources are locked (taker to maker)
     TradeAccepted {
         offer_id: u64,
     },
     
-    /// Trade rejected/cancelled
-    TradeCancelled {
+    /// Trade settled; the offered resources are released (maker to taker)
+    ///
+    /// The maker keeps the request locked until the taker confirms, so a
+    /// bounce returns both sides' resources.
+    TradeCompleted {
         offer_id: u64,
     },
     
-    /// Fleet arriving in region
-    FleetArrival {
-        fleet_id: u64,
-        owner: AccountOwner,
-        owner_chain: ChainId,
+    /// Offer received; the locked request is released (taker to maker)
+    TradeReceived {
+        offer_id: u64,
+    },
+    
+    /// Offer withdrawn, expired, or acceptance refused (maker to taker)
+    TradeCancelled {
+        offer_id: u64,
     },
     
-    /// Battle result notification
-    BattleResult {
-        battle_id: u64,
-        won: bool,
-        surviving_ships: Vec<u32>,
-        resources_gained_iron: u64,
-        resources_gained_deuterium: u64,
-        resources_gained_crystals: u64,
+    /// Offer declined (taker to maker)
+    TradeDeclined {
+        offer_id: u64,
     },
     
     /// Alliance invitation
@@ -288,9 +443,10 @@ pub enum Message {
         sender: AccountOwner,
         sender_chain: ChainId,
         alliance_name: String,
+        expires_micros: u64,
     },
     
-    /// Alliance proposal accepted
+    /// Alliance proposal accepted; the acceptor is now allied with the proposer
     AllianceAccepted {
         proposal_id: u64,
         alliance_name: String,
@@ -312,33 +468,189 @@ pub enum Message {
         proposal_id: u64,
         sender: AccountOwner,
         sender_chain: ChainId,
+        expires_micros: u64,
     },
     
-    /// Peace accepted
+    /// Peace accepted; the acceptor is now neutral toward the proposer
     PeaceAccepted {
         proposal_id: u64,
     },
     
     // ===== Invasion Messages =====
-    /// Invasion launched
+    /// Invasion launched; the defender has a window to commit fleets
     InvasionLaunched {
         invasion_id: u64,
         attacker: AccountOwner,
         attacker_chain: ChainId,
         fleet_strength: u64,
+        ship_counts: Vec<u32>,
+        modifiers: TechModifiers,
         target_x: i64,
         target_y: i64,
+        arrival_micros: u64,
     },
     
-    /// Invasion result
+    /// Invasion resolved by the defender; loot is already taken from its wallet
     InvasionResult {
         invasion_id: u64,
         attacker_won: bool,
-        attacker_losses: u32,
-        defender_losses: u32,
-        loot_iron: u64,
-        loot_deuterium: u64,
-        loot_crystals: u64,
+        attacker_losses: Vec<u32>,
+        defender_losses: Vec<u32>,
+        loot: Resources,
+    },
+}
+
+// ==================== RESPONSES ====================
+
+/// Outcome of a successful operation
+///
+/// Ids are those of the records the operation created or changed; times are
+/// in microseconds since the epoch.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DominionResponse {
+    /// Nothing to report beyond success
+    Ok,
+
+    // ===== Buildings =====
+    ConstructionStarted {
+        building_id: u64,
+        level: u32,
+        completes_micros: u64,
+        cost: Resources,
+    },
+    ConstructionCancelled {
+        building_id: u64,
+        refund: Resources,
+    },
+    BuildingDemolished {
+        building_id: u64,
+        salvage: Resources,
+    },
+
+    // ===== Research =====
+    ResearchStarted {
+        technology: Technology,
+        level: u32,
+        completes_micros: u64,
+        cost: Resources,
+    },
+    ResearchCancelled {
+        technology: Technology,
+        refund: Resources,
+    },
+
+    // ===== Ships and fleets =====
+    /// `completes_micros` is `None` while the order waits for shipyard capacity
+    ShipOrderQueued {
+        order_id: u64,
+        completes_micros: Option<u64>,
+        cost: Resources,
+    },
+    ShipOrderCancelled {
+        order_id: u64,
+        refund: Resources,
+    },
+    ShipOrderMoved {
+        order_id: u64,
+        completes_micros: Option<u64>,
+    },
+    FleetCreated {
+        fleet_id: u64,
+    },
+    /// `fleet_id` is the new fleet holding the detached ships
+    FleetSplit {
+        fleet_id: u64,
+    },
+    FleetsMerged {
+        fleet_id: u64,
+    },
+    FleetDisbanded {
+        fleet_id: u64,
+        cargo: Resources,
+    },
+    /// `debited` is the cargo plus the fuel for the trip
+    FleetSent {
+        fleet_id: u64,
+        arrival_micros: u64,
+        debited: Resources,
+    },
+    FleetRecalled {
+        fleet_id: u64,
+        arrival_micros: Option<u64>,
+        refund: Resources,
+    },
+
+    // ===== Territory =====
+    PlanetClaimRequested {
+        planet_id: u64,
+        stake: Resources,
+    },
+    PlanetResupplied {
+        planet_id: u64,
+        additional_stake: Resources,
+    },
+    PlanetAbandoned {
+        planet_id: u64,
+    },
+    PlanetHarvestRequested {
+        planet_id: u64,
+    },
+    DebrisCollectionRequested {
+        debris_id: u64,
+    },
+    RegionSynced {
+        notifications: u32,
+    },
+
+    // ===== Trade =====
+    TradeCreated {
+        offer_id: u64,
+        expires_micros: u64,
+        escrow: Resources,
+    },
+    TradeAccepted {
+        offer_id: u64,
+        escrow: Resources,
+    },
+    TradeCancelled {
+        offer_id: u64,
+        refund: Resources,
+    },
+
+    // ===== Alliance and Senate =====
+    DepositedToAlliance {
+        resources: Resources,
+    },
+    StakedInSenate {
+        crystals: u64,
+    },
+
+    // ===== Diplomacy =====
+    ProposalSent {
+        proposal_id: u64,
+        expires_micros: u64,
+    },
+    ProposalAnswered {
+        proposal_id: u64,
+        accepted: bool,
+    },
+
+    // ===== Invasions =====
+    InvasionLaunched {
+        invasion_id: u64,
+        arrival_micros: u64,
+        debited: Resources,
+    },
+    InvasionDefended {
+        invasion_id: u64,
+        defense_ends_micros: u64,
+    },
+    InvasionRewardsClaimed {
+        invasion_id: u64,
+        loot: Resources,
+    },
+    InvasionWithdrawn {
+        invasion_id: u64,
     },
 }
 
@@ -350,6 +662,9 @@ pub enum DominionError {
     #[error("Insufficient resources: {0}")]
     InsufficientResources(String),
     
+    #[error("Resource amount overflow")]
+    ResourceOverflow,
+    
     #[error("Building not found")]
     BuildingNotFound,
     
@@ -359,6 +674,12 @@ pub enum DominionError {
     #[error("Trade not found: {0}")]
     TradeNotFound(u64),
     
+    #[error("Trade is not open: {0}")]
+    TradeNotOpen(u64),
+    
+    #[error("Trade offer expired: {0}")]
+    TradeExpired(u64),
+    
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
     
@@ -371,11 +692,155 @@ pub enum DominionError {
     #[error("Not in alliance")]
     NotInAlliance,
     
+    #[error("Already allied with this player")]
+    AlreadyAllied,
+    
+    #[error("Already at war with this player")]
+    AlreadyAtWar,
+    
+    #[error("Not at war with this player")]
+    NotAtWar,
+    
+    #[error("A proposal to this player is already pending")]
+    ProposalPending,
+    
+    #[error("Proposal not found: {0}")]
+    ProposalNotFound(u64),
+    
+    #[error("Proposal expired: {0}")]
+    ProposalExpired(u64),
+    
+    #[error("Invasion not found: {0}")]
+    InvasionNotFound(u64),
+    
+    #[error("Defense window closed for invasion {0}")]
+    DefenseWindowClosed(u64),
+    
+    #[error("No rewards to claim for invasion {0}")]
+    NoInvasionRewards(u64),
+    
+    #[error("Invasion {0} can still be resolved by the defender")]
+    InvasionNotOverdue(u64),
+    
+    #[error("Target ({x}, {y}) is out of range")]
+    TargetOutOfRange { x: i64, y: i64 },
+    
     #[error("Construction in progress")]
     ConstructionInProgress,
     
+    #[error("Building limit reached: {0} per base")]
+    BuildingLimitReached(usize),
+    
+    #[error("Maximum building level reached")]
+    MaxBuildingLevel,
+    
+    #[error("Tile occupied by a different building")]
+    TileOccupied,
+    
+    #[error("Building is not under construction")]
+    NotUnderConstruction,
+    
     #[error("Research in progress")]
     ResearchInProgress,
+    
+    #[error("No research in progress")]
+    NoResearchInProgress,
+    
+    #[error("A Research Lab is required")]
+    NoResearchLab,
+    
+    #[error("A Shipyard is required")]
+    NoShipyard,
+    
+    #[error("Shipyard capacity exceeded: {0} ships at a time")]
+    ShipyardCapacityExceeded(u32),
+    
+    #[error("Ship order not found: {0}")]
+    ShipOrderNotFound(u64),
+    
+    #[error("Ship order already started")]
+    ShipOrderStarted,
+    
+    #[error("Fleet limit reached: {0} per player")]
+    FleetLimitReached(usize),
+    
+    #[error("Fleet too large: {0} ships at most")]
+    FleetTooLarge(usize),
+    
+    #[error("Fleet has no ships")]
+    EmptyFleet,
+    
+    #[error("Not enough {0:?} ships")]
+    InsufficientShips(ShipType),
+    
+    #[error("Fleet is not idle: {0}")]
+    FleetNotIdle(u64),
+    
+    #[error("Fleet is not at home: {0}")]
+    FleetNotAtHome(u64),
+    
+    #[error("Fleets are not at the same position")]
+    FleetsNotColocated,
+    
+    #[error("Fleet is already at its destination")]
+    AlreadyAtDestination,
+    
+    #[error("Fleet is already home or on its way: {0}")]
+    FleetAlreadyReturning(u64),
+    
+    #[error("Fleet has no Colonizer: {0}")]
+    NoColonizer(u64),
+    
+    #[error("Insufficient stake: {0} at least")]
+    InsufficientStake(u128),
+    
+    #[error("A planet at ({x}, {y}) is already held or being claimed")]
+    PlanetAlreadyHeld {
+        x: i64,
+        y: i64,
+    },
+    
+    #[error("No planet held at ({x}, {y})")]
+    PlanetNotHeld {
+        x: i64,
+        y: i64,
+    },
+    
+    #[error("Cargo exceeds fleet capacity: {0} at most")]
+    CargoExceedsCapacity(u64),
+    
+    #[error("No Region Chain registered for sector ({sector_x}, {sector_y})")]
+    UnknownRegion {
+        sector_x: i64,
+        sector_y: i64,
+    },
+    
+    #[error("Region Chain call failed: {0}")]
+    RegionCallFailed(String),
+    
+    #[error("Alliance call failed: {0}")]
+    AllianceCallFailed(String),
+    
+    #[error("Senate call failed: {0}")]
+    SenateCallFailed(String),
+    
+    #[error("Technology prerequisite not met: requires {prerequisite:?} level {level}")]
+    TechPrerequisiteNotMet {
+        prerequisite: Technology,
+        level: u32,
+    },
+}
+
+impl From<ResourceError> for DominionError {
+    fn from(error: ResourceError) -> Self {
+        match error {
+            ResourceError::InsufficientResources { required, available } => {
+                DominionError::InsufficientResources(format!("need {required}, have {available}"))
+            }
+            ResourceError::InvalidAmount => DominionError::InvalidOperation(error.to_string()),
+            ResourceError::Overflow => DominionError::ResourceOverflow,
+        }
+    }
 }
 
 // ==================== PARAMETERS ====================
@@ -385,34 +850,57 @@ pub enum DominionError {
 pub struct DominionParameters {
     /// Player name
     pub player_name: String,
-    /// Starting iron amount
-    pub starting_iron: u64,
-    /// Starting deuterium amount  
-    pub starting_deuterium: u64,
-    /// Starting crystals amount
-    pub starting_crystals: u64,
+    /// Starting resources
+    pub starting_resources: Resources,
     /// Home coordinate X
     pub home_x: i64,
     /// Home coordinate Y
     pub home_y: i64,
     /// Universe seed
     pub universe_seed: u64,
+    /// Game configuration in force when the chain starts
+    #[serde(default)]
+    pub config: GameConfig,
 }
 
 impl Default for DominionParameters {
     fn default() -> Self {
         Self {
             player_name: "Commander".to_string(),
-            starting_iron: 500,
-            starting_deuterium: 200,
-            starting_crystals: 50,
+            starting_resources: Resources::new(500, 200, 50),
             home_x: 0,
             home_y: 0,
             universe_seed: 0,
+            config: GameConfig::default(),
         }
     }
 }
 
+/// Applications the chain trusts, fixed when it is created
+///
+/// Notifications are only taken from these applications, so they are never
+/// chosen by an operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DominionInstantiationArgument {
+    /// Alliance application shared by all Alliance Chains
+    #[serde(default)]
+    pub alliance_app: Option<ApplicationId>,
+    /// Senate application, created on the Senate Chain
+    #[serde(default)]
+    pub senate_app: Option<ApplicationId>,
+    /// Region applications, each created on its Region Chain
+    #[serde(default)]
+    pub regions: Vec<RegionApp>,
+}
+
+/// Region application of a sector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionApp {
+    pub sector_x: i64,
+    pub sector_y: i64,
+    pub application_id: ApplicationId,
+}
+
 // ==================== ABI ====================
 
 /// Application ABI
@@ -420,7 +908,7 @@ pub struct DominionAbi;
 
 impl linera_sdk::abi::ContractAbi for DominionAbi {
     type Operation = Operation;
-    type Response = Result<(), DominionError>;
+    type Response = Result<DominionResponse, DominionError>;
 }
 
 impl linera_sdk::abi::ServiceAbi for DominionAbi {
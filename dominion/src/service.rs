@@ -2,17 +2,27 @@
 
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
-use std::sync::Arc;
-use async_graphql::{Object, Request, Response, Schema, EmptySubscription};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use async_graphql::{Object, Request, Response, Schema, EmptySubscription, SimpleObject};
 use linera_sdk::{
     linera_base_types::{WithServiceAbi, ChainId},
     Service, ServiceRuntime,
     views::View,
 };
 use linera_dominion::{
-    DominionAbi, DominionParameters, Operation, BuildingType, ShipType, Technology,
+    DominionAbi, DominionParameters, DominionResponse, Operation, BuildingType, ShipOrder, ShipType,
+    Technology,
+};
+use linera_dominion::state::{
+    DiplomacyData, DominionState, FleetData, InvasionData, PlanetHoldingData, ProposalData, ResearchData,
+    TradeOfferData,
+};
+use linera_dominion_common::{
+    config::GameConfig, coordinates::Coordinate, resources::Resources, types as common_types,
 };
-use linera_dominion::state::DominionState;
 
 pub struct DominionService {
     state: DominionState,
@@ -43,23 +53,167 @@ impl Service for DominionService {
         let name = self.state.name().get().clone();
         let home_x = *self.state.home_x().get();
         let home_y = *self.state.home_y().get();
-        let wallet = self.state.wallet().get().clone();
+        let now_micros = self.runtime.system_time().micros();
+        let wallet = self.state.wallet_at(now_micros).await
+            .expect("Failed to settle production");
         let building_count = *self.state.building_count().get();
         let fleet_count = *self.state.fleet_count().get();
+        let config = self.state.config().get().clone();
+        let operation_count = *self.state.operation_count().get();
+        
+        let mut buildings = Vec::new();
+        self.state.buildings()
+            .for_each_index_value(|building_id, building| {
+                if let Some(building_type) = BuildingType::from_u8(building.building_type) {
+                    let construction_end_micros = building.construction_end_micros
+                        .filter(|end| *end > now_micros);
+                    buildings.push(BuildingView {
+                        building_id,
+                        building_type,
+                        level: building.level,
+                        x: building.x,
+                        y: building.y,
+                        under_construction: construction_end_micros.is_some(),
+                        construction_end_micros,
+                    });
+                }
+                Ok(())
+            })
+            .await
+            .expect("Failed to read buildings");
+        
+        let mut fleets = Vec::new();
+        self.state.fleets()
+            .for_each_index_value(|fleet_id, fleet| {
+                let fleet = fleet.into_owned();
+                fleets.push(FleetView {
+                    fleet_id,
+                    position: fleet.position_at(now_micros),
+                    total_ships: fleet.total_ships(),
+                    fleet,
+                });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read fleets");
+        
+        let mut ship_pool = Vec::new();
+        self.state.ship_pool()
+            .for_each_index_value(|ship_type, count| {
+                if let Some(ship_type) = ShipType::from_u8(ship_type).filter(|_| *count > 0) {
+                    ship_pool.push(ShipCountView { ship_type, count: *count });
+                }
+                Ok(())
+            })
+            .await
+            .expect("Failed to read ship pool");
+        
+        let ship_queue = self.state.ship_queue().get()
+            .iter()
+            .filter_map(|order| Some(ShipOrderView {
+                order_id: order.id,
+                ship_type: ShipType::from_u8(order.ship_type as u8)?,
+                quantity: order.quantity,
+                started_micros: order.started_at.map(|started| started.micros()),
+                completes_micros: order.completes_at.map(|completes| completes.micros()),
+            }))
+            .collect();
+        
+        let mut research = Vec::new();
+        self.state.research()
+            .for_each_index_value(|technology, project| {
+                if let Some(view) = ResearchView::new(technology, &project) {
+                    research.push(view);
+                }
+                Ok(())
+            })
+            .await
+            .expect("Failed to read research");
+        let current_research = self.state.current_research().get()
+            .as_ref()
+            .and_then(|project| ResearchView::new(project.technology_type, project));
+        
+        let mut trades = Vec::new();
+        self.state.trades()
+            .for_each_index_value(|offer_id, trade| {
+                trades.push(TradeView { offer_id, trade: trade.into_owned() });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read trades");
+        
+        let mut proposals = Vec::new();
+        self.state.proposals()
+            .for_each_index_value(|proposal_id, proposal| {
+                proposals.push(ProposalView { proposal_id, proposal: proposal.into_owned() });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read proposals");
+        
+        let mut relations = Vec::new();
+        self.state.diplomacy()
+            .for_each_index_value(|chain, relation| {
+                relations.push(RelationView { chain, relation: relation.into_owned() });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read diplomacy");
+        
+        let mut invasions = Vec::new();
+        self.state.invasions()
+            .for_each_index_value(|invasion_id, invasion| {
+                invasions.push(InvasionView { invasion_id, invasion: invasion.into_owned() });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read invasions");
+        
+        let mut planets = Vec::new();
+        self.state.planets()
+            .for_each_index_value(|(x, y), planet| {
+                planets.push(PlanetView { x, y, planet: planet.into_owned() });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read planets");
+        
+        let mut responses = Vec::new();
+        self.state.responses()
+            .for_each_index_value(|index, response| {
+                responses.push(ResponseView::new(index, response.into_owned()));
+                Ok(())
+            })
+            .await
+            .expect("Failed to read responses");
         
         let query_root = QueryRoot {
             name,
             home_x,
             home_y,
-            iron: wallet.iron,
-            deuterium: wallet.deuterium,
-            crystals: wallet.crystals,
+            resources: wallet.resources,
+            settled_micros: wallet.last_update_micros,
             building_count,
             fleet_count,
+            config,
+            buildings,
+            fleets,
+            ship_pool,
+            ship_queue,
+            research,
+            current_research,
+            trades,
+            proposals,
+            relations,
+            invasions,
+            planets,
+            operation_count,
+            responses,
         };
         
         let mutation_root = MutationRoot {
             runtime: self.runtime.clone(),
+            next_index: AtomicU64::new(operation_count),
         };
         
         let schema = Schema::build(query_root, mutation_root, EmptySubscription)
@@ -72,11 +226,24 @@ struct QueryRoot {
     name: String,
     home_x: i64,
     home_y: i64,
-    iron: u64,
-    deuterium: u64,
-    crystals: u64,
+    resources: Resources,
+    settled_micros: u64,
     building_count: u64,
     fleet_count: u64,
+    config: GameConfig,
+    buildings: Vec<BuildingView>,
+    fleets: Vec<FleetView>,
+    ship_pool: Vec<ShipCountView>,
+    ship_queue: Vec<ShipOrderView>,
+    research: Vec<ResearchView>,
+    current_research: Option<ResearchView>,
+    trades: Vec<TradeView>,
+    proposals: Vec<ProposalView>,
+    relations: Vec<RelationView>,
+    invasions: Vec<InvasionView>,
+    planets: Vec<PlanetView>,
+    operation_count: u64,
+    responses: Vec<ResponseView>,
 }
 
 #[Object]
@@ -93,16 +260,14 @@ impl QueryRoot {
         self.home_y
     }
 
-    async fn iron(&self) -> u64 {
-        self.iron
-    }
-
-    async fn deuterium(&self) -> u64 {
-        self.deuterium
+    /// Wallet with production settled up to now
+    async fn resources(&self) -> &Resources {
+        &self.resources
     }
 
-    async fn crystals(&self) -> u64 {
-        self.crystals
+    /// Time the wallet was settled to
+    async fn settled_micros(&self) -> u64 {
+        self.settled_micros
     }
 
     async fn building_count(&self) -> u64 {
@@ -112,49 +277,544 @@ impl QueryRoot {
     async fn fleet_count(&self) -> u64 {
         self.fleet_count
     }
+
+    /// Game configuration in force, with the Senate changes still to come
+    async fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    async fn buildings(
+        &self,
+        building_type: Option<BuildingType>,
+        under_construction: Option<bool>,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Vec<&BuildingView> {
+        let matching = self.buildings.iter().filter(|view| {
+            building_type.is_none_or(|building_type| view.building_type == building_type)
+                && under_construction.is_none_or(|flag| view.under_construction == flag)
+        });
+        paginate(matching, offset, limit)
+    }
+
+    /// Fleets with their position interpolated to now
+    async fn fleets(&self, status: Option<u8>, offset: Option<usize>, limit: Option<usize>) -> Vec<&FleetView> {
+        let matching = self.fleets
+            .iter()
+            .filter(|view| status.is_none_or(|status| view.fleet.status == status));
+        paginate(matching, offset, limit)
+    }
+
+    async fn fleet(&self, fleet_id: u64) -> Option<&FleetView> {
+        self.fleets.iter().find(|view| view.fleet_id == fleet_id)
+    }
+
+    /// Ships docked at home and not assigned to a fleet
+    async fn ship_pool(&self) -> &[ShipCountView] {
+        &self.ship_pool
+    }
+
+    /// Ship orders in build order, the started ones first
+    async fn ship_queue(&self) -> &[ShipOrderView] {
+        &self.ship_queue
+    }
+
+    /// Completed research levels
+    async fn research(&self, offset: Option<usize>, limit: Option<usize>) -> Vec<&ResearchView> {
+        paginate(self.research.iter(), offset, limit)
+    }
+
+    async fn current_research(&self) -> Option<&ResearchView> {
+        self.current_research.as_ref()
+    }
+
+    async fn trades(
+        &self,
+        status: Option<u8>,
+        outgoing: Option<bool>,
+        counterparty_chain: Option<String>,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Vec<&TradeView> {
+        let matching = self.trades.iter().filter(|view| {
+            status.is_none_or(|status| view.trade.status == status)
+                && outgoing.is_none_or(|outgoing| view.trade.outgoing == outgoing)
+                && counterparty_chain.as_ref().is_none_or(|chain| &view.trade.counterparty_chain == chain)
+        });
+        paginate(matching, offset, limit)
+    }
+
+    /// Alliance and peace proposals waiting for an answer
+    async fn proposals(
+        &self,
+        proposal_type: Option<u8>,
+        outgoing: Option<bool>,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Vec<&ProposalView> {
+        let matching = self.proposals.iter().filter(|view| {
+            proposal_type.is_none_or(|proposal_type| view.proposal.proposal_type == proposal_type)
+                && outgoing.is_none_or(|outgoing| view.proposal.outgoing == outgoing)
+        });
+        paginate(matching, offset, limit)
+    }
+
+    /// Diplomatic relations other than neutral
+    async fn relations(&self, status: Option<u8>, offset: Option<usize>, limit: Option<usize>) -> Vec<&RelationView> {
+        let matching = self.relations
+            .iter()
+            .filter(|view| status.is_none_or(|status| view.relation.status == status));
+        paginate(matching, offset, limit)
+    }
+
+    /// Invasions launched and suffered, newest first
+    async fn invasions(
+        &self,
+        status: Option<u8>,
+        attacking: Option<bool>,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Vec<&InvasionView> {
+        let matching = self.invasions.iter().rev().filter(|view| {
+            status.is_none_or(|status| view.invasion.status == status)
+                && attacking.is_none_or(|attacking| view.invasion.attacking == attacking)
+        });
+        paginate(matching, offset, limit)
+    }
+
+    /// Planets held, and claims the Region Chains have yet to answer
+    async fn planets(&self, status: Option<u8>) -> Vec<&PlanetView> {
+        self.planets
+            .iter()
+            .filter(|view| status.is_none_or(|status| view.planet.status == status))
+            .collect()
+    }
+
+    /// Number of operations executed on this chain
+    async fn operation_count(&self) -> u64 {
+        self.operation_count
+    }
+
+    /// Responses of the latest operations, oldest first
+    async fn responses(&self, since: Option<u64>) -> Vec<&ResponseView> {
+        self.responses
+            .iter()
+            .filter(|view| since.is_none_or(|index| view.index >= index))
+            .collect()
+    }
+
+    /// Response of the operation at `index`, as returned by a mutation
+    async fn response(&self, index: u64) -> Option<&ResponseView> {
+        self.responses.iter().find(|view| view.index == index)
+    }
+
+    /// Research prerequisites of every technology
+    async fn tech_tree(&self) -> Vec<TechTreeNode> {
+        common_types::Technology::all()
+            .iter()
+            .filter_map(|tech| {
+                let prerequisites = tech.prerequisites()
+                    .iter()
+                    .map(|requirement| Some(TechRequirementView {
+                        technology: Technology::from_u8(requirement.technology as u8)?,
+                        level: requirement.level,
+                    }))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(TechTreeNode {
+                    technology: Technology::from_u8(*tech as u8)?,
+                    prerequisites,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Skip `offset` items and keep at most `limit`
+fn paginate<'a, T>(items: impl Iterator<Item = &'a T>, offset: Option<usize>, limit: Option<usize>) -> Vec<&'a T> {
+    items
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// A building and whether it is still being built or upgraded
+///
+/// While under construction `level` is the level being reached.
+#[derive(SimpleObject)]
+struct BuildingView {
+    building_id: u64,
+    building_type: BuildingType,
+    level: u32,
+    x: i64,
+    y: i64,
+    under_construction: bool,
+    construction_end_micros: Option<u64>,
+}
+
+#[derive(SimpleObject)]
+struct FleetView {
+    fleet_id: u64,
+    #[graphql(flatten)]
+    fleet: FleetData,
+    position: Coordinate,
+    total_ships: u64,
+}
+
+#[derive(SimpleObject)]
+struct ShipCountView {
+    ship_type: ShipType,
+    count: u32,
+}
+
+/// A ship order; the times are unset while it waits for shipyard capacity
+#[derive(SimpleObject)]
+struct ShipOrderView {
+    order_id: u64,
+    ship_type: ShipType,
+    quantity: u32,
+    started_micros: Option<u64>,
+    completes_micros: Option<u64>,
+}
+
+#[derive(SimpleObject)]
+struct ResearchView {
+    technology: Technology,
+    level: u32,
+    started_micros: u64,
+    completion_micros: Option<u64>,
+}
+
+impl ResearchView {
+    fn new(technology: u8, project: &ResearchData) -> Option<Self> {
+        Some(Self {
+            technology: Technology::from_u8(technology)?,
+            level: project.level,
+            started_micros: project.started_micros,
+            completion_micros: project.completion_micros,
+        })
+    }
+}
+
+#[derive(SimpleObject)]
+struct TradeView {
+    offer_id: u64,
+    #[graphql(flatten)]
+    trade: TradeOfferData,
+}
+
+#[derive(SimpleObject)]
+struct ProposalView {
+    proposal_id: u64,
+    #[graphql(flatten)]
+    proposal: ProposalData,
+}
+
+/// Relation with the player on `chain`
+#[derive(SimpleObject)]
+struct RelationView {
+    chain: String,
+    #[graphql(flatten)]
+    relation: DiplomacyData,
+}
+
+#[derive(SimpleObject)]
+struct InvasionView {
+    invasion_id: u64,
+    #[graphql(flatten)]
+    invasion: InvasionData,
+}
+
+#[derive(SimpleObject)]
+struct PlanetView {
+    x: i64,
+    y: i64,
+    #[graphql(flatten)]
+    planet: PlanetHoldingData,
+}
+
+/// A technology and the levels required before it can be researched
+#[derive(SimpleObject)]
+struct TechTreeNode {
+    technology: Technology,
+    prerequisites: Vec<TechRequirementView>,
+}
+
+/// A technology level that must be reached first
+#[derive(SimpleObject)]
+struct TechRequirementView {
+    technology: Technology,
+    level: u32,
+}
+
+/// Response of an operation, flattened for GraphQL
+///
+/// `kind` names the `DominionResponse` variant. `id` is the building, order,
+/// fleet, proposal, trade or invasion the operation created or changed.
+#[derive(SimpleObject, Default)]
+struct ResponseView {
+    index: u64,
+    kind: String,
+    id: Option<u64>,
+    technology: Option<Technology>,
+    level: Option<u32>,
+    /// When the construction, research, ship order or trip completes
+    completes_micros: Option<u64>,
+    expires_micros: Option<u64>,
+    accepted: Option<bool>,
+    /// Resources taken from the wallet
+    debited: Option<Resources>,
+    /// Resources returned to the wallet
+    credited: Option<Resources>,
+    /// Notifications a region sync applied
+    count: Option<u32>,
+}
+
+impl ResponseView {
+    fn new(index: u64, response: DominionResponse) -> Self {
+        let view = Self { index, ..Self::default() };
+        let (kind, view) = match response {
+            DominionResponse::Ok => ("Ok", view),
+            DominionResponse::ConstructionStarted { building_id, level, completes_micros, cost } => (
+                "ConstructionStarted",
+                Self {
+                    id: Some(building_id),
+                    level: Some(level),
+                    completes_micros: Some(completes_micros),
+                    debited: Some(cost),
+                    ..view
+                },
+            ),
+            DominionResponse::ConstructionCancelled { building_id, refund } => (
+                "ConstructionCancelled",
+                Self { id: Some(building_id), credited: Some(refund), ..view },
+            ),
+            DominionResponse::BuildingDemolished { building_id, salvage } => (
+                "BuildingDemolished",
+                Self { id: Some(building_id), credited: Some(salvage), ..view },
+            ),
+            DominionResponse::ResearchStarted { technology, level, completes_micros, cost } => (
+                "ResearchStarted",
+                Self {
+                    technology: Some(technology),
+                    level: Some(level),
+                    completes_micros: Some(completes_micros),
+                    debited: Some(cost),
+                    ..view
+                },
+            ),
+            DominionResponse::ResearchCancelled { technology, refund } => (
+                "ResearchCancelled",
+                Self { technology: Some(technology), credited: Some(refund), ..view },
+            ),
+            DominionResponse::ShipOrderQueued { order_id, completes_micros, cost } => (
+                "ShipOrderQueued",
+                Self { id: Some(order_id), completes_micros, debited: Some(cost), ..view },
+            ),
+            DominionResponse::ShipOrderCancelled { order_id, refund } => (
+                "ShipOrderCancelled",
+                Self { id: Some(order_id), credited: Some(refund), ..view },
+            ),
+            DominionResponse::ShipOrderMoved { order_id, completes_micros } => (
+                "ShipOrderMoved",
+                Self { id: Some(order_id), completes_micros, ..view },
+            ),
+            DominionResponse::FleetCreated { fleet_id } => ("FleetCreated", Self { id: Some(fleet_id), ..view }),
+            DominionResponse::FleetSplit { fleet_id } => ("FleetSplit", Self { id: Some(fleet_id), ..view }),
+            DominionResponse::FleetsMerged { fleet_id } => ("FleetsMerged", Self { id: Some(fleet_id), ..view }),
+            DominionResponse::FleetDisbanded { fleet_id, cargo } => (
+                "FleetDisbanded",
+                Self { id: Some(fleet_id), credited: Some(cargo), ..view },
+            ),
+            DominionResponse::FleetSent { fleet_id, arrival_micros, debited } => (
+                "FleetSent",
+                Self {
+                    id: Some(fleet_id),
+                    completes_micros: Some(arrival_micros),
+                    debited: Some(debited),
+                    ..view
+                },
+            ),
+            DominionResponse::FleetRecalled { fleet_id, arrival_micros, refund } => (
+                "FleetRecalled",
+                Self {
+                    id: Some(fleet_id),
+                    completes_micros: arrival_micros,
+                    credited: Some(refund),
+                    ..view
+                },
+            ),
+            DominionResponse::PlanetClaimRequested { planet_id, stake } => (
+                "PlanetClaimRequested",
+                Self { id: Some(planet_id), debited: Some(stake), ..view },
+            ),
+            DominionResponse::PlanetResupplied { planet_id, additional_stake } => (
+                "PlanetResupplied",
+                Self { id: Some(planet_id), debited: Some(additional_stake), ..view },
+            ),
+            DominionResponse::PlanetAbandoned { planet_id } => (
+                "PlanetAbandoned",
+                Self { id: Some(planet_id), ..view },
+            ),
+            DominionResponse::PlanetHarvestRequested { planet_id } => (
+                "PlanetHarvestRequested",
+                Self { id: Some(planet_id), ..view },
+            ),
+            DominionResponse::DebrisCollectionRequested { debris_id } => (
+                "DebrisCollectionRequested",
+                Self { id: Some(debris_id), ..view },
+            ),
+            DominionResponse::RegionSynced { notifications } => (
+                "RegionSynced",
+                Self { count: Some(notifications), ..view },
+            ),
+            DominionResponse::TradeCreated { offer_id, expires_micros, escrow } => (
+                "TradeCreated",
+                Self {
+                    id: Some(offer_id),
+                    expires_micros: Some(expires_micros),
+                    debited: Some(escrow),
+                    ..view
+                },
+            ),
+            DominionResponse::TradeAccepted { offer_id, escrow } => (
+                "TradeAccepted",
+                Self { id: Some(offer_id), debited: Some(escrow), ..view },
+            ),
+            DominionResponse::TradeCancelled { offer_id, refund } => (
+                "TradeCancelled",
+                Self { id: Some(offer_id), credited: Some(refund), ..view },
+            ),
+            DominionResponse::DepositedToAlliance { resources } => (
+                "DepositedToAlliance",
+                Self { debited: Some(resources), ..view },
+            ),
+            DominionResponse::StakedInSenate { crystals } => (
+                "StakedInSenate",
+                Self { debited: Some(Resources::crystals(crystals as u128)), ..view },
+            ),
+            DominionResponse::ProposalSent { proposal_id, expires_micros } => (
+                "ProposalSent",
+                Self { id: Some(proposal_id), expires_micros: Some(expires_micros), ..view },
+            ),
+            DominionResponse::ProposalAnswered { proposal_id, accepted } => (
+                "ProposalAnswered",
+                Self { id: Some(proposal_id), accepted: Some(accepted), ..view },
+            ),
+            DominionResponse::InvasionLaunched { invasion_id, arrival_micros, debited } => (
+                "InvasionLaunched",
+                Self {
+                    id: Some(invasion_id),
+                    completes_micros: Some(arrival_micros),
+                    debited: Some(debited),
+                    ..view
+                },
+            ),
+            DominionResponse::InvasionDefended { invasion_id, defense_ends_micros } => (
+                "InvasionDefended",
+                Self { id: Some(invasion_id), completes_micros: Some(defense_ends_micros), ..view },
+            ),
+            DominionResponse::InvasionRewardsClaimed { invasion_id, loot } => (
+                "InvasionRewardsClaimed",
+                Self { id: Some(invasion_id), credited: Some(loot), ..view },
+            ),
+            DominionResponse::InvasionWithdrawn { invasion_id } => (
+                "InvasionWithdrawn",
+                Self { id: Some(invasion_id), ..view },
+            ),
+        };
+        Self { kind: kind.to_string(), ..view }
+    }
 }
 
 // ==================== MUTATIONS ====================
 
+/// Mutations schedule an operation and return the index its response will be
+/// recorded under, valid as long as no other operation executes first
 struct MutationRoot {
     runtime: Arc<ServiceRuntime<DominionService>>,
+    /// Index of the next operation scheduled by this request
+    next_index: AtomicU64,
+}
+
+impl MutationRoot {
+    fn schedule(&self, operation: Operation) -> u64 {
+        self.runtime.schedule_operation(&operation);
+        self.next_index.fetch_add(1, Ordering::Relaxed)
+    }
 }
 
 #[Object]
 impl MutationRoot {
     /// Build a structure at coordinates
-    async fn build(&self, building_type: BuildingType, x: i64, y: i64) -> Vec<u8> {
+    async fn build(&self, building_type: BuildingType, x: i64, y: i64) -> u64 {
         let operation = Operation::Build { building_type, x, y };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     /// Build ships
-    async fn build_ships(&self, ship_type: ShipType, quantity: u32) -> Vec<u8> {
+    async fn build_ships(&self, ship_type: ShipType, quantity: u32) -> u64 {
         let operation = Operation::BuildShips { ship_type, quantity };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
+    }
+    
+    /// Cancel a ship order
+    async fn cancel_ship_order(&self, order_id: u64) -> u64 {
+        let operation = Operation::CancelShipOrder { order_id };
+        self.schedule(operation)
+    }
+    
+    /// Move a waiting ship order within the queue
+    async fn reorder_ship_order(&self, order_id: u64, position: u32) -> u64 {
+        let operation = Operation::ReorderShipOrder { order_id, position };
+        self.schedule(operation)
     }
     
     /// Start research
-    async fn research(&self, technology: Technology) -> Vec<u8> {
+    async fn research(&self, technology: Technology) -> u64 {
         let operation = Operation::Research { technology };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     /// Cancel construction
-    async fn cancel_construction(&self, building_id: u64) -> Vec<u8> {
+    async fn cancel_construction(&self, building_id: u64) -> u64 {
         let operation = Operation::CancelConstruction { building_id };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
+    }
+    
+    /// Demolish a building
+    async fn demolish_building(&self, building_id: u64) -> u64 {
+        let operation = Operation::DemolishBuilding { building_id };
+        self.schedule(operation)
     }
     
     /// Cancel research
-    async fn cancel_research(&self) -> Vec<u8> {
+    async fn cancel_research(&self) -> u64 {
         let operation = Operation::CancelResearch;
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
+    }
+    
+    /// Assemble a fleet from the ship pool
+    async fn create_fleet(&self, ships: Vec<ShipOrder>, name: Option<String>) -> u64 {
+        let operation = Operation::CreateFleet { ships, name };
+        self.schedule(operation)
+    }
+    
+    /// Split ships off a fleet into a new fleet
+    async fn split_fleet(&self, fleet_id: u64, ships: Vec<ShipOrder>, name: Option<String>) -> u64 {
+        let operation = Operation::SplitFleet { fleet_id, ships, name };
+        self.schedule(operation)
+    }
+    
+    /// Merge two co-located fleets
+    async fn merge_fleets(&self, fleet_id: u64, other_fleet_id: u64) -> u64 {
+        let operation = Operation::MergeFleets { fleet_id, other_fleet_id };
+        self.schedule(operation)
+    }
+    
+    /// Disband a fleet back into the ship pool
+    async fn disband_fleet(&self, fleet_id: u64) -> u64 {
+        let operation = Operation::DisbandFleet { fleet_id };
+        self.schedule(operation)
     }
     
     /// Send fleet to destination
@@ -163,66 +823,112 @@ impl MutationRoot {
         fleet_id: u64, 
         destination_x: i64, 
         destination_y: i64
-    ) -> Vec<u8> {
+    ) -> u64 {
         let operation = Operation::SendFleet { 
             fleet_id, 
             destination_x, 
             destination_y,
             cargo: None,
         };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     /// Recall fleet
-    async fn recall_fleet(&self, fleet_id: u64) -> Vec<u8> {
+    async fn recall_fleet(&self, fleet_id: u64) -> u64 {
         let operation = Operation::RecallFleet { fleet_id };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
+    }
+    
+    // ===== Territory Mutations =====
+    
+    /// Claim the planet where a fleet holding a Colonizer is stationed
+    async fn claim_planet(&self, fleet_id: u64, planet_id: u64, stake: Resources) -> u64 {
+        let operation = Operation::ClaimPlanet { fleet_id, planet_id, stake };
+        self.schedule(operation)
+    }
+    
+    /// Add to the stake of a held planet
+    async fn resupply_planet(&self, x: i64, y: i64, additional_stake: Resources) -> u64 {
+        let operation = Operation::ResupplyPlanet { x, y, additional_stake };
+        self.schedule(operation)
+    }
+    
+    /// Give up a held planet, getting its stake back
+    async fn abandon_planet(&self, x: i64, y: i64) -> u64 {
+        let operation = Operation::AbandonPlanet { x, y };
+        self.schedule(operation)
+    }
+    
+    /// Collect the yield accrued by a held planet
+    async fn harvest_planet(&self, x: i64, y: i64) -> u64 {
+        let operation = Operation::HarvestPlanet { x, y };
+        self.schedule(operation)
+    }
+    
+    /// Collect a debris field with an idle fleet stationed on it
+    async fn collect_debris(&self, fleet_id: u64, debris_id: u64) -> u64 {
+        let operation = Operation::CollectDebris { fleet_id, debris_id };
+        self.schedule(operation)
+    }
+    
+    /// Apply what the Region Chain of a sector has sent back for this chain
+    async fn sync_region(&self, sector_x: i64, sector_y: i64) -> u64 {
+        let operation = Operation::SyncRegion { sector_x, sector_y };
+        self.schedule(operation)
+    }
+    
+    // ===== Alliance Mutations =====
+    
+    /// Move resources from the wallet into the alliance treasury
+    async fn deposit_to_alliance(&self, resources: Resources) -> u64 {
+        let operation = Operation::DepositToAlliance { resources };
+        self.schedule(operation)
+    }
+    
+    // ===== Senate Mutations =====
+    
+    /// Move crystals from the wallet into a stake in the Senate
+    async fn stake_in_senate(&self, crystals: u64) -> u64 {
+        let operation = Operation::StakeInSenate { crystals };
+        self.schedule(operation)
     }
     
     // ===== Diplomacy Mutations =====
     
     /// Propose alliance with another player
-    async fn propose_alliance(&self, target_chain: ChainId, alliance_name: String) -> Vec<u8> {
+    async fn propose_alliance(&self, target_chain: ChainId, alliance_name: String) -> u64 {
         let operation = Operation::ProposeAlliance { target_chain, alliance_name };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     /// Accept alliance proposal
-    async fn accept_alliance_proposal(&self, proposal_id: u64) -> Vec<u8> {
+    async fn accept_alliance_proposal(&self, proposal_id: u64) -> u64 {
         let operation = Operation::AcceptAllianceProposal { proposal_id };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     /// Reject alliance proposal
-    async fn reject_alliance_proposal(&self, proposal_id: u64) -> Vec<u8> {
+    async fn reject_alliance_proposal(&self, proposal_id: u64) -> u64 {
         let operation = Operation::RejectAllianceProposal { proposal_id };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     /// Declare war on another player
-    async fn declare_war(&self, target_chain: ChainId) -> Vec<u8> {
+    async fn declare_war(&self, target_chain: ChainId) -> u64 {
         let operation = Operation::DeclareWar { target_chain };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     /// Propose peace treaty
-    async fn propose_peace(&self, target_chain: ChainId) -> Vec<u8> {
+    async fn propose_peace(&self, target_chain: ChainId) -> u64 {
         let operation = Operation::ProposePeace { target_chain };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     /// Accept peace treaty
-    async fn accept_peace(&self, proposal_id: u64) -> Vec<u8> {
+    async fn accept_peace(&self, proposal_id: u64) -> u64 {
         let operation = Operation::AcceptPeace { proposal_id };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     // ===== Invasion Mutations =====
@@ -234,28 +940,31 @@ impl MutationRoot {
         fleet_id: u64,
         target_x: i64,
         target_y: i64,
-    ) -> Vec<u8> {
+    ) -> u64 {
         let operation = Operation::LaunchInvasion { 
             target_chain, 
             fleet_id, 
             target_x, 
             target_y 
         };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     /// Defend against incoming invasion
-    async fn defend_invasion(&self, invasion_id: u64, defender_fleet_id: u64) -> Vec<u8> {
+    async fn defend_invasion(&self, invasion_id: u64, defender_fleet_id: u64) -> u64 {
         let operation = Operation::DefendInvasion { invasion_id, defender_fleet_id };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
     }
     
     /// Claim invasion rewards after victory
-    async fn claim_invasion_rewards(&self, invasion_id: u64) -> Vec<u8> {
+    async fn claim_invasion_rewards(&self, invasion_id: u64) -> u64 {
         let operation = Operation::ClaimInvasionRewards { invasion_id };
-        self.runtime.schedule_operation(&operation);
-        vec![]
+        self.schedule(operation)
+    }
+    
+    /// Release the fleet of an invasion the defender never resolved
+    async fn withdraw_invasion(&self, invasion_id: u64) -> u64 {
+        let operation = Operation::WithdrawInvasion { invasion_id };
+        self.schedule(operation)
     }
 }
@@ -2,21 +2,37 @@
 
 use async_graphql::SimpleObject;
 use linera_sdk::{
-    linera_base_types::{ChainId, Timestamp},
-    views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
+    linera_base_types::{ApplicationId, ArithmeticError, ChainId, Timestamp},
+    views::{linera_views, MapView, RegisterView, RootView, ViewError, ViewStorageContext},
+};
+use std::collections::BTreeMap;
+
+use linera_dominion_common::{
+    config::GameConfig,
+    constants::SHIPS_PER_SHIPYARD_LEVEL,
+    coordinates::{calculate_travel_time, Coordinate},
+    research::TechModifiers,
+    resources::{ProductionRates, ProductionRemainder, ResourceError, Resources},
+    types::BuildingType,
+    units::{ShipConstructionOrder, ShipType},
 };
-use linera_dominion_common::coordinates::Coordinate;
 use serde::{Deserialize, Serialize};
 
+use crate::DominionResponse;
+
+/// Number of operation responses kept for clients to read back
+pub const RESPONSE_HISTORY: u64 = 100;
+
 // ==================== DATA TYPES ====================
 
 /// Wallet data for resource storage
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct WalletData {
-    pub iron: u64,
-    pub deuterium: u64,
-    pub crystals: u64,
+    pub resources: Resources,
     pub last_update_micros: u64,
+    /// Sub-unit production carried to the next settlement
+    #[graphql(skip)]
+    pub production_remainder: ProductionRemainder,
 }
 
 /// Building data
@@ -27,49 +43,131 @@ pub struct BuildingData {
     pub x: i64,
     pub y: i64,
     pub construction_end_micros: Option<u64>,
+    /// Length of the current construction, fixed when it started
+    pub construction_micros: u64,
 }
 
 /// Fleet data
+///
+/// While the fleet is moving, `current_x/y` is where the trip started.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct FleetData {
     pub name: String,
     pub ships: Vec<u32>, // Ship counts by type
-    pub status: u8,      // 0=idle, 1=traveling, 2=attacking, etc.
+    pub status: u8,      // 0=idle, 1=traveling, 2=attacking, 3=returning, 4=defending
     pub current_x: i64,
     pub current_y: i64,
     pub dest_x: i64,
     pub dest_y: i64,
     pub arrival_micros: Option<u64>,
+    pub departure_micros: Option<u64>,
+    /// Tech-adjusted speed for the current trip
+    pub speed: u32,
+    /// Deuterium burned for the current trip
+    pub fuel: u64,
+    /// Whether the destination Region Chain accepted the fleet
+    pub arrival_confirmed: bool,
+    pub cargo: Resources,
+    /// Commitment to the fleet composition announced to Region Chains (hex)
+    pub commitment_hash: String,
+    /// Salt of the commitment, kept for a later reveal (hex)
+    #[graphql(skip)]
+    pub salt: String,
+}
+
+impl FleetData {
+    pub const IDLE: u8 = 0;
+    pub const TRAVELING: u8 = 1;
+    pub const ATTACKING: u8 = 2;
+    pub const RETURNING: u8 = 3;
+    /// Committed to the defense of an invasion
+    pub const DEFENDING: u8 = 4;
+    
+    pub fn is_idle(&self) -> bool {
+        self.status == Self::IDLE
+    }
+    
+    pub fn is_moving(&self) -> bool {
+        self.status == Self::TRAVELING || self.status == Self::RETURNING
+    }
+    
+    /// Position at `now_micros`, interpolated along the current trip
+    pub fn position_at(&self, now_micros: u64) -> Coordinate {
+        let current = Coordinate::new(self.current_x, self.current_y);
+        match (self.is_moving(), self.departure_micros, self.arrival_micros) {
+            (true, Some(departure), Some(arrival)) => current.interpolate(
+                &Coordinate::new(self.dest_x, self.dest_y),
+                now_micros.saturating_sub(departure),
+                arrival.saturating_sub(departure),
+            ),
+            _ => current,
+        }
+    }
+    
+    /// End the current trip at the destination
+    pub fn land(&mut self) {
+        self.status = Self::IDLE;
+        self.current_x = self.dest_x;
+        self.current_y = self.dest_y;
+        self.departure_micros = None;
+        self.arrival_micros = None;
+    }
+    
+    pub fn total_ships(&self) -> u64 {
+        self.ships.iter().map(|count| *count as u64).sum()
+    }
 }
 
 /// Trade offer data
+///
+/// The maker keys an offer by its own id; the taker stores it under a local id
+/// and keeps the maker's in `remote_offer_id`. Each side's share is held out of
+/// its wallet while locked: the offer from creation, the request from acceptance.
+/// The maker's record stays until the taker confirms it received the offer.
+/// Offers are removed once they complete, are cancelled or expire.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct TradeOfferData {
-    pub offer_iron: u64,
-    pub offer_deuterium: u64,
-    pub offer_crystals: u64,
-    pub request_iron: u64,
-    pub request_deuterium: u64,
-    pub request_crystals: u64,
+    pub counterparty_chain: String, // ChainId as string
+    pub outgoing: bool,             // true if this chain made the offer
+    pub remote_offer_id: u64,
+    pub status: u8,                 // 0=open, 1=accepted, 2=settling
+    pub offer: Resources,
+    pub request: Resources,
     pub expires_micros: u64,
 }
 
+impl TradeOfferData {
+    pub const OPEN: u8 = 0;
+    /// Accepted by the taker, whose request is locked until the maker settles
+    pub const ACCEPTED: u8 = 1;
+    /// Settled by the maker, who is waiting for the taker to receive the offer
+    pub const SETTLING: u8 = 2;
+    
+    pub fn is_open(&self) -> bool {
+        self.status == Self::OPEN
+    }
+    
+    /// Offer id the maker uses in trade messages
+    pub fn maker_offer_id(&self, offer_id: u64) -> u64 {
+        if self.outgoing { offer_id } else { self.remote_offer_id }
+    }
+}
+
 /// Research data
+///
+/// In the `research` map `level` is the completed level; for the current
+/// project it is the level being researched.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct ResearchData {
     pub technology_type: u8,
     pub level: u32,
+    pub started_micros: u64,
     pub completion_micros: Option<u64>,
 }
 
-/// Alliance data
-#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
-pub struct AllianceData {
-    pub name: String,
-    pub role: u8, // 0=member, 1=officer, 2=leader
-}
-
 /// Diplomacy status with another player
+///
+/// A counterparty without an entry is neutral.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct DiplomacyData {
     pub status: u8,             // 0=neutral, 1=allied, 2=at_war, 3=peace_pending
@@ -77,34 +175,92 @@ pub struct DiplomacyData {
     pub started_micros: u64,
 }
 
+impl DiplomacyData {
+    pub const NEUTRAL: u8 = 0;
+    pub const ALLIED: u8 = 1;
+    pub const AT_WAR: u8 = 2;
+    /// At war, with a peace proposal waiting for an answer
+    pub const PEACE_PENDING: u8 = 3;
+    
+    pub fn is_at_war(&self) -> bool {
+        self.status == Self::AT_WAR || self.status == Self::PEACE_PENDING
+    }
+}
+
 /// Alliance/Diplomacy proposal
+///
+/// Like trades, outgoing proposals are keyed by their own id and incoming ones
+/// keep the proposer's id in `remote_proposal_id`. Incoming proposals expire;
+/// outgoing ones stay until answered so a late acceptance is still honored.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct ProposalData {
     pub proposal_type: u8,      // 0=alliance, 1=peace
-    pub sender_chain: String,   // ChainId as string
+    pub counterparty_chain: String, // ChainId as string
+    pub outgoing: bool,
+    pub remote_proposal_id: u64,
     pub alliance_name: Option<String>,
     pub created_micros: u64,
     pub expires_micros: u64,
 }
 
+impl ProposalData {
+    pub const ALLIANCE: u8 = 0;
+    pub const PEACE: u8 = 1;
+}
+
 /// Invasion record
+///
+/// Both sides keep one. The attacker keys it by its own id; the defender keeps
+/// the attacker's id in `remote_invasion_id`. Outcomes are from the attacker's
+/// point of view. Records are removed once resolved, except a victory the
+/// attacker keeps until it claims the loot.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct InvasionData {
     pub attacker_chain: String, // ChainId as string
     pub defender_chain: String,
+    pub attacking: bool,        // true on the attacker's chain
+    pub remote_invasion_id: u64,
     pub attacker_fleet_id: u64,
+    pub attacker_ships: Vec<u32>,
+    pub attacker_modifiers: TechModifiers,
+    pub defender_fleet_ids: Vec<u64>,
     pub target_x: i64,
     pub target_y: i64,
     pub status: u8,             // 0=pending, 1=in_progress, 2=victory, 3=defeat
     pub attacker_strength: u64,
-    pub defender_strength: u64,
-    pub loot_iron: u64,
-    pub loot_deuterium: u64,
-    pub loot_crystals: u64,
+    pub attacker_losses: Vec<u32>,
+    pub defender_losses: Vec<u32>,
+    pub loot: Resources,
     pub started_micros: u64,
+    /// End of the defender's window to commit fleets
+    pub defense_ends_micros: u64,
     pub resolved_micros: Option<u64>,
 }
 
+impl InvasionData {
+    pub const PENDING: u8 = 0;
+    pub const IN_PROGRESS: u8 = 1;
+    pub const VICTORY: u8 = 2;
+    pub const DEFEAT: u8 = 3;
+}
+
+/// A planet claimed in a Region Chain
+///
+/// The stake is the Region Chain's figure as of its last confirmation; a claim
+/// stays pending until the Region Chain confirms it or returns the stake.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct PlanetHoldingData {
+    pub planet_id: u64,
+    pub status: u8,             // 0=pending, 1=held
+    pub stake: Resources,
+    pub updated_micros: u64,
+}
+
+impl PlanetHoldingData {
+    pub const PENDING: u8 = 0;
+    pub const HELD: u8 = 1;
+}
+
 // ==================== STATE VIEW ====================
 
 /// Root state for a Dominion (user chain)
@@ -123,23 +279,37 @@ pub struct DominionState {
     buildings: MapView<u64, BuildingData>,
     building_count: RegisterView<u64>,
     
+    /// Building index by tile: key = (x, y), value = building_id
+    building_tiles: MapView<(i64, i64), u64>,
+    
     /// Fleets: key = fleet_id
     fleets: MapView<u64, FleetData>,
     fleet_count: RegisterView<u64>,
     
+    /// Region applications: key = sector (x, y), each created on its Region Chain
+    regions: MapView<(i64, i64), ApplicationId>,
+    
+    /// Alliance and Senate applications the chain takes notifications from
+    alliance_app: RegisterView<Option<ApplicationId>>,
+    senate_app: RegisterView<Option<ApplicationId>>,
+    
     /// Ship pool (available ships not in fleets): key = ship_type as u8
     ship_pool: MapView<u8, u32>,
     
+    /// Ship construction queue: started orders first, then waiting orders in build order
+    ship_queue: RegisterView<Vec<ShipConstructionOrder>>,
+    ship_order_count: RegisterView<u64>,
+    
     /// Research: key = tech_type as u8
     research: MapView<u8, ResearchData>,
     
+    /// Research project occupying the chain's single research slot
+    current_research: RegisterView<Option<ResearchData>>,
+    
     /// Trade offers: key = offer_id
     trades: MapView<u64, TradeOfferData>,
     trade_count: RegisterView<u64>,
     
-    /// Alliance membership
-    alliance: RegisterView<Option<AllianceData>>,
-    
     /// Diplomacy status: key = other player's chain_id as string
     diplomacy: MapView<String, DiplomacyData>,
     
@@ -150,6 +320,16 @@ pub struct DominionState {
     /// Invasion records: key = invasion_id
     invasions: MapView<u64, InvasionData>,
     invasion_count: RegisterView<u64>,
+    
+    /// Planets claimed or held: key = planet (x, y)
+    planets: MapView<(i64, i64), PlanetHoldingData>,
+    
+    /// Game configuration, with the Senate changes not yet in effect
+    config: RegisterView<GameConfig>,
+    
+    /// Responses of the latest operations: key = operation index
+    responses: MapView<u64, DominionResponse>,
+    operation_count: RegisterView<u64>,
 }
 
 // ==================== STATE IMPLEMENTATION ====================
@@ -169,6 +349,10 @@ impl DominionState {
         &self.home_y
     }
     
+    pub fn config(&self) -> &RegisterView<GameConfig> {
+        &self.config
+    }
+    
     pub fn wallet(&self) -> &RegisterView<WalletData> {
         &self.wallet
     }
@@ -181,6 +365,10 @@ impl DominionState {
         &self.building_count
     }
     
+    pub fn building_tiles(&self) -> &MapView<(i64, i64), u64> {
+        &self.building_tiles
+    }
+    
     pub fn fleets(&self) -> &MapView<u64, FleetData> {
         &self.fleets
     }
@@ -189,14 +377,38 @@ impl DominionState {
         &self.fleet_count
     }
     
+    pub fn regions(&self) -> &MapView<(i64, i64), ApplicationId> {
+        &self.regions
+    }
+    
+    pub fn alliance_app(&self) -> &RegisterView<Option<ApplicationId>> {
+        &self.alliance_app
+    }
+    
+    pub fn senate_app(&self) -> &RegisterView<Option<ApplicationId>> {
+        &self.senate_app
+    }
+    
     pub fn ship_pool(&self) -> &MapView<u8, u32> {
         &self.ship_pool
     }
     
+    pub fn ship_queue(&self) -> &RegisterView<Vec<ShipConstructionOrder>> {
+        &self.ship_queue
+    }
+    
+    pub fn ship_order_count(&self) -> &RegisterView<u64> {
+        &self.ship_order_count
+    }
+    
     pub fn research(&self) -> &MapView<u8, ResearchData> {
         &self.research
     }
     
+    pub fn current_research(&self) -> &RegisterView<Option<ResearchData>> {
+        &self.current_research
+    }
+    
     pub fn trades(&self) -> &MapView<u64, TradeOfferData> {
         &self.trades
     }
@@ -205,8 +417,28 @@ impl DominionState {
         &self.trade_count
     }
     
-    pub fn alliance(&self) -> &RegisterView<Option<AllianceData>> {
-        &self.alliance
+    pub fn diplomacy(&self) -> &MapView<String, DiplomacyData> {
+        &self.diplomacy
+    }
+    
+    pub fn proposals(&self) -> &MapView<u64, ProposalData> {
+        &self.proposals
+    }
+    
+    pub fn invasions(&self) -> &MapView<u64, InvasionData> {
+        &self.invasions
+    }
+    
+    pub fn planets(&self) -> &MapView<(i64, i64), PlanetHoldingData> {
+        &self.planets
+    }
+    
+    pub fn responses(&self) -> &MapView<u64, DominionResponse> {
+        &self.responses
+    }
+    
+    pub fn operation_count(&self) -> &RegisterView<u64> {
+        &self.operation_count
     }
     
     // --- Mutable getters ---
@@ -223,6 +455,10 @@ impl DominionState {
         &mut self.home_y
     }
     
+    pub fn config_mut(&mut self) -> &mut RegisterView<GameConfig> {
+        &mut self.config
+    }
+    
     pub fn wallet_mut(&mut self) -> &mut RegisterView<WalletData> {
         &mut self.wallet
     }
@@ -235,6 +471,10 @@ impl DominionState {
         &mut self.building_count
     }
     
+    pub fn building_tiles_mut(&mut self) -> &mut MapView<(i64, i64), u64> {
+        &mut self.building_tiles
+    }
+    
     pub fn fleets_mut(&mut self) -> &mut MapView<u64, FleetData> {
         &mut self.fleets
     }
@@ -243,14 +483,38 @@ impl DominionState {
         &mut self.fleet_count
     }
     
+    pub fn regions_mut(&mut self) -> &mut MapView<(i64, i64), ApplicationId> {
+        &mut self.regions
+    }
+    
+    pub fn alliance_app_mut(&mut self) -> &mut RegisterView<Option<ApplicationId>> {
+        &mut self.alliance_app
+    }
+    
+    pub fn senate_app_mut(&mut self) -> &mut RegisterView<Option<ApplicationId>> {
+        &mut self.senate_app
+    }
+    
     pub fn ship_pool_mut(&mut self) -> &mut MapView<u8, u32> {
         &mut self.ship_pool
     }
     
+    pub fn ship_queue_mut(&mut self) -> &mut RegisterView<Vec<ShipConstructionOrder>> {
+        &mut self.ship_queue
+    }
+    
+    pub fn ship_order_count_mut(&mut self) -> &mut RegisterView<u64> {
+        &mut self.ship_order_count
+    }
+    
     pub fn research_mut(&mut self) -> &mut MapView<u8, ResearchData> {
         &mut self.research
     }
     
+    pub fn current_research_mut(&mut self) -> &mut RegisterView<Option<ResearchData>> {
+        &mut self.current_research
+    }
+    
     pub fn trades_mut(&mut self) -> &mut MapView<u64, TradeOfferData> {
         &mut self.trades
     }
@@ -259,10 +523,6 @@ impl DominionState {
         &mut self.trade_count
     }
     
-    pub fn alliance_mut(&mut self) -> &mut RegisterView<Option<AllianceData>> {
-        &mut self.alliance
-    }
-    
     pub fn diplomacy_mut(&mut self) -> &mut MapView<String, DiplomacyData> {
         &mut self.diplomacy
     }
@@ -283,89 +543,655 @@ impl DominionState {
         &mut self.invasion_count
     }
     
+    pub fn planets_mut(&mut self) -> &mut MapView<(i64, i64), PlanetHoldingData> {
+        &mut self.planets
+    }
+    
     // --- Business logic ---
     
     /// Initialize a new dominion
     pub fn initialize(
         &mut self,
         home: Coordinate,
-        starting_iron: u64,
-        starting_deuterium: u64,
-        starting_crystals: u64,
+        starting_resources: Resources,
         now_micros: u64,
     ) {
         self.home_x.set(home.x);
         self.home_y.set(home.y);
         self.wallet.set(WalletData {
-            iron: starting_iron,
-            deuterium: starting_deuterium,
-            crystals: starting_crystals,
+            resources: starting_resources,
             last_update_micros: now_micros,
+            production_remainder: ProductionRemainder::default(),
         });
         self.building_count.set(0);
         self.fleet_count.set(0);
+        self.ship_order_count.set(0);
         self.trade_count.set(0);
-        self.alliance.set(None);
         self.proposal_count.set(0);
         self.invasion_count.set(0);
     }
     
-    /// Update wallet with time-based resource production
-    pub fn update_wallet(&mut self, now_micros: u64, production_rate_per_hour: u64) {
+    /// Wallet with building production settled up to `now_micros`
+    ///
+    /// Production is integrated piecewise: every construction or research
+    /// project completing between the last settlement and `now_micros` starts a
+    /// new segment with the updated building levels and research modifiers.
+    pub async fn wallet_at(&self, now_micros: u64) -> Result<WalletData, ViewError> {
         let mut wallet = self.wallet.get().clone();
-        let elapsed_micros = now_micros.saturating_sub(wallet.last_update_micros);
-        let hours = elapsed_micros as f64 / (3600.0 * 1_000_000.0);
-        let produced = (hours * production_rate_per_hour as f64) as u64;
+        if now_micros <= wallet.last_update_micros {
+            return Ok(wallet);
+        }
         
-        wallet.iron = wallet.iron.saturating_add(produced);
-        wallet.deuterium = wallet.deuterium.saturating_add(produced / 2);
-        wallet.last_update_micros = now_micros;
+        let producers = self.production_buildings().await?;
+        let research_levels = self.research_levels().await?;
+        let project = self.current_research.get().as_ref();
+        
+        let mut boundaries: Vec<u64> = producers
+            .iter()
+            .filter_map(|(_, _, end)| *end)
+            .chain(project.and_then(|project| project.completion_micros))
+            .filter(|end| *end > wallet.last_update_micros && *end < now_micros)
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        boundaries.push(now_micros);
+        
+        for boundary in boundaries {
+            let start = wallet.last_update_micros;
+            let levels: Vec<(BuildingType, u32)> = producers
+                .iter()
+                .map(|(building_type, level, end)| {
+                    (*building_type, completed_level(*level, *end, start))
+                })
+                .collect();
+            
+            let modifiers = modifiers_at(&research_levels, project, start);
+            
+            let produced = ProductionRates::from_buildings(&levels, self.config.get())
+                .with_modifier(modifiers.production)
+                .accrue(boundary - start, &mut wallet.production_remainder);
+            
+            wallet.resources = wallet.resources.checked_add(&produced)
+                .map_err(|_| ViewError::ArithmeticError(ArithmeticError::Overflow))?;
+            wallet.last_update_micros = boundary;
+        }
         
+        Ok(wallet)
+    }
+    
+    /// Settle resource production up to `now_micros` and persist the wallet
+    pub async fn settle_production(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        let wallet = self.wallet_at(now_micros).await?;
         self.wallet.set(wallet);
+        Ok(())
     }
     
-    /// Debit resources from wallet
-    pub fn debit_resources(
-        &mut self,
-        iron: u64,
-        deuterium: u64,
-        crystals: u64,
-        now_micros: u64,
-    ) -> Result<(), String> {
-        let mut wallet = self.wallet.get().clone();
+    /// Settle everything that progresses with time up to `now_micros`
+    pub async fn settle(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        // Production up to a parameter change uses the values it replaces
+        while let Some(effective_micros) = self.config.get().next_due(now_micros) {
+            self.settle_production(effective_micros).await?;
+            self.config.get_mut().apply_due(effective_micros);
+        }
         
-        if wallet.iron < iron {
-            return Err(format!("Insufficient iron: have {}, need {}", wallet.iron, iron));
+        // Production and the shipyard first: they need the construction and
+        // research end times to replay what happened at each point in time
+        self.settle_production(now_micros).await?;
+        self.complete_ship_orders(now_micros).await?;
+        self.complete_construction(now_micros).await?;
+        self.complete_fleet_movements(now_micros).await?;
+        self.complete_research(now_micros)
+    }
+    
+    /// Mark every construction or upgrade that ended by `now_micros` as complete
+    pub async fn complete_construction(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        let mut completed = Vec::new();
+        self.buildings
+            .for_each_index_value(|building_id, building| {
+                if building.construction_end_micros.is_some_and(|end| end <= now_micros) {
+                    completed.push(building_id);
+                }
+                Ok(())
+            })
+            .await?;
+        
+        for building_id in completed {
+            if let Some(building) = self.buildings.get_mut(&building_id).await? {
+                building.construction_end_micros = None;
+            }
         }
-        if wallet.deuterium < deuterium {
-            return Err(format!("Insufficient deuterium: have {}, need {}", wallet.deuterium, deuterium));
+        Ok(())
+    }
+    
+    /// Deliver every ship order finished by `now_micros` to the ship pool
+    ///
+    /// Capacity freed by a finished order is handed to the waiting orders at
+    /// the moment it frees up.
+    pub async fn complete_ship_orders(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        loop {
+            let mut queue = self.ship_queue.get().clone();
+            let Some(at_micros) = queue
+                .iter()
+                .filter_map(|order| order.completes_at)
+                .map(|completes_at| completes_at.micros())
+                .filter(|end| *end <= now_micros)
+                .min()
+            else {
+                break;
+            };
+            
+            let mut finished = Vec::new();
+            queue.retain(|order| {
+                let done = order.completes_at.is_some_and(|end| end.micros() <= at_micros);
+                if done {
+                    finished.push((order.ship_type, order.quantity));
+                }
+                !done
+            });
+            self.ship_queue.set(queue);
+            
+            for (ship_type, quantity) in finished {
+                self.credit_ships(ship_type as u8, quantity).await?;
+            }
+            self.start_ship_orders(at_micros).await?;
         }
-        if wallet.crystals < crystals {
-            return Err(format!("Insufficient crystals: have {}, need {}", wallet.crystals, crystals));
+        
+        // Shipyard upgrades may have added capacity since the last order finished
+        self.start_ship_orders(now_micros).await
+    }
+    
+    /// Start waiting ship orders, in queue order, while the shipyard has capacity at `at_micros`
+    pub async fn start_ship_orders(&mut self, at_micros: u64) -> Result<(), ViewError> {
+        let capacity = self.shipyard_capacity(at_micros).await?;
+        let time_modifier = self.tech_modifiers(at_micros).await?.construction_time;
+        
+        let mut queue = self.ship_queue.get().clone();
+        let mut used: u32 = queue
+            .iter()
+            .filter(|order| order.is_started())
+            .map(|order| order.quantity)
+            .sum();
+        let mut changed = false;
+        
+        for order in queue.iter_mut().filter(|order| !order.is_started()) {
+            // Orders start strictly in queue order
+            if used.saturating_add(order.quantity) > capacity {
+                break;
+            }
+            used += order.quantity;
+            order.started_at = Some(Timestamp::from(at_micros));
+            order.completes_at = Some(Timestamp::from(
+                at_micros + ship_build_micros(self.config.get(), order.ship_type, time_modifier),
+            ));
+            changed = true;
         }
         
-        wallet.iron -= iron;
-        wallet.deuterium -= deuterium;
-        wallet.crystals -= crystals;
-        wallet.last_update_micros = now_micros;
+        if changed {
+            self.ship_queue.set(queue);
+        }
+        Ok(())
+    }
+    
+    /// Number of ships the shipyard can build in parallel at `at_micros`
+    pub async fn shipyard_capacity(&self, at_micros: u64) -> Result<u32, ViewError> {
+        let level = self
+            .operational_building_level(BuildingType::Shipyard as u8, at_micros)
+            .await?;
+        Ok(level.saturating_mul(SHIPS_PER_SHIPYARD_LEVEL))
+    }
+    
+    /// Add ships to the pool
+    pub async fn credit_ships(&mut self, ship_type: u8, quantity: u32) -> Result<(), ViewError> {
+        let current = self.ship_pool.get(&ship_type).await?.unwrap_or(0);
+        self.ship_pool.insert(&ship_type, current.saturating_add(quantity))
+    }
+    
+    /// Take ships from the pool, returning the first ship type the pool is short of
+    ///
+    /// `ship_counts` is indexed by ship type; nothing is taken if any type is short.
+    pub async fn debit_ships(&mut self, ship_counts: &[u32]) -> Result<Result<(), u8>, ViewError> {
+        let mut remaining = Vec::new();
+        for (ship_type, &count) in ship_counts.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let ship_type = ship_type as u8;
+            let available = self.ship_pool.get(&ship_type).await?.unwrap_or(0);
+            if available < count {
+                return Ok(Err(ship_type));
+            }
+            remaining.push((ship_type, available - count));
+        }
         
-        self.wallet.set(wallet);
+        for (ship_type, count) in remaining {
+            if count == 0 {
+                self.ship_pool.remove(&ship_type)?;
+            } else {
+                self.ship_pool.insert(&ship_type, count)?;
+            }
+        }
+        Ok(Ok(()))
+    }
+    
+    /// Land every fleet whose trip ended by `now_micros`
+    pub async fn complete_fleet_movements(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        let mut arrived = Vec::new();
+        self.fleets
+            .for_each_index_value(|fleet_id, fleet| {
+                if fleet.is_moving() && fleet.arrival_micros.is_some_and(|end| end <= now_micros) {
+                    arrived.push(fleet_id);
+                }
+                Ok(())
+            })
+            .await?;
+        
+        for fleet_id in arrived {
+            if let Some(fleet) = self.fleets.get_mut(&fleet_id).await? {
+                fleet.land();
+            }
+        }
         Ok(())
     }
     
-    /// Credit resources to wallet
-    pub fn credit_resources(
+    /// Turn a fleet back toward home from wherever it is at `now_micros`
+    ///
+    /// Returns whether the fleet exists.
+    pub async fn return_fleet_home(&mut self, fleet_id: u64, now_micros: u64) -> Result<bool, ViewError> {
+        let home = Coordinate::new(*self.home_x.get(), *self.home_y.get());
+        let Some(fleet) = self.fleets.get_mut(&fleet_id).await? else {
+            return Ok(false);
+        };
+        
+        let position = fleet.position_at(now_micros);
+        fleet.current_x = position.x;
+        fleet.current_y = position.y;
+        fleet.dest_x = home.x;
+        fleet.dest_y = home.y;
+        fleet.arrival_confirmed = false;
+        
+        if position == home {
+            fleet.land();
+        } else {
+            let travel_secs = calculate_travel_time(&position, &home, fleet.speed).max(1);
+            fleet.status = FleetData::RETURNING;
+            fleet.departure_micros = Some(now_micros);
+            fleet.arrival_micros = Some(now_micros.saturating_add(travel_secs.saturating_mul(1_000_000)));
+        }
+        Ok(true)
+    }
+    
+    /// Store a new fleet, returning its id
+    pub fn add_fleet(&mut self, fleet: FleetData) -> Result<u64, ViewError> {
+        let fleet_id = *self.fleet_count.get();
+        self.fleets.insert(&fleet_id, fleet)?;
+        self.fleet_count.set(fleet_id + 1);
+        Ok(fleet_id)
+    }
+    
+    /// Store a new trade, returning its id
+    pub fn add_trade(&mut self, trade: TradeOfferData) -> Result<u64, ViewError> {
+        let trade_id = *self.trade_count.get();
+        self.trades.insert(&trade_id, trade)?;
+        self.trade_count.set(trade_id + 1);
+        Ok(trade_id)
+    }
+    
+    /// Store a new invasion record, returning its id
+    pub fn add_invasion(&mut self, invasion: InvasionData) -> Result<u64, ViewError> {
+        let invasion_id = *self.invasion_count.get();
+        self.invasions.insert(&invasion_id, invasion)?;
+        self.invasion_count.set(invasion_id + 1);
+        Ok(invasion_id)
+    }
+    
+    /// Record the response of an operation, returning the operation's index
+    ///
+    /// Only the latest `RESPONSE_HISTORY` responses are kept.
+    pub fn record_response(&mut self, response: DominionResponse) -> Result<u64, ViewError> {
+        let index = *self.operation_count.get();
+        self.responses.insert(&index, response)?;
+        if let Some(expired) = index.checked_sub(RESPONSE_HISTORY) {
+            self.responses.remove(&expired)?;
+        }
+        self.operation_count.set(index + 1);
+        Ok(index)
+    }
+    
+    /// Local id of an incoming invasion, given its attacker's chain and invasion id
+    pub async fn find_incoming_invasion(
+        &self,
+        attacker_chain: &str,
+        remote_invasion_id: u64,
+    ) -> Result<Option<u64>, ViewError> {
+        let mut found = None;
+        self.invasions
+            .for_each_index_value_while(|invasion_id, invasion| {
+                if !invasion.attacking
+                    && invasion.remote_invasion_id == remote_invasion_id
+                    && invasion.attacker_chain == attacker_chain
+                {
+                    found = Some(invasion_id);
+                    return Ok(false);
+                }
+                Ok(true)
+            })
+            .await?;
+        Ok(found)
+    }
+    
+    /// Diplomatic relation with `chain`, neutral if none was recorded
+    pub async fn relation(&self, chain: &str) -> Result<DiplomacyData, ViewError> {
+        Ok(self.diplomacy.get(chain).await?.unwrap_or_default())
+    }
+    
+    /// Move the relation with `chain` to `status`
+    pub fn set_relation(
         &mut self,
-        iron: u64,
-        deuterium: u64,
-        crystals: u64,
+        chain: &str,
+        status: u8,
+        alliance_name: Option<String>,
         now_micros: u64,
-    ) {
-        let mut wallet = self.wallet.get().clone();
-        wallet.iron = wallet.iron.saturating_add(iron);
-        wallet.deuterium = wallet.deuterium.saturating_add(deuterium);
-        wallet.crystals = wallet.crystals.saturating_add(crystals);
-        wallet.last_update_micros = now_micros;
-        self.wallet.set(wallet);
+    ) -> Result<(), ViewError> {
+        if status == DiplomacyData::NEUTRAL {
+            return self.diplomacy.remove(chain);
+        }
+        self.diplomacy.insert(chain, DiplomacyData {
+            status,
+            alliance_name,
+            started_micros: now_micros,
+        })
+    }
+    
+    /// Store a new proposal, returning its id
+    pub fn add_proposal(&mut self, proposal: ProposalData) -> Result<u64, ViewError> {
+        let proposal_id = *self.proposal_count.get();
+        self.proposals.insert(&proposal_id, proposal)?;
+        self.proposal_count.set(proposal_id + 1);
+        Ok(proposal_id)
     }
+    
+    /// Local id of a proposal of `proposal_type` exchanged with `chain`
+    ///
+    /// Incoming proposals are matched on the proposer's id when one is given.
+    pub async fn find_proposal(
+        &self,
+        chain: &str,
+        proposal_type: u8,
+        outgoing: bool,
+        remote_proposal_id: Option<u64>,
+    ) -> Result<Option<u64>, ViewError> {
+        let mut found = None;
+        self.proposals
+            .for_each_index_value_while(|proposal_id, proposal| {
+                if proposal.counterparty_chain == chain
+                    && proposal.proposal_type == proposal_type
+                    && proposal.outgoing == outgoing
+                    && remote_proposal_id.is_none_or(|id| id == proposal.remote_proposal_id)
+                {
+                    found = Some(proposal_id);
+                    return Ok(false);
+                }
+                Ok(true)
+            })
+            .await?;
+        Ok(found)
+    }
+    
+    /// Drop every proposal exchanged with `chain`
+    pub async fn remove_proposals_with(&mut self, chain: &str) -> Result<(), ViewError> {
+        let mut stale = Vec::new();
+        self.proposals
+            .for_each_index_value(|proposal_id, proposal| {
+                if proposal.counterparty_chain == chain {
+                    stale.push(proposal_id);
+                }
+                Ok(())
+            })
+            .await?;
+        for proposal_id in stale {
+            self.proposals.remove(&proposal_id)?;
+        }
+        Ok(())
+    }
+    
+    /// Drop incoming proposals past their deadline
+    ///
+    /// A relation waiting on peace falls back to war once no live peace
+    /// proposal remains in either direction.
+    pub async fn expire_proposals(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        let mut expired = Vec::new();
+        let mut live_peace = Vec::new();
+        self.proposals
+            .for_each_index_value(|proposal_id, proposal| {
+                if proposal.expires_micros <= now_micros {
+                    if !proposal.outgoing {
+                        expired.push(proposal_id);
+                    }
+                } else if proposal.proposal_type == ProposalData::PEACE {
+                    live_peace.push(proposal.counterparty_chain.clone());
+                }
+                Ok(())
+            })
+            .await?;
+        for proposal_id in expired {
+            self.proposals.remove(&proposal_id)?;
+        }
+        
+        let mut lapsed = Vec::new();
+        self.diplomacy
+            .for_each_index_value(|chain, relation| {
+                if relation.status == DiplomacyData::PEACE_PENDING && !live_peace.contains(&chain) {
+                    lapsed.push(chain);
+                }
+                Ok(())
+            })
+            .await?;
+        for chain in lapsed {
+            if let Some(relation) = self.diplomacy.get_mut(&chain).await? {
+                relation.status = DiplomacyData::AT_WAR;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Local id of an incoming offer, given its maker's chain and offer id
+    pub async fn find_incoming_trade(
+        &self,
+        maker_chain: &str,
+        remote_offer_id: u64,
+    ) -> Result<Option<u64>, ViewError> {
+        let mut found = None;
+        self.trades
+            .for_each_index_value_while(|trade_id, trade| {
+                if !trade.outgoing
+                    && trade.remote_offer_id == remote_offer_id
+                    && trade.counterparty_chain == maker_chain
+                {
+                    found = Some(trade_id);
+                    return Ok(false);
+                }
+                Ok(true)
+            })
+            .await?;
+        Ok(found)
+    }
+    
+    /// Record the current research project as complete once it has ended by `now_micros`
+    pub fn complete_research(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        let Some(project) = self.current_research.get().clone() else {
+            return Ok(());
+        };
+        if project.completion_micros.is_some_and(|end| end > now_micros) {
+            return Ok(());
+        }
+        
+        self.research.insert(&project.technology_type, ResearchData {
+            completion_micros: None,
+            ..project
+        })?;
+        self.current_research.set(None);
+        Ok(())
+    }
+    
+    /// Completed level of a technology
+    pub async fn tech_level(&self, technology: u8) -> Result<u32, ViewError> {
+        Ok(self
+            .research
+            .get(&technology)
+            .await?
+            .map_or(0, |research| research.level))
+    }
+    
+    /// Research modifiers in effect at `now_micros`
+    pub async fn tech_modifiers(&self, now_micros: u64) -> Result<TechModifiers, ViewError> {
+        let research_levels = self.research_levels().await?;
+        Ok(modifiers_at(
+            &research_levels,
+            self.current_research.get().as_ref(),
+            now_micros,
+        ))
+    }
+    
+    /// Completed level of every researched technology
+    async fn research_levels(&self) -> Result<BTreeMap<u8, u32>, ViewError> {
+        let mut levels = BTreeMap::new();
+        self.research
+            .for_each_index_value(|technology, research| {
+                levels.insert(technology, research.level);
+                Ok(())
+            })
+            .await?;
+        Ok(levels)
+    }
+    
+    /// Highest level at which a building of `building_type` is operational at `now_micros`
+    pub async fn operational_building_level(
+        &self,
+        building_type: u8,
+        now_micros: u64,
+    ) -> Result<u32, ViewError> {
+        let mut highest = 0;
+        self.buildings
+            .for_each_index_value(|_, building| {
+                if building.building_type == building_type {
+                    let level = completed_level(
+                        building.level,
+                        building.construction_end_micros,
+                        now_micros,
+                    );
+                    highest = highest.max(level);
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(highest)
+    }
+    
+    /// Building standing on tile (x, y), if any
+    pub async fn building_at(&self, x: i64, y: i64) -> Result<Option<(u64, BuildingData)>, ViewError> {
+        let Some(building_id) = self.building_tiles.get(&(x, y)).await? else {
+            return Ok(None);
+        };
+        Ok(self
+            .buildings
+            .get(&building_id)
+            .await?
+            .map(|building| (building_id, building)))
+    }
+    
+    /// Store a new building and index its tile, returning its id
+    pub fn add_building(&mut self, building: BuildingData) -> Result<u64, ViewError> {
+        let building_id = *self.building_count.get();
+        self.building_tiles.insert(&(building.x, building.y), building_id)?;
+        self.buildings.insert(&building_id, building)?;
+        self.building_count.set(building_id + 1);
+        Ok(building_id)
+    }
+    
+    /// Remove a building and free its tile
+    pub fn remove_building(&mut self, building_id: u64, building: &BuildingData) -> Result<(), ViewError> {
+        self.building_tiles.remove(&(building.x, building.y))?;
+        self.buildings.remove(&building_id)
+    }
+    
+    /// Resource-producing buildings as (type, level, construction end)
+    async fn production_buildings(
+        &self,
+    ) -> Result<Vec<(BuildingType, u32, Option<u64>)>, ViewError> {
+        let mut producers = Vec::new();
+        self.buildings
+            .for_each_index_value(|_, building| {
+                let building_type = BuildingType::from_u8(building.building_type);
+                if let Some(
+                    building_type @ (BuildingType::MinerDrone
+                    | BuildingType::GasSiphon
+                    | BuildingType::ChronosCollider),
+                ) = building_type
+                {
+                    producers.push((
+                        building_type,
+                        building.level,
+                        building.construction_end_micros,
+                    ));
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(producers)
+    }
+    
+    /// Debit resources from wallet
+    ///
+    /// Production must already be settled with [`Self::settle_production`].
+    pub fn debit_resources(&mut self, amount: &Resources) -> Result<(), ResourceError> {
+        let wallet = self.wallet.get_mut();
+        wallet.resources = wallet.resources.checked_sub(amount)?;
+        Ok(())
+    }
+    
+    /// Credit resources to wallet
+    pub fn credit_resources(&mut self, amount: &Resources) -> Result<(), ResourceError> {
+        let wallet = self.wallet.get_mut();
+        wallet.resources = wallet.resources.checked_add(amount)?;
+        Ok(())
+    }
+}
+
+/// Level a building produces at during `at_micros`
+///
+/// A building under construction keeps producing at its previous level until
+/// the construction completes.
+fn completed_level(level: u32, construction_end_micros: Option<u64>, at_micros: u64) -> u32 {
+    match construction_end_micros {
+        Some(end) if end > at_micros => level.saturating_sub(1),
+        _ => level,
+    }
+}
+
+/// Build time of one ship order in microseconds
+///
+/// All ships of an order are built in parallel, so it takes as long as a single ship.
+pub fn ship_build_micros(config: &GameConfig, ship_type: ShipType, time_modifier: u32) -> u64 {
+    let seconds = config.ship_time(ship_type);
+    TechModifiers::apply(time_modifier, seconds).max(1) * 1_000_000
+}
+
+/// Research modifiers at `at_micros`, counting the current project once it has completed
+fn modifiers_at(
+    research_levels: &BTreeMap<u8, u32>,
+    project: Option<&ResearchData>,
+    at_micros: u64,
+) -> TechModifiers {
+    TechModifiers::from_levels(|technology| {
+        let technology = technology as u8;
+        match project {
+            Some(project)
+                if project.technology_type == technology
+                    && project.completion_micros.is_none_or(|end| end <= at_micros) =>
+            {
+                project.level
+            }
+            _ => research_levels.get(&technology).copied().unwrap_or(0),
+        }
+    })
 }
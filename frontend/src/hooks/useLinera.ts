@@ -9,6 +9,7 @@ import {
   SEND_FLEET,
   START_RESEARCH,
 } from '@/lib/queries';
+import { fromChainResources, toChainResources } from '@/lib/linera/queries';
 
 // Hook to sync Linera state with the game store
 export function useLineraSync() {
@@ -23,11 +24,7 @@ export function useLineraSync() {
   // Update store when data changes
   useEffect(() => {
     if (data) {
-      setResources({
-        iron: data.iron || 0,
-        deuterium: data.deuterium || 0,
-        crystals: data.crystals || 0,
-      });
+      setResources(fromChainResources(data.resources));
     }
   }, [data, setResources]);
 
@@ -85,7 +82,7 @@ export function useFleetMutations() {
     ) => {
       try {
         const result = await sendFleetMutation({
-          variables: { fleetId, destinationX, destinationY, cargo },
+          variables: { fleetId, destinationX, destinationY, cargo: cargo && toChainResources(cargo) },
         });
         return result.data;
       } catch (err) {
@@ -6,6 +6,7 @@
 
 // Re-export everything from the linera directory
 export * from './linera/index';
+import { fromChainResources, toChainResources } from './linera/queries';
 
 // Also keep the legacy Apollo-based types for backwards compatibility
 import { lineraClient, buildGraphQLEndpoint, LINERA_CONFIG } from './apollo';
@@ -98,11 +99,7 @@ export class LineraAPI {
       query: GET_PLAYER_RESOURCES,
       context: { uri: this.endpoint },
     });
-    return {
-      iron: data.iron,
-      deuterium: data.deuterium,
-      crystals: data.crystals,
-    };
+    return fromChainResources(data.resources);
   }
 
   // Mutation methods
@@ -136,7 +133,7 @@ export class LineraAPI {
   async sendFleet(fleetId: string, destinationX: number, destinationY: number, cargo?: Resources) {
     const { data } = await lineraClient.mutate({
       mutation: SEND_FLEET,
-      variables: { fleetId, destinationX, destinationY, cargo },
+      variables: { fleetId, destinationX, destinationY, cargo: cargo && toChainResources(cargo) },
       context: { uri: this.endpoint },
     });
     return data;
@@ -14,9 +14,11 @@ export const GET_DOMINION_STATE = `
     name
     homeX
     homeY
-    iron
-    deuterium
-    crystals
+    resources {
+      iron
+      deuterium
+      chronosCrystals
+    }
     buildingCount
     fleetCount
   }
@@ -27,9 +29,11 @@ export const GET_DOMINION_STATE = `
  */
 export const GET_RESOURCES = `
   query GetResources {
-    iron
-    deuterium
-    crystals
+    resources {
+      iron
+      deuterium
+      chronosCrystals
+    }
   }
 `;
 
@@ -111,7 +115,7 @@ export const DISBAND_FLEET = `
  * Send a fleet to a destination
  */
 export const SEND_FLEET = `
-  mutation SendFleet($fleetId: Int!, $destinationX: Int!, $destinationY: Int!, $cargo: ResourceAmountInput) {
+  mutation SendFleet($fleetId: Int!, $destinationX: Int!, $destinationY: Int!, $cargo: ResourcesInput) {
     sendFleet(fleetId: $fleetId, destinationX: $destinationX, destinationY: $destinationY, cargo: $cargo)
   }
 `;
@@ -129,7 +133,7 @@ export const RECALL_FLEET = `
  * Create a trade offer
  */
 export const CREATE_TRADE = `
-  mutation CreateTrade($targetChain: String!, $offering: ResourceAmountInput!, $requesting: ResourceAmountInput!) {
+  mutation CreateTrade($targetChain: String!, $offering: ResourcesInput!, $requesting: ResourcesInput!) {
     createTrade(targetChain: $targetChain, offering: $offering, requesting: $requesting)
   }
 `;
@@ -313,6 +317,33 @@ export interface ResourceAmount {
   crystals: number;
 }
 
+/**
+ * Resource amounts as exchanged with the contract
+ *
+ * Amounts are 128-bit, so the schema encodes them as decimal strings.
+ */
+export interface ChainResources {
+  iron: string;
+  deuterium: string;
+  chronosCrystals: string;
+}
+
+export function fromChainResources(resources: ChainResources): ResourceAmount {
+  return {
+    iron: Number(resources.iron),
+    deuterium: Number(resources.deuterium),
+    crystals: Number(resources.chronosCrystals),
+  };
+}
+
+export function toChainResources(resources: ResourceAmount): ChainResources {
+  return {
+    iron: Math.floor(resources.iron).toString(),
+    deuterium: Math.floor(resources.deuterium).toString(),
+    chronosCrystals: Math.floor(resources.crystals).toString(),
+  };
+}
+
 /**
  * Ship order for fleet creation
  */
@@ -328,9 +359,7 @@ export interface DominionStateResponse {
   name: string;
   homeX: number;
   homeY: number;
-  iron: number;
-  deuterium: number;
-  crystals: number;
+  resources: ChainResources;
   buildingCount: number;
   fleetCount: number;
 }
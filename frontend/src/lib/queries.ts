@@ -7,9 +7,11 @@ export const GET_DOMINION_STATE = gql`
     name
     homeX
     homeY
-    iron
-    deuterium
-    crystals
+    resources {
+      iron
+      deuterium
+      chronosCrystals
+    }
     buildingCount
     fleetCount
   }
@@ -17,39 +19,47 @@ export const GET_DOMINION_STATE = gql`
 
 export const GET_PLAYER_RESOURCES = gql`
   query GetPlayerResources {
-    iron
-    deuterium
-    crystals
+    resources {
+      iron
+      deuterium
+      chronosCrystals
+    }
   }
 `;
 
 export const GET_PLAYER_BUILDINGS = gql`
-  query GetPlayerBuildings {
-    buildings {
-      id
+  query GetPlayerBuildings($underConstruction: Boolean, $offset: Int, $limit: Int) {
+    buildings(underConstruction: $underConstruction, offset: $offset, limit: $limit) {
+      buildingId
       buildingType
       level
       x
       y
-      constructionEnd
+      underConstruction
+      constructionEndMicros
     }
   }
 `;
 
 export const GET_PLAYER_FLEETS = gql`
-  query GetPlayerFleets {
-    fleets {
-      id
+  query GetPlayerFleets($status: Int, $offset: Int, $limit: Int) {
+    fleets(status: $status, offset: $offset, limit: $limit) {
+      fleetId
       name
-      ships {
-        shipType
-        quantity
-      }
+      ships
+      totalShips
       status
-      x
-      y
-      destinationX
-      destinationY
+      position {
+        x
+        y
+      }
+      destX
+      destY
+      arrivalMicros
+    }
+    shipPool {
+      shipType
+      count
     }
   }
 `;
@@ -59,8 +69,12 @@ export const GET_PLAYER_RESEARCH = gql`
     research {
       technology
       level
-      inProgress
-      completionTime
+    }
+    currentResearch {
+      technology
+      level
+      startedMicros
+      completionMicros
     }
   }
 `;
@@ -86,7 +100,7 @@ export const CREATE_FLEET = gql`
 `;
 
 export const SEND_FLEET = gql`
-  mutation SendFleet($fleetId: ID!, $destinationX: Int!, $destinationY: Int!, $cargo: ResourceAmountInput) {
+  mutation SendFleet($fleetId: ID!, $destinationX: Int!, $destinationY: Int!, $cargo: ResourcesInput) {
     sendFleet(fleetId: $fleetId, destinationX: $destinationX, destinationY: $destinationY, cargo: $cargo)
   }
 `;
@@ -110,11 +124,30 @@ export const CANCEL_RESEARCH = gql`
 `;
 
 export const CREATE_TRADE = gql`
-  mutation CreateTrade($targetChain: ChainId!, $offering: ResourceAmountInput!, $requesting: ResourceAmountInput!) {
+  mutation CreateTrade($targetChain: ChainId!, $offering: ResourcesInput!, $requesting: ResourcesInput!) {
     createTrade(targetChain: $targetChain, offering: $offering, requesting: $requesting)
   }
 `;
 
+// Planets are claimed through the dominion, which stakes from the wallet
+export const CLAIM_PLANET = gql`
+  mutation ClaimPlanet($fleetId: ID!, $planetId: ID!, $stake: ResourcesInput!) {
+    claimPlanet(fleetId: $fleetId, planetId: $planetId, stake: $stake)
+  }
+`;
+
+export const ABANDON_PLANET = gql`
+  mutation AbandonPlanet($x: Int!, $y: Int!) {
+    abandonPlanet(x: $x, y: $y)
+  }
+`;
+
+export const HARVEST_PLANET = gql`
+  mutation HarvestPlanet($x: Int!, $y: Int!) {
+    harvestPlanet(x: $x, y: $y)
+  }
+`;
+
 // ===== REGION CHAIN QUERIES =====
 
 export const GET_SECTOR_INFO = gql`
@@ -127,53 +160,55 @@ export const GET_SECTOR_INFO = gql`
 `;
 
 export const GET_SECTOR_PLANETS = gql`
-  query GetSectorPlanets {
-    planets {
+  query GetSectorPlanets($unclaimed: Boolean, $offset: Int, $limit: Int) {
+    planets(unclaimed: $unclaimed, offset: $offset, limit: $limit) {
       id
       x
       y
-      type
-      owner
-      resources {
+      planetType
+      name
+      ownerChain
+      currentStake {
+        iron
+        deuterium
+        chronosCrystals
+      }
+      expiresMicros
+      pendingYield {
         iron
         deuterium
-        crystals
+        chronosCrystals
       }
     }
   }
 `;
 
 export const GET_SECTOR_FLEETS = gql`
-  query GetSectorFleets {
-    fleets {
-      id
-      owner
+  query GetSectorFleets($minX: Int, $minY: Int, $maxX: Int, $maxY: Int) {
+    fleets(minX: $minX, minY: $minY, maxX: $maxX, maxY: $maxY) {
+      fleetId
       ownerChain
-      x
-      y
-      status
-      shipCount
+      positionX
+      positionY
+      revealed
+      shipCounts
+      revealedAttack
+      revealedDefense
     }
   }
 `;
 
 // ===== REGION MUTATIONS =====
 
-export const CLAIM_PLANET = gql`
-  mutation ClaimPlanet($x: Int!, $y: Int!, $fleetId: ID!) {
-    claimPlanet(x: $x, y: $y, fleetId: $fleetId)
-  }
-`;
-
 export const SCAN_FLEET = gql`
-  mutation ScanFleet($targetFleetId: ID!) {
-    scanFleet(targetFleetId: $targetFleetId)
+  mutation ScanFleet($targetChain: ChainId!, $targetFleetId: Int!, $scannerChain: ChainId!, $scannerFleetId: Int!) {
+    scanFleet(targetChain: $targetChain, targetFleetId: $targetFleetId, scannerChain: $scannerChain, scannerFleetId: $scannerFleetId)
   }
 `;
 
 export const DECLARE_HOSTILITY = gql`
-  mutation DeclareHostility($targetChain: ChainId!) {
-    declareHostility(targetChain: $targetChain)
+  mutation DeclareHostility($attackerChain: ChainId!, $attackerFleetId: Int!, $targetChain: ChainId!, $targetFleetId: Int!) {
+    declareHostility(attackerChain: $attackerChain, attackerFleetId: $attackerFleetId, targetChain: $targetChain, targetFleetId: $targetFleetId)
   }
 `;
 
@@ -1,5 +1,5 @@
 import { create } from 'zustand';
-import { lineraAdapter } from '@/lib/linera';
+import { lineraAdapter, fromChainResources, type ChainResources } from '@/lib/linera';
 import { saveGameState, loadGameState } from '@/lib/persistence';
 
 // Building type mapping for GraphQL enum
@@ -447,16 +447,12 @@ export const useGameStore = create<GameState>((set, get) => ({
         
         // Try to sync with blockchain, but DON'T overwrite local resources with 0 values
         try {
-          const query = `query { iron deuterium crystals }`;
-          const result = await lineraAdapter.query<{ iron: number; deuterium: number; crystals: number }>(query);
-          if (result) {
+          const query = `query { resources { iron deuterium chronosCrystals } }`;
+          const result = await lineraAdapter.query<{ resources: ChainResources }>(query);
+          if (result?.resources) {
             // Only update if blockchain has MORE resources than local (prevents overwriting with 0)
             const localResources = persisted.resources;
-            const blockchainResources = {
-              iron: result.iron || 0,
-              deuterium: result.deuterium || 0,
-              crystals: result.crystals || 0,
-            };
+            const blockchainResources = fromChainResources(result.resources);
             
             // Use the HIGHER of local vs blockchain values
             // This handles the case where blockchain returns 0 but local has accumulated resources
@@ -488,9 +484,7 @@ export const useGameStore = create<GameState>((set, get) => ({
           name
           homeX
           homeY
-          iron
-          deuterium
-          crystals
+          resources { iron deuterium chronosCrystals }
           buildingCount
           fleetCount
         }
@@ -500,9 +494,7 @@ export const useGameStore = create<GameState>((set, get) => ({
         name: string;
         homeX: number;
         homeY: number;
-        iron: number;
-        deuterium: number;
-        crystals: number;
+        resources: ChainResources;
         buildingCount: number;
         fleetCount: number;
       }>(query);
@@ -513,6 +505,7 @@ export const useGameStore = create<GameState>((set, get) => ({
       const MIN_IRON = 500;
       const MIN_DEUTERIUM = 200;
       const MIN_CRYSTALS = 50;
+      const chainResources = fromChainResources(result.resources);
       
       const newState = {
         gameState: 'playing' as const,
@@ -521,9 +514,9 @@ export const useGameStore = create<GameState>((set, get) => ({
         homeX: result.homeX || 100,
         homeY: result.homeY || 100,
         resources: {
-          iron: Math.max(result.iron || 0, MIN_IRON),
-          deuterium: Math.max(result.deuterium || 0, MIN_DEUTERIUM),
-          crystals: Math.max(result.crystals || 0, MIN_CRYSTALS),
+          iron: Math.max(chainResources.iron || 0, MIN_IRON),
+          deuterium: Math.max(chainResources.deuterium || 0, MIN_DEUTERIUM),
+          crystals: Math.max(chainResources.crystals || 0, MIN_CRYSTALS),
         },
       };
       
@@ -1043,9 +1036,7 @@ export const useGameStore = create<GameState>((set, get) => ({
           name
           homeX
           homeY
-          iron
-          deuterium
-          crystals
+          resources { iron deuterium chronosCrystals }
           buildingCount
           fleetCount
         }
@@ -1055,9 +1046,7 @@ export const useGameStore = create<GameState>((set, get) => ({
         name: string;
         homeX: number;
         homeY: number;
-        iron: number;
-        deuterium: number;
-        crystals: number;
+        resources: ChainResources;
         buildingCount: number;
         fleetCount: number;
       }>(query);
@@ -1066,11 +1055,7 @@ export const useGameStore = create<GameState>((set, get) => ({
       
       // Get current local resources (may have accumulated while offline or through ticking)
       const currentResources = get().resources;
-      const blockchainResources = {
-        iron: result.iron || 0,
-        deuterium: result.deuterium || 0,
-        crystals: result.crystals || 0,
-      };
+      const blockchainResources = fromChainResources(result.resources);
       
       // Use MAX of local vs blockchain - never lose accumulated resources
       // This prevents blockchain returning 0 from wiping local progress
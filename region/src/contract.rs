@@ -3,15 +3,26 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
 use linera_sdk::{
-    linera_base_types::WithContractAbi,
+    linera_base_types::{AccountOwner, ChainId, Timestamp, WithContractAbi},
     Contract, ContractRuntime,
-    views::{RootView, View},
+    views::{RootView, View, ViewError},
 };
 use linera_dominion_region::{
-    RegionAbi, RegionError, RegionParameters,
+    RegionAbi, RegionError, RegionParameters, RegionResponse,
     Operation, Message,
 };
-use linera_dominion_region::state::{RegionState, FleetPresenceData, BattleRef};
+use linera_dominion_region::state::{RegionState, FleetPresenceData, BattleRef, PlanetData};
+use linera_dominion_senate::{
+    Operation as SenateOperation, SenateAbi, SenateResponse,
+};
+use linera_dominion_common::{
+    constants::{MAX_UNITS_PER_SECTOR, MINIMUM_PLANET_STAKE, SCAN_RANGE, STAKE_DECAY_BATCH_SIZE},
+    coordinates::{Coordinate, SectorCoordinate},
+    crypto::{generate_sector, universe_seed_bytes, verify_ship_counts},
+    messages::{DominionMessage, FleetReturnReason},
+    resources::Resources,
+    units::{Fleet, FleetStats, ShipType},
+};
 
 pub struct RegionContract {
     state: RegionState,
@@ -39,112 +50,195 @@ impl Contract for RegionContract {
 
     async fn instantiate(&mut self, _argument: Self::InstantiationArgument) {
         let params = self.runtime.application_parameters();
+        assert!(params.config.is_valid(), "Invalid game configuration");
         self.state.initialize(
             params.sector_x,
             params.sector_y,
+            params.sector_size,
             params.universe_seed,
         );
+        let planets = generate_sector(
+            &universe_seed_bytes(params.universe_seed),
+            SectorCoordinate::new(params.sector_x, params.sector_y),
+            params.sector_size as i64,
+        );
+        self.state.seed_planets(planets).expect("Failed to seed planets");
+        self.state.config.set(params.config);
+        if let Some(senate_app) = params.senate_app {
+            self.runtime
+                .call_application(true, senate_app.with_abi::<SenateAbi>(), &SenateOperation::Subscribe)
+                .expect("Failed to subscribe to the senate");
+        }
     }
 
-    async fn execute_operation(&mut self, operation: Self::Operation) -> Result<(), RegionError> {
+    async fn execute_operation(&mut self, operation: Self::Operation) -> Result<RegionResponse, RegionError> {
         let now = self.runtime.system_time();
         let now_micros = now.micros();
         
+        self.update_config(now_micros);
+        
         match operation {
-            Operation::ClaimPlanet { planet_id, stake } => {
-                if let Some(mut planet) = self.state.planets.get(&planet_id).await
-                    .map_err(|_| RegionError::PlanetNotFound(planet_id))? {
-                    if !planet.owner_chain.is_empty() {
-                        return Err(RegionError::PlanetAlreadyClaimed);
-                    }
-                    planet.owner_chain = String::new();
-                    planet.stake_iron = stake.iron;
-                    planet.stake_deuterium = stake.deuterium;
-                    planet.stake_crystals = stake.crystals;
-                    planet.stake_updated_micros = now_micros;
-                    self.state.planets.insert(&planet_id, planet)
-                        .map_err(|_| RegionError::NotAuthorized)?;
-                }
-                Ok(())
-            }
-            
-            Operation::ResupplyPlanet { planet_id, additional_stake } => {
-                if let Some(mut planet) = self.state.planets.get(&planet_id).await
-                    .map_err(|_| RegionError::PlanetNotFound(planet_id))? {
-                    planet.stake_iron += additional_stake.iron;
-                    planet.stake_deuterium += additional_stake.deuterium;
-                    planet.stake_crystals += additional_stake.crystals;
-                    planet.stake_updated_micros = now_micros;
-                    self.state.planets.insert(&planet_id, planet)
-                        .map_err(|_| RegionError::NotAuthorized)?;
+            Operation::ScanFleet { target_chain, target_fleet_id, scanner_chain, scanner_fleet_id } => {
+                let mut presence = self.state.fleets.get(&(target_chain, target_fleet_id)).await
+                    .map_err(|_| RegionError::StorageError)?
+                    .ok_or(RegionError::FleetNotFound(target_fleet_id))?;
+                let position = Coordinate::new(presence.position_x, presence.position_y);
+                let nearby = self.state.fleets_near(position, SCAN_RANGE).await
+                    .map_err(|_| RegionError::StorageError)??;
+                if !nearby.iter().any(|fleet| is_fleet(fleet, scanner_chain, scanner_fleet_id)) {
+                    return Err(RegionError::OutOfRange);
                 }
-                Ok(())
+                presence.revealed = true;
+                self.state.insert_fleet(target_chain, presence).await
+                    .map_err(|_| RegionError::StorageError)?;
+                Ok(RegionResponse::Ok)
             }
             
-            Operation::AbandonPlanet { planet_id } => {
-                if let Some(mut planet) = self.state.planets.get(&planet_id).await
-                    .map_err(|_| RegionError::PlanetNotFound(planet_id))? {
-                    planet.owner_chain = String::new();
-                    planet.stake_iron = 0;
-                    planet.stake_deuterium = 0;
-                    planet.stake_crystals = 0;
-                    self.state.planets.insert(&planet_id, planet)
-                        .map_err(|_| RegionError::NotAuthorized)?;
-                }
-                Ok(())
-            }
-            
-            Operation::ScanFleet { target_fleet_id, scanner_fleet_id: _ } => {
-                let count = *self.state.fleet_count.get();
-                for i in 0..count {
-                    if let Ok(Some(mut f)) = self.state.fleets.get(&i).await {
-                        if f.fleet_id == target_fleet_id {
-                            f.revealed = true;
-                            let _ = self.state.fleets.insert(&i, f);
-                            return Ok(());
-                        }
-                    }
+            Operation::DeclareHostility { attacker_chain, attacker_fleet_id, target_chain, target_fleet_id } => {
+                let attacker = self.state.fleets.get(&(attacker_chain, attacker_fleet_id)).await
+                    .map_err(|_| RegionError::StorageError)?
+                    .ok_or(RegionError::FleetNotFound(attacker_fleet_id))?;
+                let position = Coordinate::new(attacker.position_x, attacker.position_y);
+                let present = self.state.fleets_at(position).await
+                    .map_err(|_| RegionError::StorageError)?;
+                if !present.iter().any(|fleet| is_fleet(fleet, target_chain, target_fleet_id)) {
+                    return Err(RegionError::OutOfRange);
                 }
-                Err(RegionError::FleetNotFound(target_fleet_id))
-            }
-            
-            Operation::DeclareHostility { attacker_fleet_id, target_fleet_id } => {
                 let count = *self.state.battle_count.get();
                 let battle_ref = BattleRef {
                     battle_id: count,
                     battle_chain: String::new(),
-                    x: 0,
-                    y: 0,
+                    x: position.x,
+                    y: position.y,
                     started_at_micros: now_micros,
                 };
                 self.state.battles.insert(&count, battle_ref)
                     .map_err(|_| RegionError::NotAuthorized)?;
                 self.state.battle_count.set(count + 1);
-                Ok(())
+                Ok(RegionResponse::Ok)
             }
             
-            Operation::CollectDebris { fleet_id, debris_id } => {
-                let mut fleet_found = false;
-                let count = *self.state.fleet_count.get();
-                for i in 0..count {
-                    if let Ok(Some(f)) = self.state.fleets.get(&i).await {
-                        if f.fleet_id == fleet_id {
-                            fleet_found = true;
-                            break;
-                        }
-                    }
+            Operation::ProcessStakeDecay => {
+                if !self.is_region_chain() {
+                    return Err(RegionError::NotAuthorized);
+                }
+                self.sweep_stake_decay(now_micros).await
+                    .map_err(|_| RegionError::StorageError)?;
+                Ok(RegionResponse::Ok)
+            }
+            
+            Operation::RegisterDominion => {
+                let caller = self.runtime.authenticated_caller_id()
+                    .ok_or(RegionError::NotAuthorized)?;
+                if self.state.dominion_app.get().is_some_and(|dominion_app| dominion_app != caller) {
+                    return Err(RegionError::NotAuthorized);
                 }
-                if !fleet_found {
-                    return Err(RegionError::FleetNotInSector);
+                self.state.dominion_app.set(Some(caller));
+                Ok(RegionResponse::Ok)
+            }
+            
+            Operation::EnterFleet {
+                fleet_id,
+                position,
+                commitment_hash,
+                arrival_micros,
+                modifiers,
+            } => {
+                self.check_relay_caller()?;
+                let owner = self.runtime.authenticated_signer()
+                    .ok_or(RegionError::NotAuthorized)?;
+                let owner_chain = self.runtime.chain_id();
+                // The Region Chain is where this sector's application was created
+                let region_chain = self.runtime.application_creator_chain_id();
+                self.runtime
+                    .prepare_message(Message::FleetEnter {
+                        fleet_id,
+                        owner,
+                        owner_chain,
+                        x: position.x,
+                        y: position.y,
+                        commitment_hash,
+                        arrival_micros,
+                        modifiers,
+                    })
+                    .with_authentication()
+                    .with_tracking()
+                    .send_to(region_chain);
+                Ok(RegionResponse::Ok)
+            }
+            
+            Operation::LeaveFleet { fleet_id } => {
+                self.check_relay_caller()?;
+                let region_chain = self.runtime.application_creator_chain_id();
+                self.runtime
+                    .prepare_message(Message::FleetLeave { fleet_id })
+                    .with_authentication()
+                    .send_to(region_chain);
+                Ok(RegionResponse::Ok)
+            }
+            
+            Operation::ClaimPlanet { planet_id, position, fleet_id, ship_counts, salt, stake } => {
+                self.check_relay_caller()?;
+                if ship_counts.get(ShipType::Colonizer as usize).is_none_or(|count| *count == 0) {
+                    return Err(RegionError::NoColonizer);
                 }
-                
-                self.state.debris.remove(&debris_id)
-                    .map_err(|_| RegionError::NotAuthorized)?;
-                Ok(())
+                if stake.total().is_none_or(|total| total < MINIMUM_PLANET_STAKE) {
+                    return Err(RegionError::InsufficientStake(MINIMUM_PLANET_STAKE));
+                }
+                self.send_stake(planet_id, position, stake, Some((fleet_id, ship_counts, salt)))?;
+                Ok(RegionResponse::Ok)
             }
             
-            Operation::ProcessStakeDecay => {
-                Ok(())
+            Operation::ResupplyPlanet { planet_id, position, additional_stake } => {
+                self.check_relay_caller()?;
+                self.send_stake(planet_id, position, additional_stake, None)?;
+                Ok(RegionResponse::Ok)
+            }
+            
+            Operation::AbandonPlanet { planet_id } => {
+                self.check_relay_caller()?;
+                let owner = self.runtime.authenticated_signer()
+                    .ok_or(RegionError::NotAuthorized)?;
+                let region_chain = self.runtime.application_creator_chain_id();
+                self.runtime
+                    .prepare_message(Message::UnstakePlanet { planet_id, owner })
+                    .with_authentication()
+                    .send_to(region_chain);
+                Ok(RegionResponse::Ok)
+            }
+            
+            Operation::HarvestPlanet { planet_id } => {
+                self.check_relay_caller()?;
+                let owner = self.runtime.authenticated_signer()
+                    .ok_or(RegionError::NotAuthorized)?;
+                let region_chain = self.runtime.application_creator_chain_id();
+                self.runtime
+                    .prepare_message(Message::HarvestPlanet { planet_id, owner })
+                    .with_authentication()
+                    .send_to(region_chain);
+                Ok(RegionResponse::Ok)
+            }
+            
+            Operation::CollectDebris { fleet_id, debris_id } => {
+                self.check_relay_caller()?;
+                let collector = self.runtime.authenticated_signer()
+                    .ok_or(RegionError::NotAuthorized)?;
+                let region_chain = self.runtime.application_creator_chain_id();
+                self.runtime
+                    .prepare_message(Message::CollectDebris { fleet_id, debris_id, collector })
+                    .with_authentication()
+                    .send_to(region_chain);
+                Ok(RegionResponse::Ok)
+            }
+            
+            Operation::TakeNotifications => {
+                self.check_relay_caller()?;
+                let notifications = self.state.notifications.elements().await
+                    .map_err(|_| RegionError::StorageError)?;
+                for _ in 0..notifications.len() {
+                    self.state.notifications.delete_front();
+                }
+                Ok(RegionResponse::Notifications(notifications))
             }
         }
     }
@@ -153,51 +247,202 @@ impl Contract for RegionContract {
         let now = self.runtime.system_time();
         let now_micros = now.micros();
         
+        self.update_config(now_micros);
+        
         match message {
-            Message::FleetEnter { fleet_id, owner: _, owner_chain: _, x, y, commitment_hash } => {
-                let count = *self.state.fleet_count.get();
+            Message::FleetEnter {
+                fleet_id,
+                owner,
+                owner_chain: _,
+                x,
+                y,
+                commitment_hash,
+                arrival_micros,
+                modifiers,
+            } => {
+                let owner_chain = self.runtime.message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                let returned = |reason| DominionMessage::FleetReturn {
+                    fleet: Fleet::new(
+                        fleet_id,
+                        owner,
+                        owner_chain,
+                        Vec::new(),
+                        Coordinate::new(x, y),
+                        Timestamp::from(now_micros),
+                    )
+                    .with_modifiers(modifiers),
+                    reason,
+                };
+                
+                if self.runtime.message_is_bouncing() == Some(true) {
+                    // The Region Chain rejected the message: we are back on the owner's chain
+                    self.state.notifications.push_back(returned(FleetReturnReason::Bounced));
+                    return;
+                }
+                
+                if let Err(reason) = self.admit_fleet(owner, x, y).await {
+                    self.notify(owner_chain, returned(reason));
+                    return;
+                }
+                
                 let presence = FleetPresenceData {
                     fleet_id,
-                    owner_chain: String::new(),
+                    owner_chain: owner_chain.to_string(),
                     position_x: x,
                     position_y: y,
                     commitment_hash,
-                    arrived_at_micros: now_micros,
+                    arrived_at_micros: arrival_micros,
                     revealed: false,
+                    modifiers,
+                    ..FleetPresenceData::default()
                 };
-                let _ = self.state.fleets.insert(&count, presence);
-                self.state.fleet_count.set(count + 1);
+                self.state.insert_fleet(owner_chain, presence).await
+                    .expect("Failed to record fleet");
+                
+                self.notify(owner_chain, DominionMessage::FleetArrivalConfirmed {
+                    fleet_id,
+                    position: Coordinate::new(x, y),
+                });
             }
             
             Message::FleetLeave { fleet_id } => {
-                let count = *self.state.fleet_count.get();
-                for i in 0..count {
-                    if let Ok(Some(f)) = self.state.fleets.get(&i).await {
-                        if f.fleet_id == fleet_id {
-                            let _ = self.state.fleets.remove(&i);
-                            break;
-                        }
-                    }
+                let owner_chain = self.runtime.message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                self.state.remove_fleet(owner_chain, fleet_id).await
+                    .expect("Failed to remove fleet");
+            }
+            
+            Message::FleetReveal { fleet_id, ship_counts, salt } => {
+                let owner_chain = self.runtime.message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                let Some(presence) = self.state.fleets.get(&(owner_chain, fleet_id)).await
+                    .expect("Failed to read fleet")
+                    .filter(|presence| verify_ship_counts(fleet_id, &ship_counts, &salt, &presence.commitment_hash))
+                else {
+                    return;
+                };
+                self.reveal_fleet(owner_chain, presence, ship_counts).await;
+            }
+            
+            Message::StakePlanet {
+                planet_id,
+                position,
+                staker,
+                staker_chain: _,
+                resources,
+                fleet_id,
+                ship_counts,
+                salt,
+            } => {
+                let staker_chain = self.runtime.message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                let returned = DominionMessage::StakeReturn { planet_id, position, resources };
+                
+                if self.runtime.message_is_bouncing() == Some(true) {
+                    // The Region Chain never took the stake: hand it back
+                    self.state.notifications.push_back(returned);
+                    return;
+                }
+                
+                let colonizer = fleet_id.map(|fleet_id| (fleet_id, ship_counts, salt));
+                match self.stake_planet(planet_id, position, staker, staker_chain, resources, colonizer).await {
+                    Some(stake) => self.notify(staker_chain, DominionMessage::PlanetStaked {
+                        planet_id,
+                        position,
+                        stake,
+                    }),
+                    None => self.notify(staker_chain, returned),
                 }
             }
             
-            Message::FleetReveal { fleet_id, ship_counts: _, salt: _ } => {
-                let count = *self.state.fleet_count.get();
-                for i in 0..count {
-                    if let Ok(Some(mut f)) = self.state.fleets.get(&i).await {
-                        if f.fleet_id == fleet_id {
-                            f.revealed = true;
-                            let _ = self.state.fleets.insert(&i, f);
-                            break;
-                        }
-                    }
+            Message::UnstakePlanet { planet_id, owner } => {
+                let owner_chain = self.runtime.message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                if self.runtime.authenticated_signer() != Some(owner) {
+                    return;
+                }
+                let Some(mut planet) = self.state.planets.get(&planet_id).await
+                    .expect("Failed to read planet")
+                else {
+                    return;
+                };
+                if planet.owner_chain != owner_chain.to_string()
+                    || planet.owner != Some(owner)
+                    || self.expire_stake(&mut planet, now_micros)
+                {
+                    return;
                 }
+                let resources = planet.release(now_micros, self.state.config.get());
+                let position = Coordinate::new(planet.x, planet.y);
+                self.state.planets.insert(&planet_id, planet)
+                    .expect("Failed to update planet");
+                self.notify(owner_chain, DominionMessage::StakeReturn { planet_id, position, resources });
+            }
+            
+            Message::HarvestPlanet { planet_id, owner } => {
+                let owner_chain = self.runtime.message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                if self.runtime.authenticated_signer() != Some(owner) {
+                    return;
+                }
+                let Some(mut planet) = self.state.planets.get(&planet_id).await
+                    .expect("Failed to read planet")
+                else {
+                    return;
+                };
+                if planet.owner_chain != owner_chain.to_string()
+                    || planet.owner != Some(owner)
+                    || self.expire_stake(&mut planet, now_micros)
+                {
+                    return;
+                }
+                let resources = planet.harvest(now_micros);
+                if resources.is_zero() {
+                    return;
+                }
+                let memo = Some(format!("Yield of {}", planet.name));
+                self.state.planets.insert(&planet_id, planet)
+                    .expect("Failed to update planet");
+                self.notify(owner_chain, DominionMessage::ResourceTransfer { from: owner, resources, memo });
+            }
+            
+            Message::CollectDebris { fleet_id, debris_id, collector } => {
+                let owner_chain = self.runtime.message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                if self.runtime.authenticated_signer() != Some(collector) {
+                    return;
+                }
+                // Only the chain owning the fleet, stationed on the debris, collects it
+                let Some(presence) = self.state.fleets.get(&(owner_chain, fleet_id)).await
+                    .expect("Failed to read fleet")
+                    .filter(|presence| presence.arrived_at_micros <= now_micros)
+                else {
+                    return;
+                };
+                let Some(debris) = self.state.debris.get(&debris_id).await
+                    .expect("Failed to read debris")
+                    .filter(|debris| (debris.x, debris.y) == (presence.position_x, presence.position_y))
+                else {
+                    return;
+                };
+                self.state.debris.remove(&debris_id)
+                    .expect("Failed to remove debris");
+                self.notify(owner_chain, DominionMessage::ResourceTransfer {
+                    from: collector,
+                    resources: debris.resources,
+                    memo: Some("Debris salvage".to_string()),
+                });
             }
             
             Message::BattleResolved { battle_id, .. } => {
                 // Battle completed - no resolved field in BattleRef
             }
             
+            Message::Notify { notification } => {
+                self.state.notifications.push_back(notification);
+            }
+            
             _ => {}
         }
     }
@@ -206,3 +451,374 @@ impl Contract for RegionContract {
         self.state.save().await.expect("Failed to save state");
     }
 }
+
+impl RegionContract {
+    /// Apply the Senate's parameter updates that are due by `now_micros`
+    ///
+    /// Only the Region Chain listens to the Senate: on user chains the
+    /// notifications belong to the dominion application.
+    fn update_config(&mut self, now_micros: u64) {
+        let senate_app = self.runtime.application_parameters().senate_app;
+        if let Some(senate_app) = senate_app.filter(|_| self.is_region_chain()) {
+            let response = self.runtime.call_application(
+                true,
+                senate_app.with_abi::<SenateAbi>(),
+                &SenateOperation::TakeNotifications,
+            );
+            if let Ok(SenateResponse::Notifications(notifications)) = response {
+                for notification in notifications {
+                    if let DominionMessage::ParameterUpdate { parameter_name, new_value, effective_from } = notification {
+                        self.state.config.get_mut().schedule(&parameter_name, &new_value, effective_from);
+                    }
+                }
+            }
+        }
+        if self.state.config.get().next_due(now_micros).is_some() {
+            self.state.config.get_mut().apply_due(now_micros);
+        }
+    }
+    
+    fn is_region_chain(&mut self) -> bool {
+        self.runtime.chain_id() == self.runtime.application_creator_chain_id()
+    }
+    
+    /// Relay operations act on behalf of the signer, so only the dominion
+    /// registered on this chain, which has already debited what they carry,
+    /// may call them
+    fn check_relay_caller(&mut self) -> Result<(), RegionError> {
+        let dominion_app = *self.state.dominion_app.get();
+        if dominion_app.is_none() || self.runtime.authenticated_caller_id() != dominion_app {
+            return Err(RegionError::RelayNotAllowed);
+        }
+        Ok(())
+    }
+    
+    /// Relay a stake from the signer to the Region Chain
+    ///
+    /// A claim reveals the colonizing fleet: its id, composition and salt.
+    fn send_stake(
+        &mut self,
+        planet_id: u64,
+        position: Coordinate,
+        resources: Resources,
+        colonizer: Option<(u64, Vec<u32>, String)>,
+    ) -> Result<(), RegionError> {
+        let (fleet_id, ship_counts, salt) = match colonizer {
+            Some((fleet_id, ship_counts, salt)) => (Some(fleet_id), ship_counts, salt),
+            None => (None, Vec::new(), String::new()),
+        };
+        let staker = self.runtime.authenticated_signer()
+            .ok_or(RegionError::NotAuthorized)?;
+        let staker_chain = self.runtime.chain_id();
+        let region_chain = self.runtime.application_creator_chain_id();
+        self.runtime
+            .prepare_message(Message::StakePlanet {
+                planet_id,
+                position,
+                staker,
+                staker_chain,
+                resources,
+                fleet_id,
+                ship_counts,
+                salt,
+            })
+            .with_authentication()
+            .with_tracking()
+            .send_to(region_chain);
+        Ok(())
+    }
+    
+    /// Add a stake to a planet, claiming it if unowned
+    ///
+    /// A claim needs `colonizer`, the id, composition and salt of the staker's
+    /// fleet holding a Colonizer at the planet. Returns the planet's new stake,
+    /// or `None` if the stake must go back: the planet is not at `position`, is
+    /// held by someone else, or the claim lacks the minimum stake, a Colonizer
+    /// or a composition matching the fleet's commitment.
+    async fn stake_planet(
+        &mut self,
+        planet_id: u64,
+        position: Coordinate,
+        staker: AccountOwner,
+        staker_chain: ChainId,
+        resources: Resources,
+        colonizer: Option<(u64, Vec<u32>, String)>,
+    ) -> Option<Resources> {
+        if self.runtime.authenticated_signer() != Some(staker) {
+            return None;
+        }
+        let mut planet = self.state.planets.get(&planet_id).await
+            .expect("Failed to read planet")
+            .filter(|planet| (planet.x, planet.y) == (position.x, position.y))?;
+        let now_micros = self.runtime.system_time().micros();
+        self.expire_stake(&mut planet, now_micros);
+        
+        if !planet.is_owned() {
+            let (fleet_id, ship_counts, salt) = colonizer?;
+            if ship_counts.get(ShipType::Colonizer as usize).is_none_or(|count| *count == 0)
+                || resources.total().is_none_or(|total| total < MINIMUM_PLANET_STAKE)
+            {
+                return None;
+            }
+            let presence = self.state.fleets.get(&(staker_chain, fleet_id)).await
+                .expect("Failed to read fleet")
+                .filter(|presence| (presence.position_x, presence.position_y) == (position.x, position.y))
+                .filter(|presence| verify_ship_counts(fleet_id, &ship_counts, &salt, &presence.commitment_hash))?;
+            // Colonizing gives the fleet away
+            self.reveal_fleet(staker_chain, presence, ship_counts).await;
+            
+            planet.owner_chain = staker_chain.to_string();
+            planet.owner = Some(staker);
+            planet.stake = resources;
+            planet.harvested_micros = now_micros;
+        } else if planet.owner_chain == staker_chain.to_string() && planet.owner == Some(staker) {
+            // Resupplying restarts the decay from the stake left now
+            planet.stake = planet.stake_at(now_micros, self.state.config.get())
+                .checked_add(&resources)
+                .ok()?;
+        } else {
+            return None;
+        }
+        
+        planet.stake_updated_micros = now_micros;
+        let stake = planet.stake;
+        self.state.planets.insert(&planet_id, planet)
+            .expect("Failed to update planet");
+        Some(stake)
+    }
+    
+    /// Revert a planet to unclaimed if its stake has decayed below the minimum
+    ///
+    /// What is left of the stake goes back to the owner. Returns whether the
+    /// planet reverted.
+    fn expire_stake(&mut self, planet: &mut PlanetData, now_micros: u64) -> bool {
+        let config = self.state.config.get();
+        if !planet.is_owned() || planet.expires_micros(config).is_none_or(|expiry| expiry > now_micros) {
+            return false;
+        }
+        let owner_chain = planet.owner_chain.parse::<ChainId>()
+            .expect("Planet owners are chain ids");
+        let resources = planet.release(now_micros, config);
+        self.state.planets.insert(&planet.id, planet.clone())
+            .expect("Failed to update planet");
+        self.notify(owner_chain, DominionMessage::StakeReturn {
+            planet_id: planet.id,
+            position: Coordinate::new(planet.x, planet.y),
+            resources,
+        });
+        true
+    }
+    
+    /// Check the next `STAKE_DECAY_BATCH_SIZE` planets for expired stakes
+    ///
+    /// Each sweep resumes after the last planet of the previous one, starting
+    /// over once every planet was checked.
+    async fn sweep_stake_decay(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        let cursor = *self.state.decay_cursor.get() as usize;
+        let end = cursor.saturating_add(STAKE_DECAY_BATCH_SIZE).min(self.state.planet_ids.count());
+        let batch = self.state.planet_ids.read(cursor..end).await?;
+        for planet_id in &batch {
+            if let Some(mut planet) = self.state.planets.get(planet_id).await? {
+                self.expire_stake(&mut planet, now_micros);
+            }
+        }
+        let next = if end < self.state.planet_ids.count() { end } else { 0 };
+        self.state.decay_cursor.set(next as u64);
+        Ok(())
+    }
+    
+    /// Record the composition and tech-adjusted stats of a fleet of `owner_chain`
+    async fn reveal_fleet(&mut self, owner_chain: ChainId, mut presence: FleetPresenceData, ship_counts: Vec<u32>) {
+        let stats = FleetStats::from_counts(&ship_counts, &presence.modifiers, self.state.config.get());
+        presence.revealed = true;
+        presence.revealed_attack = stats.attack;
+        presence.revealed_defense = stats.defense;
+        presence.ship_counts = ship_counts;
+        self.state.insert_fleet(owner_chain, presence).await
+            .expect("Failed to reveal fleet");
+    }
+    
+    /// Check whether a fleet may enter this sector at (x, y)
+    async fn admit_fleet(
+        &mut self,
+        owner: AccountOwner,
+        x: i64,
+        y: i64,
+    ) -> Result<(), FleetReturnReason> {
+        if self.runtime.authenticated_signer() != Some(owner) {
+            return Err(FleetReturnReason::AccessDenied);
+        }
+        
+        let sector_size = self.runtime.application_parameters().sector_size as i64;
+        let sector = SectorCoordinate::new(*self.state.sector_x.get(), *self.state.sector_y.get());
+        if !Coordinate::new(x, y).in_sector(&sector, sector_size) {
+            return Err(FleetReturnReason::InvalidDestination);
+        }
+        
+        let present = *self.state.fleet_count.get();
+        if *self.state.is_subdivided.get() || present >= MAX_UNITS_PER_SECTOR as u64 {
+            return Err(FleetReturnReason::SectorFull);
+        }
+        Ok(())
+    }
+    
+    /// Send a notification to the dominion application of a user chain
+    fn notify(&mut self, user_chain: ChainId, notification: DominionMessage) {
+        self.runtime
+            .prepare_message(Message::Notify { notification })
+            .send_to(user_chain);
+    }
+}
+
+/// Whether `presence` records fleet `fleet_id` of `owner_chain`
+fn is_fleet(presence: &FleetPresenceData, owner_chain: ChainId, fleet_id: u64) -> bool {
+    presence.fleet_id == fleet_id && presence.owner_chain == owner_chain.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        linera_base_types::{ApplicationId, CryptoHash},
+        util::BlockingWait,
+    };
+    use linera_dominion_common::research::TechModifiers;
+
+    use super::*;
+
+    fn chain(name: &str) -> ChainId {
+        ChainId(CryptoHash::test_hash(name))
+    }
+
+    fn owner(name: &str) -> AccountOwner {
+        AccountOwner::from(CryptoHash::test_hash(name))
+    }
+
+    fn application(name: &str) -> ApplicationId {
+        ApplicationId::new(CryptoHash::test_hash(name))
+    }
+
+    /// The region application of sector (0, 0) on `chain_id`, created on the `region` chain
+    fn create_region(chain_id: ChainId) -> RegionContract {
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(RegionParameters::default())
+            .with_application_creator_chain_id(chain("region"))
+            .with_chain_id(chain_id)
+            .with_system_time(Timestamp::from(1_000_000));
+        let mut contract = RegionContract {
+            state: RegionState::load(runtime.root_view_storage_context())
+                .blocking_wait()
+                .expect("Failed to read from mock key value store"),
+            runtime,
+        };
+        contract.instantiate(()).now_or_never().expect("Instantiation should not await anything");
+        contract
+    }
+
+    fn execute(contract: &mut RegionContract, operation: Operation) -> Result<RegionResponse, RegionError> {
+        contract.execute_operation(operation).now_or_never().expect("Operation should not await anything")
+    }
+
+    fn fleet_enter(fleet_id: u64, owner: AccountOwner, x: i64, y: i64) -> Message {
+        Message::FleetEnter {
+            fleet_id,
+            owner,
+            owner_chain: chain("player"),
+            x,
+            y,
+            commitment_hash: String::new(),
+            arrival_micros: 1_000_000,
+            modifiers: TechModifiers::default(),
+        }
+    }
+
+    fn enter(contract: &mut RegionContract, origin: ChainId, signer: AccountOwner, message: Message) {
+        contract.runtime.set_message_origin_chain_id(origin);
+        contract.runtime.set_message_is_bouncing(false);
+        contract.runtime.set_authenticated_signer(signer);
+        contract.execute_message(message).now_or_never().expect("Message should not await anything");
+    }
+
+    #[test]
+    fn test_relay_only_from_registered_dominion() {
+        let mut region = create_region(chain("player"));
+        region.runtime.set_authenticated_caller_id(application("dominion"));
+        assert!(matches!(
+            execute(&mut region, Operation::LeaveFleet { fleet_id: 1 }),
+            Err(RegionError::RelayNotAllowed)
+        ));
+
+        execute(&mut region, Operation::RegisterDominion).expect("The first registration should count");
+        region.runtime.set_authenticated_caller_id(application("intruder"));
+        assert!(matches!(execute(&mut region, Operation::RegisterDominion), Err(RegionError::NotAuthorized)));
+        assert!(matches!(
+            execute(&mut region, Operation::LeaveFleet { fleet_id: 1 }),
+            Err(RegionError::RelayNotAllowed)
+        ));
+
+        region.runtime.set_authenticated_caller_id(application("dominion"));
+        execute(&mut region, Operation::LeaveFleet { fleet_id: 1 }).expect("The dominion may relay");
+        let sent = region.runtime.created_send_message_requests();
+        assert!(sent.iter().any(|request| {
+            request.destination == chain("region") && matches!(request.message, Message::FleetLeave { fleet_id: 1 })
+        }));
+    }
+
+    #[test]
+    fn test_fleet_enter_confirms_arrival() {
+        let mut region = create_region(chain("region"));
+
+        // A fleet signed for by someone else is sent back
+        enter(&mut region, chain("player"), owner("intruder"), fleet_enter(1, owner("player"), 5, 5));
+        assert!(region.state.fleets.get(&(chain("player"), 1)).blocking_wait().unwrap().is_none());
+
+        enter(&mut region, chain("player"), owner("player"), fleet_enter(2, owner("player"), 5, 5));
+        let presence = region.state.fleets.get(&(chain("player"), 2)).blocking_wait().unwrap()
+            .expect("The fleet should be recorded");
+        assert_eq!((presence.position_x, presence.position_y), (5, 5));
+        assert_eq!(region.state.fleets_at(Coordinate::new(5, 5)).blocking_wait().unwrap().len(), 1);
+
+        let sent = region.runtime.created_send_message_requests();
+        assert!(sent.iter().any(|request| {
+            request.destination == chain("player")
+                && matches!(
+                    request.message,
+                    Message::Notify {
+                        notification: DominionMessage::FleetReturn { reason: FleetReturnReason::AccessDenied, .. },
+                    }
+                )
+        }));
+        assert!(sent.iter().any(|request| {
+            request.destination == chain("player")
+                && matches!(
+                    request.message,
+                    Message::Notify { notification: DominionMessage::FleetArrivalConfirmed { fleet_id: 2, .. } }
+                )
+        }));
+    }
+
+    #[test]
+    fn test_scan_requires_nearby_scanner() {
+        let mut region = create_region(chain("region"));
+        enter(&mut region, chain("player"), owner("player"), fleet_enter(1, owner("player"), 5, 5));
+        enter(&mut region, chain("scout"), owner("scout"), fleet_enter(1, owner("scout"), 5 + SCAN_RANGE * 3, 5));
+        let scan = Operation::ScanFleet {
+            target_chain: chain("player"),
+            target_fleet_id: 1,
+            scanner_chain: chain("scout"),
+            scanner_fleet_id: 1,
+        };
+        assert!(matches!(execute(&mut region, scan), Err(RegionError::OutOfRange)));
+
+        enter(&mut region, chain("scout"), owner("scout"), fleet_enter(2, owner("scout"), 6, 5));
+        let scan = Operation::ScanFleet {
+            target_chain: chain("player"),
+            target_fleet_id: 1,
+            scanner_chain: chain("scout"),
+            scanner_fleet_id: 2,
+        };
+        execute(&mut region, scan).expect("A scanner in range should reveal the fleet");
+        let presence = region.state.fleets.get(&(chain("player"), 1)).blocking_wait().unwrap().unwrap();
+        assert!(presence.revealed);
+    }
+}
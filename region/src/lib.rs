@@ -7,9 +7,13 @@ pub mod state;
 use async_graphql::{Request, Response, InputObject, SimpleObject, Enum};
 use linera_sdk::{
     graphql::GraphQLMutationRoot,
-    linera_base_types::{AccountOwner, ChainId, Timestamp},
+    linera_base_types::{AccountOwner, ApplicationId, ChainId, Timestamp},
     views::{linera_views, RootView, ViewStorageContext},
 };
+use linera_dominion_common::{
+    config::GameConfig, constants::SECTOR_SIZE, coordinates::Coordinate, messages::DominionMessage, research::TechModifiers,
+    resources::Resources,
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -40,56 +44,92 @@ pub struct CoordinateInput {
     pub y: i64,
 }
 
-/// Resource stake input
-#[derive(Debug, Clone, Serialize, Deserialize, InputObject)]
-pub struct StakeInput {
-    pub iron: u64,
-    pub deuterium: u64,
-    pub crystals: u64,
-}
-
 // ==================== OPERATIONS ====================
 
 /// Operations on Region Chain
 #[derive(Debug, Serialize, Deserialize, GraphQLMutationRoot)]
 pub enum Operation {
-    /// Claim a planet with stake
+    /// Scan a fleet (force reveal) with a fleet within `SCAN_RANGE` of it
+    ScanFleet {
+        target_chain: ChainId,
+        target_fleet_id: u64,
+        scanner_chain: ChainId,
+        scanner_fleet_id: u64,
+    },
+    
+    /// Declare hostility (trigger combat) on a fleet at the attacker's position
+    DeclareHostility {
+        attacker_chain: ChainId,
+        attacker_fleet_id: u64,
+        target_chain: ChainId,
+        target_fleet_id: u64,
+    },
+    
+    /// Process stake decay
+    ProcessStakeDecay,
+    
+    // ===== Relay operations =====
+    // Called by the dominion application on a user chain, which cannot send
+    // region messages itself.
+    
+    /// Make the calling application the dominion of this chain
+    ///
+    /// Only the first registration counts: the dominion registers when it is created.
+    RegisterDominion,
+    
+    /// Announce one of the signer's fleets to the Region Chain owning its destination
+    EnterFleet {
+        fleet_id: u64,
+        position: Coordinate,
+        commitment_hash: String,
+        arrival_micros: u64,
+        modifiers: TechModifiers,
+    },
+    
+    /// Withdraw one of this chain's fleets from the Region Chain, e.g. when recalled
+    LeaveFleet {
+        fleet_id: u64,
+    },
+    
+    /// Claim the unowned planet at `position` for the signer
+    ///
+    /// The caller has already debited `stake`. `ship_counts` is the composition
+    /// of the signer's fleet `fleet_id`, which must hold a Colonizer at the
+    /// planet; claiming reveals it, with the hex `salt` of its commitment.
     ClaimPlanet {
         planet_id: u64,
-        stake: StakeInput,
+        position: Coordinate,
+        fleet_id: u64,
+        ship_counts: Vec<u32>,
+        salt: String,
+        stake: Resources,
     },
     
-    /// Resupply planet stake
+    /// Add to the stake of one of the signer's planets
     ResupplyPlanet {
         planet_id: u64,
-        additional_stake: StakeInput,
+        position: Coordinate,
+        additional_stake: Resources,
     },
     
-    /// Abandon a planet
+    /// Give up one of the signer's planets, returning its stake
     AbandonPlanet {
         planet_id: u64,
     },
     
-    /// Scan a fleet (force reveal)
-    ScanFleet {
-        target_fleet_id: u64,
-        scanner_fleet_id: u64,
-    },
-    
-    /// Declare hostility (trigger combat)
-    DeclareHostility {
-        attacker_fleet_id: u64,
-        target_fleet_id: u64,
+    /// Collect the yield accrued by one of the signer's planets
+    HarvestPlanet {
+        planet_id: u64,
     },
     
-    /// Collect debris
+    /// Collect debris with one of this chain's fleets stationed at its position
     CollectDebris {
         fleet_id: u64,
         debris_id: u64,
     },
     
-    /// Process stake decay
-    ProcessStakeDecay,
+    /// Hand over the notifications Region Chains sent to this chain, oldest first
+    TakeNotifications,
 }
 
 // ==================== MESSAGES ====================
@@ -105,6 +145,10 @@ pub enum Message {
         x: i64,
         y: i64,
         commitment_hash: String,
+        /// When the fleet reaches (x, y)
+        arrival_micros: u64,
+        /// Research modifiers of the owner, applied to the fleet's stats
+        modifiers: TechModifiers,
     },
     
     /// Fleet leaving sector
@@ -131,8 +175,41 @@ pub enum Message {
     BattleResolved {
         battle_id: u64,
         winner_fleet_id: Option<u64>,
-        debris_iron: u64,
-        debris_deuterium: u64,
+        debris: Resources,
+    },
+    
+    /// Stake for the planet at `position`, claiming it if it has no owner
+    ///
+    /// A claim names the staker's fleet holding a Colonizer there and reveals
+    /// its composition; a resupply of an owned planet names none.
+    StakePlanet {
+        planet_id: u64,
+        position: Coordinate,
+        staker: AccountOwner,
+        staker_chain: ChainId,
+        resources: Resources,
+        fleet_id: Option<u64>,
+        ship_counts: Vec<u32>,
+        salt: String,
+    },
+    
+    /// Give up a planet, returning its stake to the owner's chain
+    UnstakePlanet {
+        planet_id: u64,
+        owner: AccountOwner,
+    },
+    
+    /// Ship the yield accrued by a planet to the owner's chain
+    HarvestPlanet {
+        planet_id: u64,
+        owner: AccountOwner,
+    },
+    
+    /// Ship a debris field to the chain of the fleet at its position
+    CollectDebris {
+        fleet_id: u64,
+        debris_id: u64,
+        collector: AccountOwner,
     },
     
     /// Planet claim notification
@@ -141,6 +218,24 @@ pub enum Message {
         owner: AccountOwner,
         owner_chain: ChainId,
     },
+    
+    /// Notification for the dominion application of a user chain
+    ///
+    /// Queued on the user chain until the dominion takes it with
+    /// `Operation::TakeNotifications`.
+    Notify {
+        notification: DominionMessage,
+    },
+}
+
+// ==================== RESPONSES ====================
+
+/// Operation responses
+#[derive(Debug, Serialize, Deserialize)]
+pub enum RegionResponse {
+    Ok,
+    /// Notifications taken from the queue, oldest first
+    Notifications(Vec<DominionMessage>),
 }
 
 // ==================== ERRORS ====================
@@ -160,20 +255,41 @@ pub enum RegionError {
     #[error("Fleet not in sector")]
     FleetNotInSector,
     
+    #[error("Debris not found: {0}")]
+    DebrisNotFound(u64),
+    
     #[error("Invalid reveal")]
     InvalidReveal,
     
     #[error("Not authorized")]
     NotAuthorized,
     
-    #[error("Insufficient stake")]
-    InsufficientStake,
+    #[error("Insufficient stake: {0} at least")]
+    InsufficientStake(u128),
+    
+    #[error("A Colonizer is required to claim a planet")]
+    NoColonizer,
     
     #[error("Sector is subdivided")]
     SectorSubdivided,
     
     #[error("Battle already in progress")]
     BattleInProgress,
+    
+    #[error("Target out of range")]
+    OutOfRange,
+    
+    #[error("Area too large: at most {0} index cells along each axis")]
+    AreaTooLarge(i64),
+    
+    #[error("Relay operations must be called by the chain's dominion application")]
+    RelayNotAllowed,
+    
+    #[error("Stake overflow")]
+    StakeOverflow,
+    
+    #[error("Storage error")]
+    StorageError,
 }
 
 // ==================== PARAMETERS ====================
@@ -189,6 +305,12 @@ pub struct RegionParameters {
     pub universe_seed: u64,
     /// Sector size
     pub sector_size: u64,
+    /// Game configuration the sector starts with
+    #[serde(default)]
+    pub config: GameConfig,
+    /// Senate application whose parameter updates the Region Chain applies
+    #[serde(default)]
+    pub senate_app: Option<ApplicationId>,
 }
 
 impl Default for RegionParameters {
@@ -197,7 +319,9 @@ impl Default for RegionParameters {
             sector_x: 0,
             sector_y: 0,
             universe_seed: 0,
-            sector_size: 1000,
+            sector_size: SECTOR_SIZE as u64,
+            config: GameConfig::default(),
+            senate_app: None,
         }
     }
 }
@@ -209,7 +333,7 @@ pub struct RegionAbi;
 
 impl linera_sdk::abi::ContractAbi for RegionAbi {
     type Operation = Operation;
-    type Response = Result<(), RegionError>;
+    type Response = Result<RegionResponse, RegionError>;
 }
 
 impl linera_sdk::abi::ServiceAbi for RegionAbi {
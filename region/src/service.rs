@@ -2,17 +2,19 @@
 
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
-use async_graphql::{Object, Request, Response};
+use std::sync::Arc;
+use async_graphql::{Object, Request, Response, SimpleObject};
 use linera_sdk::{
     linera_base_types::WithServiceAbi,
     Service, ServiceRuntime,
     views::View,
 };
 use linera_dominion_region::RegionAbi;
-use linera_dominion_region::state::RegionState;
+use linera_dominion_region::state::{FleetPresenceData, PlanetData, RegionState};
+use linera_dominion_common::{config::GameConfig, coordinates::Coordinate, resources::Resources};
 
 pub struct RegionService {
-    state: RegionState,
+    state: Arc<RegionState>,
     runtime: ServiceRuntime<Self>,
 }
 
@@ -29,7 +31,10 @@ impl Service for RegionService {
         let state = RegionState::load(runtime.root_view_storage_context())
             .await
             .expect("Failed to load state");
-        Self { state, runtime }
+        Self {
+            state: Arc::new(state),
+            runtime,
+        }
     }
 
     async fn handle_query(&self, request: Request) -> Response {
@@ -41,6 +46,17 @@ impl Service for RegionService {
         let debris_count = *self.state.debris_count.get();
         let battle_count = *self.state.battle_count.get();
         let is_subdivided = *self.state.is_subdivided.get();
+        let now_micros = self.runtime.system_time().micros();
+        let config = self.state.config.get();
+        
+        let mut planets = Vec::new();
+        self.state.planets
+            .for_each_index_value(|_, planet| {
+                planets.push(PlanetView::new(planet.into_owned(), now_micros, config));
+                Ok(())
+            })
+            .await
+            .expect("Failed to read planets");
         
         let schema = async_graphql::Schema::build(
             QueryRoot {
@@ -52,6 +68,8 @@ impl Service for RegionService {
                 debris_count,
                 battle_count,
                 is_subdivided,
+                planets,
+                state: self.state.clone(),
             },
             async_graphql::EmptyMutation,
             async_graphql::EmptySubscription,
@@ -70,6 +88,8 @@ struct QueryRoot {
     debris_count: u64,
     battle_count: u64,
     is_subdivided: bool,
+    planets: Vec<PlanetView>,
+    state: Arc<RegionState>,
 }
 
 #[Object]
@@ -105,4 +125,65 @@ impl QueryRoot {
     async fn is_subdivided(&self) -> bool {
         self.is_subdivided
     }
+
+    /// Planets of the sector in id order, optionally only the unclaimed ones
+    async fn planets(&self, unclaimed: Option<bool>, offset: Option<usize>, limit: Option<usize>) -> Vec<&PlanetView> {
+        self.planets
+            .iter()
+            .filter(|view| unclaimed.is_none_or(|unclaimed| view.planet.is_owned() != unclaimed))
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    async fn planet(&self, planet_id: u64) -> Option<&PlanetView> {
+        self.planets.iter().find(|view| view.planet.id == planet_id)
+    }
+
+    /// Fleets in the rectangle from (min_x, min_y) to (max_x, max_y), by
+    /// default the whole sector
+    ///
+    /// Larger areas are refused rather than cut short: query them in parts.
+    async fn fleets(
+        &self,
+        min_x: Option<i64>,
+        min_y: Option<i64>,
+        max_x: Option<i64>,
+        max_y: Option<i64>,
+    ) -> async_graphql::Result<Vec<FleetPresenceData>> {
+        let sector_size = *self.state.sector_size.get() as i64;
+        let sector_min = Coordinate::new(self.sector_x * sector_size, self.sector_y * sector_size);
+        let min = Coordinate::new(min_x.unwrap_or(sector_min.x), min_y.unwrap_or(sector_min.y));
+        let max = Coordinate::new(
+            max_x.unwrap_or(sector_min.x + sector_size - 1),
+            max_y.unwrap_or(sector_min.y + sector_size - 1),
+        );
+        let fleets = self.state.fleets_in_area(min, max).await??;
+        Ok(fleets)
+    }
+}
+
+/// A planet with its stake decayed to the time of the query
+#[derive(SimpleObject)]
+struct PlanetView {
+    #[graphql(flatten)]
+    planet: PlanetData,
+    current_stake: Resources,
+    /// When the stake falls below the minimum and the planet reverts to
+    /// unclaimed, unless resupplied; none while unclaimed or not decaying
+    expires_micros: Option<u64>,
+    /// Yield the owner can harvest now
+    pending_yield: Resources,
+}
+
+impl PlanetView {
+    fn new(planet: PlanetData, now_micros: u64, config: &GameConfig) -> Self {
+        let owned = planet.is_owned();
+        Self {
+            current_stake: planet.stake_at(now_micros, config),
+            expires_micros: planet.expires_micros(config).filter(|_| owned),
+            pending_yield: planet.pending_yield(now_micros),
+            planet,
+        }
+    }
 }
@@ -1,9 +1,37 @@
 //! State management for the Region Chain
 
 use async_graphql::SimpleObject;
-use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext};
+use linera_dominion_common::{
+    config::GameConfig,
+    constants::MINIMUM_PLANET_STAKE,
+    coordinates::Coordinate,
+    crypto::SeededPlanet,
+    messages::DominionMessage,
+    research::TechModifiers,
+    types::PlanetType,
+    resources::{calculate_stake_decay, stake_lifetime_hours, Resources},
+};
+use linera_sdk::linera_base_types::{AccountOwner, ApplicationId, ChainId};
+use linera_sdk::views::{
+    linera_views, LogView, MapView, QueueView, RegisterView, RootView, ViewError, ViewStorageContext,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::RegionError;
+
+const HOUR_MICROS: u64 = 3_600 * 1_000_000;
+
+/// Side of the square cells of the fleet spatial index
+const FLEET_CELL_SIZE: i64 = 10;
+
+/// Most index cells an area query may read along each axis
+const MAX_AREA_CELLS: i64 = 16;
+
+/// A fleet present in the sector: its owner's chain and its id there
+///
+/// Fleet ids are only unique per user chain.
+pub type FleetKey = (ChainId, u64);
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct FleetPresenceData {
     pub fleet_id: u64,
@@ -13,6 +41,14 @@ pub struct FleetPresenceData {
     pub commitment_hash: String,
     pub arrived_at_micros: u64,
     pub revealed: bool,
+    /// Research modifiers of the fleet owner
+    pub modifiers: TechModifiers,
+    /// Ship counts by type, known once the fleet is revealed
+    pub ship_counts: Vec<u32>,
+    /// Tech-adjusted attack of the revealed fleet
+    pub revealed_attack: u64,
+    /// Tech-adjusted defense of the revealed fleet
+    pub revealed_defense: u64,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
@@ -21,20 +57,72 @@ pub struct PlanetData {
     pub x: i64,
     pub y: i64,
     pub planet_type: u8,
+    /// Empty while the planet is unowned
     pub owner_chain: String,
-    pub stake_iron: u64,
-    pub stake_deuterium: u64,
-    pub stake_crystals: u64,
+    /// Signer who claimed the planet
+    pub owner: Option<AccountOwner>,
+    /// Stake as of `stake_updated_micros`, decaying linearly from then on
+    pub stake: Resources,
     pub stake_updated_micros: u64,
+    /// Yield accrues to the owner from here on
+    pub harvested_micros: u64,
     pub name: String,
 }
 
+impl PlanetData {
+    pub fn is_owned(&self) -> bool {
+        !self.owner_chain.is_empty()
+    }
+    
+    /// Stake left at `now_micros`
+    pub fn stake_at(&self, now_micros: u64, config: &GameConfig) -> Resources {
+        let elapsed_hours = now_micros.saturating_sub(self.stake_updated_micros) / HOUR_MICROS;
+        calculate_stake_decay(&self.stake, elapsed_hours, config)
+    }
+    
+    /// When the stake falls below the minimum and the planet reverts to unclaimed
+    pub fn expires_micros(&self, config: &GameConfig) -> Option<u64> {
+        let hours = stake_lifetime_hours(&self.stake, MINIMUM_PLANET_STAKE, config)?;
+        Some(self.stake_updated_micros.saturating_add(hours.saturating_mul(HOUR_MICROS)))
+    }
+    
+    /// Yield accrued for the owner by `now_micros`, in whole hours
+    pub fn pending_yield(&self, now_micros: u64) -> Resources {
+        let Some(planet_type) = PlanetType::from_u8(self.planet_type).filter(|_| self.is_owned()) else {
+            return Resources::zero();
+        };
+        let hours = now_micros.saturating_sub(self.harvested_micros) / HOUR_MICROS;
+        planet_type.hourly_yield().map(|amount| amount.saturating_mul(hours as u128))
+    }
+    
+    /// Take the accrued yield
+    ///
+    /// The part of an hour not yet complete keeps accruing.
+    pub fn harvest(&mut self, now_micros: u64) -> Resources {
+        let harvested = self.pending_yield(now_micros);
+        let hours = now_micros.saturating_sub(self.harvested_micros) / HOUR_MICROS;
+        self.harvested_micros = self.harvested_micros.saturating_add(hours * HOUR_MICROS);
+        harvested
+    }
+    
+    /// Drop the owner, returning the stake left at `now_micros`
+    ///
+    /// Yield not harvested yet is lost with the planet.
+    pub fn release(&mut self, now_micros: u64, config: &GameConfig) -> Resources {
+        let remaining = self.stake_at(now_micros, config);
+        self.owner_chain = String::new();
+        self.owner = None;
+        self.stake = Resources::zero();
+        self.stake_updated_micros = now_micros;
+        remaining
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct DebrisData {
     pub x: i64,
     pub y: i64,
-    pub iron: u64,
-    pub deuterium: u64,
+    pub resources: Resources,
     pub created_at_micros: u64,
 }
 
@@ -52,22 +140,43 @@ pub struct BattleRef {
 pub struct RegionState {
     pub sector_x: RegisterView<i64>,
     pub sector_y: RegisterView<i64>,
+    pub sector_size: RegisterView<u64>,
     pub universe_seed: RegisterView<u64>,
-    pub fleets: MapView<u64, FleetPresenceData>,
+    #[graphql(skip)]
+    pub fleets: MapView<FleetKey, FleetPresenceData>,
+    /// Fleets present in the sector
     pub fleet_count: RegisterView<u64>,
+    /// Spatial index: the fleets in each `FLEET_CELL_SIZE` square cell
+    #[graphql(skip)]
+    pub fleet_cells: MapView<(i64, i64), Vec<FleetKey>>,
     pub planets: MapView<u64, PlanetData>,
     pub planet_count: RegisterView<u64>,
+    /// Ids of the planets in `planets`, in id order
+    #[graphql(skip)]
+    pub planet_ids: LogView<u64>,
     pub debris: MapView<u64, DebrisData>,
     pub debris_count: RegisterView<u64>,
     pub battles: MapView<u64, BattleRef>,
     pub battle_count: RegisterView<u64>,
     pub is_subdivided: RegisterView<bool>,
+    /// Game configuration, updated by the Senate
+    pub config: RegisterView<GameConfig>,
+    /// Position in `planet_ids` of the first planet the next stake decay sweep checks
+    #[graphql(skip)]
+    pub decay_cursor: RegisterView<u64>,
+    /// On user chains: notifications from Region Chains awaiting the dominion application
+    #[graphql(skip)]
+    pub notifications: QueueView<DominionMessage>,
+    /// On user chains: the dominion application allowed to call relay operations
+    #[graphql(skip)]
+    pub dominion_app: RegisterView<Option<ApplicationId>>,
 }
 
 impl RegionState {
-    pub fn initialize(&mut self, sector_x: i64, sector_y: i64, universe_seed: u64) {
+    pub fn initialize(&mut self, sector_x: i64, sector_y: i64, sector_size: u64, universe_seed: u64) {
         self.sector_x.set(sector_x);
         self.sector_y.set(sector_y);
+        self.sector_size.set(sector_size);
         self.universe_seed.set(universe_seed);
         self.fleet_count.set(0);
         self.planet_count.set(0);
@@ -75,4 +184,123 @@ impl RegionState {
         self.battle_count.set(0);
         self.is_subdivided.set(false);
     }
+    
+    /// Store the planets of a freshly generated sector, unowned
+    pub fn seed_planets(&mut self, planets: Vec<SeededPlanet>) -> Result<(), ViewError> {
+        let planet_count = planets.len() as u64;
+        for planet in planets {
+            self.planet_ids.push(planet.id);
+            self.planets.insert(&planet.id, PlanetData {
+                id: planet.id,
+                x: planet.x,
+                y: planet.y,
+                planet_type: planet.planet_type as u8,
+                name: planet.name,
+                ..PlanetData::default()
+            })?;
+        }
+        self.planet_count.set(planet_count);
+        Ok(())
+    }
+    
+    /// Record the presence of a fleet of `owner_chain`, moving it in the
+    /// spatial index if it was already in the sector
+    pub async fn insert_fleet(&mut self, owner_chain: ChainId, presence: FleetPresenceData) -> Result<(), ViewError> {
+        let key = (owner_chain, presence.fleet_id);
+        let cell = fleet_cell(presence.position_x, presence.position_y);
+        match self.fleets.get(&key).await? {
+            Some(previous) => {
+                let previous_cell = fleet_cell(previous.position_x, previous.position_y);
+                if previous_cell != cell {
+                    self.unindex_fleet(previous_cell, key).await?;
+                    self.index_fleet(cell, key).await?;
+                }
+            }
+            None => {
+                self.index_fleet(cell, key).await?;
+                self.fleet_count.set(*self.fleet_count.get() + 1);
+            }
+        }
+        self.fleets.insert(&key, presence)
+    }
+    
+    /// Remove a fleet of `owner_chain` from the sector, returning its presence record
+    pub async fn remove_fleet(&mut self, owner_chain: ChainId, fleet_id: u64) -> Result<Option<FleetPresenceData>, ViewError> {
+        let key = (owner_chain, fleet_id);
+        let Some(presence) = self.fleets.get(&key).await? else {
+            return Ok(None);
+        };
+        self.unindex_fleet(fleet_cell(presence.position_x, presence.position_y), key).await?;
+        self.fleets.remove(&key)?;
+        self.fleet_count.set(self.fleet_count.get().saturating_sub(1));
+        Ok(Some(presence))
+    }
+    
+    /// Fleets in the rectangle from `min` to `max`, bounds included
+    ///
+    /// Only the index cells overlapping the rectangle are read. Rectangles
+    /// spanning more than `MAX_AREA_CELLS` cells along an axis are refused.
+    pub async fn fleets_in_area(
+        &self,
+        min: Coordinate,
+        max: Coordinate,
+    ) -> Result<Result<Vec<FleetPresenceData>, RegionError>, ViewError> {
+        let (min_x, min_y) = fleet_cell(min.x, min.y);
+        let (max_x, max_y) = fleet_cell(max.x, max.y);
+        if max_x.saturating_sub(min_x) >= MAX_AREA_CELLS || max_y.saturating_sub(min_y) >= MAX_AREA_CELLS {
+            return Ok(Err(RegionError::AreaTooLarge(MAX_AREA_CELLS)));
+        }
+        let mut fleets = Vec::new();
+        for cell_x in min_x..=max_x {
+            for cell_y in min_y..=max_y {
+                for key in self.fleet_cells.get(&(cell_x, cell_y)).await?.unwrap_or_default() {
+                    let Some(presence) = self.fleets.get(&key).await? else {
+                        continue;
+                    };
+                    if (min.x..=max.x).contains(&presence.position_x) && (min.y..=max.y).contains(&presence.position_y) {
+                        fleets.push(presence);
+                    }
+                }
+            }
+        }
+        Ok(Ok(fleets))
+    }
+    
+    /// Fleets at `position`
+    pub async fn fleets_at(&self, position: Coordinate) -> Result<Vec<FleetPresenceData>, ViewError> {
+        // A single position lies in a single cell
+        Ok(self.fleets_in_area(position, position).await?.unwrap_or_default())
+    }
+    
+    /// Fleets within `range` of `position` along each axis
+    pub async fn fleets_near(
+        &self,
+        position: Coordinate,
+        range: i64,
+    ) -> Result<Result<Vec<FleetPresenceData>, RegionError>, ViewError> {
+        let min = Coordinate::new(position.x.saturating_sub(range), position.y.saturating_sub(range));
+        let max = Coordinate::new(position.x.saturating_add(range), position.y.saturating_add(range));
+        self.fleets_in_area(min, max).await
+    }
+    
+    async fn index_fleet(&mut self, cell: (i64, i64), key: FleetKey) -> Result<(), ViewError> {
+        let mut keys = self.fleet_cells.get(&cell).await?.unwrap_or_default();
+        keys.push(key);
+        self.fleet_cells.insert(&cell, keys)
+    }
+    
+    async fn unindex_fleet(&mut self, cell: (i64, i64), key: FleetKey) -> Result<(), ViewError> {
+        let mut keys = self.fleet_cells.get(&cell).await?.unwrap_or_default();
+        keys.retain(|indexed| *indexed != key);
+        if keys.is_empty() {
+            self.fleet_cells.remove(&cell)
+        } else {
+            self.fleet_cells.insert(&cell, keys)
+        }
+    }
+}
+
+/// Cell of the fleet spatial index containing (x, y)
+fn fleet_cell(x: i64, y: i64) -> (i64, i64) {
+    (x.div_euclid(FLEET_CELL_SIZE), y.div_euclid(FLEET_CELL_SIZE))
 }
@@ -80,27 +80,9 @@ fi
 CHAIN_ID=$(linera wallet show | grep "Default Chain" | awk '{print $NF}')
 echo "  Chain ID: $CHAIN_ID"
 
-# Step 4: Deploy Dominion Application
+# Step 4: Deploy Region Application
 echo ""
-echo -e "${BLUE}[4/6] Deploying Dominion (User Chain) application...${NC}"
-
-DOMINION_APP=$(linera publish-and-create \
-    "$WASM_DIR/linera_dominion_contract.wasm" \
-    "$WASM_DIR/linera_dominion_service.wasm" \
-    --json-parameters '{
-        "home_x": 100,
-        "home_y": 200,
-        "starting_iron": 10000,
-        "starting_deuterium": 5000,
-        "starting_crystals": 2500,
-        "universe_seed": 12345
-    }' 2>&1 | tail -1)
-
-echo -e "${GREEN}✓ Dominion App ID: $DOMINION_APP${NC}"
-
-# Step 5: Deploy Region Application
-echo ""
-echo -e "${BLUE}[5/6] Deploying Region Chain application...${NC}"
+echo -e "${BLUE}[4/6] Deploying Region Chain application...${NC}"
 
 REGION_APP=$(linera publish-and-create \
     "$WASM_DIR/linera_dominion_region_contract.wasm" \
@@ -108,11 +90,38 @@ REGION_APP=$(linera publish-and-create \
     --json-parameters '{
         "sector_x": 0,
         "sector_y": 0,
-        "universe_seed": 12345
+        "universe_seed": 12345,
+        "sector_size": 100
     }' 2>&1 | tail -1)
 
 echo -e "${GREEN}✓ Region App ID: $REGION_APP${NC}"
 
+# Step 5: Deploy Dominion Application
+# The dominion knows its regions from creation and registers with each of them
+echo ""
+echo -e "${BLUE}[5/6] Deploying Dominion (User Chain) application...${NC}"
+
+DOMINION_APP=$(linera publish-and-create \
+    "$WASM_DIR/linera_dominion_contract.wasm" \
+    "$WASM_DIR/linera_dominion_service.wasm" \
+    --json-parameters '{
+        "home_x": 100,
+        "home_y": 200,
+        "starting_resources": {
+            "iron": 10000,
+            "deuterium": 5000,
+            "chronos_crystals": 2500
+        },
+        "universe_seed": 12345
+    }' \
+    --json-argument "{
+        \"regions\": [
+            { \"sector_x\": 0, \"sector_y\": 0, \"application_id\": \"$REGION_APP\" }
+        ]
+    }" 2>&1 | tail -1)
+
+echo -e "${GREEN}✓ Dominion App ID: $DOMINION_APP${NC}"
+
 # Step 6: Deploy Battle Application
 echo ""
 echo -e "${BLUE}[6/6] Deploying Battle Chain application...${NC}"
@@ -42,26 +42,9 @@ fi
 CHAIN_ID=$(linera wallet show | grep "Default Chain" | awk '{print $NF}')
 echo "Using chain: $CHAIN_ID"
 
-# Step 4: Deploy Dominion (User Chain) Application
+# Step 4: Deploy Region Chain Application
 echo ""
-echo "Step 3: Deploying Dominion (User Chain) application..."
-DOMINION_APP=$(linera publish-and-create \
-    "$WASM_DIR/linera_dominion_contract.wasm" \
-    "$WASM_DIR/linera_dominion_service.wasm" \
-    --json-parameters '{
-        "home_x": 100,
-        "home_y": 200,
-        "starting_iron": 10000,
-        "starting_deuterium": 5000,
-        "starting_crystals": 2500,
-        "universe_seed": 12345
-    }' 2>&1 | tail -1)
-
-echo "Dominion App ID: $DOMINION_APP"
-
-# Step 5: Deploy Region Chain Application
-echo ""
-echo "Step 4: Deploying Region Chain application..."
+echo "Step 3: Deploying Region Chain application..."
 REGION_APP=$(linera publish-and-create \
     "$WASM_DIR/linera_dominion_region_contract.wasm" \
     "$WASM_DIR/linera_dominion_region_service.wasm" \
@@ -69,11 +52,36 @@ REGION_APP=$(linera publish-and-create \
         "sector_x": 0,
         "sector_y": 0,
         "universe_seed": 12345,
-        "sector_size": 1000
+        "sector_size": 100
     }' 2>&1 | tail -1)
 
 echo "Region App ID: $REGION_APP"
 
+# Step 5: Deploy Dominion (User Chain) Application
+# The dominion knows its regions from creation and registers with each of them
+echo ""
+echo "Step 4: Deploying Dominion (User Chain) application..."
+DOMINION_APP=$(linera publish-and-create \
+    "$WASM_DIR/linera_dominion_contract.wasm" \
+    "$WASM_DIR/linera_dominion_service.wasm" \
+    --json-parameters '{
+        "home_x": 100,
+        "home_y": 200,
+        "starting_resources": {
+            "iron": 10000,
+            "deuterium": 5000,
+            "chronos_crystals": 2500
+        },
+        "universe_seed": 12345
+    }' \
+    --json-argument "{
+        \"regions\": [
+            { \"sector_x\": 0, \"sector_y\": 0, \"application_id\": \"$REGION_APP\" }
+        ]
+    }" 2>&1 | tail -1)
+
+echo "Dominion App ID: $DOMINION_APP"
+
 # Note: Battle chains are created dynamically per battle
 # They require specific instantiation args with attacker/defender info
 
@@ -0,0 +1,480 @@
+//! Senate Contract - Senate Chain smart contract
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+use linera_sdk::{
+    linera_base_types::{AccountOwner, ChainId, Timestamp, WithContractAbi},
+    Contract, ContractRuntime,
+    views::{RootView, View, ViewError},
+};
+use linera_dominion_senate::{SenateAbi, SenateResponse, Operation, Message};
+use linera_dominion_senate::state::{SenateState, StakeData, ProposalData, VoteData};
+use linera_dominion_common::{
+    constants::{PARAMETER_ACTIVATION_DELAY, SENATE_PROPOSAL_STAKE, VOTING_DURATION},
+    errors::SenateError,
+    governance::{encode_parameter_value, GovernedParameter},
+    messages::DominionMessage,
+    resources::Resources,
+    types::VoteTally,
+};
+
+pub struct SenateContract {
+    state: SenateState,
+    runtime: ContractRuntime<Self>,
+}
+
+linera_sdk::contract!(SenateContract);
+
+impl WithContractAbi for SenateContract {
+    type Abi = SenateAbi;
+}
+
+impl Contract for SenateContract {
+    type Message = Message;
+    type Parameters = ();
+    type InstantiationArgument = ();
+    type EventValue = ();
+
+    async fn load(runtime: ContractRuntime<Self>) -> Self {
+        let state = SenateState::load(runtime.root_view_storage_context())
+            .await
+            .expect("Failed to load state");
+        Self { state, runtime }
+    }
+
+    async fn instantiate(&mut self, _argument: Self::InstantiationArgument) {
+        // Parameters start at their defaults until the first vote passes
+    }
+
+    async fn execute_operation(&mut self, operation: Self::Operation) -> Result<SenateResponse, SenateError> {
+        let now_micros = self.runtime.system_time().micros();
+
+        self.resolve_proposals(now_micros).await
+            .map_err(|_| SenateError::StorageError)?;
+
+        match operation {
+            Operation::Stake { crystals } => {
+                self.check_subscribed_caller()?;
+                let owner = self.signer()?;
+                let senate_chain = self.runtime.application_creator_chain_id();
+                self.runtime
+                    .prepare_message(Message::Stake { owner, crystals })
+                    .with_authentication()
+                    .with_tracking()
+                    .send_to(senate_chain);
+            }
+
+            Operation::Unstake { crystals } => {
+                let owner = self.signer()?;
+                self.send_to_senate(Message::Unstake { owner, crystals });
+            }
+
+            Operation::Subscribe => {
+                if let Some(caller) = self.runtime.authenticated_caller_id() {
+                    if self.state.subscribed_app.get().is_none() {
+                        self.state.subscribed_app.set(Some(caller));
+                    }
+                }
+                self.send_to_senate(Message::Subscribe);
+            }
+
+            Operation::ProposeChange { parameter, new_value } => {
+                let bounds = parameter.bounds();
+                if new_value < bounds.min || new_value > bounds.max {
+                    return Err(SenateError::OutOfBounds { min: bounds.min, max: bounds.max });
+                }
+                let proposer = self.signer()?;
+                self.send_to_senate(Message::Propose { proposer, parameter, new_value });
+            }
+
+            Operation::Vote { proposal_id, approve } => {
+                let voter = self.signer()?;
+                if self.state.voted.contains_key(&proposal_id).await
+                    .map_err(|_| SenateError::StorageError)?
+                {
+                    return Err(SenateError::AlreadyVoted);
+                }
+                // Recorded once the Senate Chain counts it
+                self.send_to_senate(Message::Vote { proposal_id, voter, approve });
+            }
+
+            Operation::TakeNotifications => {
+                self.check_subscribed_caller()?;
+                let notifications = self.state.notifications.elements().await
+                    .map_err(|_| SenateError::StorageError)?;
+                for _ in 0..notifications.len() {
+                    self.state.notifications.delete_front();
+                }
+                return Ok(SenateResponse::Notifications(notifications));
+            }
+        }
+        Ok(SenateResponse::Ok)
+    }
+
+    async fn execute_message(&mut self, message: Self::Message) {
+        let now_micros = self.runtime.system_time().micros();
+        let origin = self.runtime.message_origin_chain_id()
+            .expect("Incoming message must have an origin");
+        let is_bouncing = self.runtime.message_is_bouncing() == Some(true);
+
+        self.resolve_proposals(now_micros).await
+            .expect("Failed to resolve proposals");
+
+        match message {
+            Message::Stake { owner, crystals } => {
+                if is_bouncing {
+                    // The Senate Chain never took the crystals: hand them back
+                    self.state.notifications.push_back(stake_refund(owner, crystals));
+                    return;
+                }
+                if !self.is_senate_chain() || self.runtime.authenticated_signer() != Some(owner) {
+                    return;
+                }
+                self.credit_stake(origin, owner, crystals).await;
+                self.state.subscribers.insert(&origin, ())
+                    .expect("Failed to record subscriber");
+            }
+
+            Message::Unstake { owner, crystals } => {
+                if !self.is_senate_chain() || self.runtime.authenticated_signer() != Some(owner) {
+                    return;
+                }
+                let Some(mut stake) = self.state.stakes.get(&origin).await
+                    .expect("Failed to read stakes")
+                else {
+                    return;
+                };
+                if stake.owner != owner || stake.crystals < crystals || stake.locked_until_micros > now_micros {
+                    return;
+                }
+                stake.crystals -= crystals;
+                if stake.crystals == 0 {
+                    self.state.stakes.remove(&origin)
+                        .expect("Failed to remove stake");
+                } else {
+                    self.state.stakes.insert(&origin, stake)
+                        .expect("Failed to update stake");
+                }
+                self.runtime
+                    .prepare_message(Message::Notify { notification: Box::new(stake_refund(owner, crystals)) })
+                    .with_tracking()
+                    .send_to(origin);
+            }
+
+            Message::Subscribe => {
+                if self.is_senate_chain() {
+                    self.state.subscribers.insert(&origin, ())
+                        .expect("Failed to record subscriber");
+                }
+            }
+
+            Message::Propose { proposer, parameter, new_value } => {
+                if self.staker(proposer, origin).await.is_none_or(|stake| stake.crystals < SENATE_PROPOSAL_STAKE) {
+                    return;
+                }
+                let has_open = self.state.has_open_proposal(parameter).await
+                    .expect("Failed to read proposals");
+                let old_value = self.state.parameter_value(parameter).await
+                    .expect("Failed to read parameters");
+                if has_open || parameter.check_change(old_value, new_value).is_err() {
+                    return;
+                }
+                self.state.add_proposal(ProposalData {
+                    proposer,
+                    proposer_chain: origin,
+                    parameter,
+                    old_value,
+                    new_value,
+                    created_micros: now_micros,
+                    ends_micros: now_micros.saturating_add(VOTING_DURATION * 1_000_000),
+                    tally: VoteTally::default(),
+                    voter_count: 0,
+                    total_power: 0,
+                    effective_micros: None,
+                    status: ProposalData::OPEN,
+                })
+                .expect("Failed to record proposal");
+            }
+
+            Message::Vote { proposal_id, voter, approve } => {
+                let Some(mut stake) = self.staker(voter, origin).await else {
+                    return;
+                };
+                let Some(mut proposal) = self.state.proposals.get(&proposal_id).await
+                    .expect("Failed to read proposal")
+                else {
+                    return;
+                };
+                if proposal.status != ProposalData::OPEN || proposal.ends_micros <= now_micros {
+                    return;
+                }
+                let key = (proposal_id, origin);
+                if self.state.votes.contains_key(&key).await.expect("Failed to read votes") {
+                    return;
+                }
+                let power = stake.crystals;
+                self.state.votes.insert(&key, VoteData {
+                    voter,
+                    approve,
+                    power,
+                    cast_micros: now_micros,
+                })
+                .expect("Failed to record vote");
+                proposal.tally.add(approve, power);
+                proposal.voter_count += 1;
+
+                // The stake backing a vote stays until the vote closes
+                stake.locked_until_micros = stake.locked_until_micros.max(proposal.ends_micros);
+                self.state.stakes.insert(&origin, stake)
+                    .expect("Failed to lock stake");
+                self.state.proposals.insert(&proposal_id, proposal)
+                    .expect("Failed to record vote");
+                self.runtime
+                    .prepare_message(Message::VoteAccepted { proposal_id, approve })
+                    .send_to(origin);
+            }
+
+            Message::VoteAccepted { proposal_id, approve } => {
+                if origin != self.runtime.application_creator_chain_id() {
+                    return;
+                }
+                self.state.voted.insert(&proposal_id, approve)
+                    .expect("Failed to record vote");
+            }
+
+            Message::Notify { notification } => {
+                if is_bouncing {
+                    // A withdrawal the User Chain rejected goes back into its stake
+                    if let DominionMessage::ResourceTransfer { from, resources, .. } = *notification {
+                        let crystals = u64::try_from(resources.chronos_crystals.0).unwrap_or(u64::MAX);
+                        self.credit_stake(origin, from, crystals).await;
+                    }
+                    return;
+                }
+                self.state.notifications.push_back(*notification);
+            }
+        }
+    }
+
+    async fn store(mut self) {
+        self.state.save().await.expect("Failed to save state");
+    }
+}
+
+impl SenateContract {
+    /// Close the proposals whose voting window has ended, applying those that passed
+    ///
+    /// Approved values take effect after `PARAMETER_ACTIVATION_DELAY`, so every
+    /// subscribed chain hears of the change before it applies.
+    async fn resolve_proposals(&mut self, now_micros: u64) -> Result<(), ViewError> {
+        if !self.is_senate_chain() {
+            return Ok(());
+        }
+        for proposal_id in self.state.closed_proposals(now_micros).await? {
+            let Some(mut proposal) = self.state.proposals.get(&proposal_id).await? else {
+                continue;
+            };
+            proposal.total_power = self.state.total_staked().await?;
+            if proposal.tally.passes(proposal.total_power) {
+                let effective_micros = now_micros.saturating_add(PARAMETER_ACTIVATION_DELAY * 1_000_000);
+                self.state.parameters.insert(&proposal.parameter, proposal.new_value)?;
+                self.broadcast_update(proposal.parameter, proposal.new_value, effective_micros).await?;
+                proposal.effective_micros = Some(effective_micros);
+                proposal.status = ProposalData::PASSED;
+            } else {
+                proposal.status = ProposalData::REJECTED;
+            }
+            self.state.proposals.insert(&proposal_id, proposal)?;
+        }
+        Ok(())
+    }
+
+    /// Tell every subscribed chain about an approved change
+    async fn broadcast_update(
+        &mut self,
+        parameter: GovernedParameter,
+        value: u64,
+        effective_micros: u64,
+    ) -> Result<(), ViewError> {
+        for chain_id in self.state.subscriber_chains().await? {
+            self.runtime
+                .prepare_message(Message::Notify {
+                    notification: Box::new(DominionMessage::ParameterUpdate {
+                        parameter_name: parameter.name().to_string(),
+                        new_value: encode_parameter_value(value),
+                        effective_from: Timestamp::from(effective_micros),
+                    }),
+                })
+                .send_to(chain_id);
+        }
+        Ok(())
+    }
+
+    async fn credit_stake(&mut self, chain_id: ChainId, owner: AccountOwner, crystals: u64) {
+        let stake = self.state.stakes.get(&chain_id).await
+            .expect("Failed to read stakes");
+        let (staked, locked_until_micros) = stake
+            .map_or((0, 0), |stake| (stake.crystals, stake.locked_until_micros));
+        self.state.stakes.insert(&chain_id, StakeData {
+            owner,
+            crystals: staked.saturating_add(crystals),
+            locked_until_micros,
+        })
+        .expect("Failed to record stake");
+    }
+
+    /// The stake of the player who sent a request from `origin`, if `player` signed it
+    async fn staker(&mut self, player: AccountOwner, origin: ChainId) -> Option<StakeData> {
+        if !self.is_senate_chain() || self.runtime.authenticated_signer() != Some(player) {
+            return None;
+        }
+        self.state.stakes.get(&origin).await
+            .expect("Failed to read stakes")
+            .filter(|stake| stake.owner == player && stake.crystals > 0)
+    }
+
+    fn is_senate_chain(&mut self) -> bool {
+        self.runtime.chain_id() == self.runtime.application_creator_chain_id()
+    }
+
+    /// Check that the operation comes from the application subscribed on this chain
+    fn check_subscribed_caller(&mut self) -> Result<(), SenateError> {
+        let subscribed_app = *self.state.subscribed_app.get();
+        if subscribed_app.is_none() || self.runtime.authenticated_caller_id() != subscribed_app {
+            return Err(SenateError::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    fn signer(&mut self) -> Result<AccountOwner, SenateError> {
+        self.runtime.authenticated_signer().ok_or(SenateError::NotAuthorized)
+    }
+
+    /// Send a request to the Senate Chain on behalf of the signer
+    fn send_to_senate(&mut self, message: Message) {
+        let senate_chain = self.runtime.application_creator_chain_id();
+        self.runtime
+            .prepare_message(message)
+            .with_authentication()
+            .send_to(senate_chain);
+    }
+}
+
+/// Crystals leaving the Senate for the staker's wallet
+fn stake_refund(owner: AccountOwner, crystals: u64) -> DominionMessage {
+    DominionMessage::ResourceTransfer {
+        from: owner,
+        resources: Resources::crystals(crystals as u128),
+        memo: Some("Senate stake".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        linera_base_types::{ApplicationId, CryptoHash},
+        util::BlockingWait,
+    };
+
+    use super::*;
+
+    fn chain(name: &str) -> ChainId {
+        ChainId(CryptoHash::test_hash(name))
+    }
+
+    fn owner(name: &str) -> AccountOwner {
+        AccountOwner::from(CryptoHash::test_hash(name))
+    }
+
+    fn application(name: &str) -> ApplicationId {
+        ApplicationId::new(CryptoHash::test_hash(name))
+    }
+
+    /// The senate application on `chain_id`, created on the `senate` chain
+    fn create_senate(chain_id: ChainId) -> SenateContract {
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(())
+            .with_application_creator_chain_id(chain("senate"))
+            .with_chain_id(chain_id)
+            .with_system_time(Timestamp::from(1_000_000));
+        let mut contract = SenateContract {
+            state: SenateState::load(runtime.root_view_storage_context())
+                .blocking_wait()
+                .expect("Failed to read from mock key value store"),
+            runtime,
+        };
+        contract.instantiate(()).now_or_never().expect("Instantiation should not await anything");
+        contract
+    }
+
+    fn execute(contract: &mut SenateContract, operation: Operation) -> Result<SenateResponse, SenateError> {
+        contract.execute_operation(operation).now_or_never().expect("Operation should not await anything")
+    }
+
+    fn receive(contract: &mut SenateContract, origin: ChainId, signer: Option<AccountOwner>, message: Message) {
+        contract.runtime.set_message_origin_chain_id(origin);
+        contract.runtime.set_message_is_bouncing(false);
+        contract.runtime.set_authenticated_signer(signer);
+        contract.execute_message(message).now_or_never().expect("Message should not await anything");
+    }
+
+    #[test]
+    fn test_only_subscribed_app_stakes() {
+        let mut user = create_senate(chain("player"));
+        user.runtime.set_authenticated_signer(owner("player"));
+        user.runtime.set_authenticated_caller_id(application("dominion"));
+        assert!(matches!(execute(&mut user, Operation::Stake { crystals: 100 }), Err(SenateError::NotAuthorized)));
+
+        execute(&mut user, Operation::Subscribe).expect("Subscribing should succeed");
+        // A later subscriber does not replace the first one
+        user.runtime.set_authenticated_caller_id(application("region"));
+        execute(&mut user, Operation::Subscribe).expect("Subscribing should succeed");
+        assert_eq!(*user.state.subscribed_app.get(), Some(application("dominion")));
+        assert!(matches!(execute(&mut user, Operation::Stake { crystals: 100 }), Err(SenateError::NotAuthorized)));
+        assert!(matches!(execute(&mut user, Operation::TakeNotifications), Err(SenateError::NotAuthorized)));
+
+        user.runtime.set_authenticated_caller_id(application("dominion"));
+        execute(&mut user, Operation::Stake { crystals: 100 }).expect("The dominion may stake");
+        let sent = user.runtime.created_send_message_requests();
+        assert!(sent.iter().any(|request| {
+            request.destination == chain("senate")
+                && request.authenticated
+                && matches!(request.message, Message::Stake { crystals: 100, .. })
+        }));
+    }
+
+    #[test]
+    fn test_vote_recorded_once_senate_accepts() {
+        let mut user = create_senate(chain("player"));
+        user.runtime.set_authenticated_signer(owner("player"));
+        execute(&mut user, Operation::Vote { proposal_id: 0, approve: true }).expect("Voting should succeed");
+        assert!(!user.state.voted.contains_key(&0).blocking_wait().unwrap());
+
+        // Only the Senate Chain confirms votes
+        receive(&mut user, chain("other"), None, Message::VoteAccepted { proposal_id: 0, approve: true });
+        assert!(!user.state.voted.contains_key(&0).blocking_wait().unwrap());
+
+        receive(&mut user, chain("senate"), None, Message::VoteAccepted { proposal_id: 0, approve: true });
+        assert_eq!(user.state.voted.get(&0).blocking_wait().unwrap(), Some(true));
+        assert!(matches!(
+            execute(&mut user, Operation::Vote { proposal_id: 0, approve: false }),
+            Err(SenateError::AlreadyVoted)
+        ));
+    }
+
+    #[test]
+    fn test_stake_credited_to_signer() {
+        let mut senate = create_senate(chain("senate"));
+        let stake = |owner| Message::Stake { owner, crystals: 100 };
+
+        receive(&mut senate, chain("player"), Some(owner("intruder")), stake(owner("player")));
+        assert!(senate.state.stakes.get(&chain("player")).blocking_wait().unwrap().is_none());
+
+        receive(&mut senate, chain("player"), Some(owner("player")), stake(owner("player")));
+        let staked = senate.state.stakes.get(&chain("player")).blocking_wait().unwrap()
+            .expect("The stake should be recorded");
+        assert_eq!((staked.owner, staked.crystals), (owner("player"), 100));
+        assert!(senate.state.subscribers.contains_key(&chain("player")).blocking_wait().unwrap());
+    }
+}
@@ -0,0 +1,134 @@
+//! # Linera Dominion - Senate Chain Application
+//!
+//! The Senate Chain is the chain that created the application. Players stake
+//! Chronos Crystals there to propose and vote on changes to universe
+//! parameters; approved changes are broadcast to every subscribed chain. The
+//! same application runs on the players' User Chains, where it relays their
+//! requests and queues the broadcasts for the dominion application.
+
+pub mod state;
+
+use async_graphql::{Request, Response};
+use linera_sdk::{
+    graphql::GraphQLMutationRoot,
+    linera_base_types::AccountOwner,
+};
+use linera_dominion_common::{
+    errors::SenateError,
+    governance::GovernedParameter,
+    messages::DominionMessage,
+};
+use serde::{Deserialize, Serialize};
+
+// Re-export state types
+pub use state::{SenateState, StakeData, ProposalData, VoteData};
+
+// ==================== OPERATIONS ====================
+
+/// Operations on the Senate application, run on a User Chain
+#[derive(Debug, Serialize, Deserialize, GraphQLMutationRoot)]
+pub enum Operation {
+    /// Stake crystals in the Senate
+    ///
+    /// Only the application subscribed on this chain (the dominion, which
+    /// debits the crystals first) may call this.
+    Stake {
+        crystals: u64,
+    },
+
+    /// Withdraw staked crystals that are not locked by a vote
+    Unstake {
+        crystals: u64,
+    },
+
+    /// Receive parameter updates without staking
+    ///
+    /// The first application to subscribe on a chain is the one allowed to
+    /// stake and take the notifications there.
+    Subscribe,
+
+    /// Propose a new value for a universe parameter
+    ProposeChange {
+        parameter: GovernedParameter,
+        new_value: u64,
+    },
+
+    /// Vote on an open proposal with the signer's stake
+    Vote {
+        proposal_id: u64,
+        approve: bool,
+    },
+
+    /// Hand the queued notifications to the subscribed application
+    TakeNotifications,
+}
+
+// ==================== MESSAGES ====================
+
+/// Messages for the Senate application
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Message {
+    // ===== User Chain to Senate Chain =====
+    Stake {
+        owner: AccountOwner,
+        crystals: u64,
+    },
+
+    Unstake {
+        owner: AccountOwner,
+        crystals: u64,
+    },
+
+    Subscribe,
+
+    Propose {
+        proposer: AccountOwner,
+        parameter: GovernedParameter,
+        new_value: u64,
+    },
+
+    Vote {
+        proposal_id: u64,
+        voter: AccountOwner,
+        approve: bool,
+    },
+
+    // ===== Senate Chain to User Chain =====
+    /// The Senate Chain counted a vote of the receiving chain
+    VoteAccepted {
+        proposal_id: u64,
+        approve: bool,
+    },
+
+    /// Notification for the dominion application of a User Chain
+    ///
+    /// Boxed to keep the requests small.
+    Notify {
+        notification: Box<DominionMessage>,
+    },
+}
+
+// ==================== RESPONSES ====================
+
+/// Senate operation response
+#[derive(Debug, Serialize, Deserialize)]
+pub enum SenateResponse {
+    Ok,
+    /// Notifications taken from the queue, oldest first
+    Notifications(Vec<DominionMessage>),
+}
+
+// ==================== ABI ====================
+
+/// Application ABI
+pub struct SenateAbi;
+
+impl linera_sdk::abi::ContractAbi for SenateAbi {
+    type Operation = Operation;
+    type Response = Result<SenateResponse, SenateError>;
+}
+
+impl linera_sdk::abi::ServiceAbi for SenateAbi {
+    type Query = Request;
+    type QueryResponse = Response;
+}
@@ -0,0 +1,184 @@
+//! Senate Service - GraphQL query service for Senate Chain
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+use std::sync::Arc;
+use async_graphql::{Object, Request, Response, Schema, EmptySubscription, SimpleObject};
+use linera_sdk::{
+    graphql::GraphQLMutationRoot,
+    linera_base_types::{ChainId, WithServiceAbi},
+    Service, ServiceRuntime,
+    views::View,
+};
+use linera_dominion_senate::{SenateAbi, Operation};
+use linera_dominion_senate::state::{SenateState, ProposalData, StakeData, VoteData};
+use linera_dominion_common::governance::{GovernedParameter, ParameterBounds};
+
+pub struct SenateService {
+    state: SenateState,
+    runtime: Arc<ServiceRuntime<Self>>,
+}
+
+linera_sdk::service!(SenateService);
+
+impl WithServiceAbi for SenateService {
+    type Abi = SenateAbi;
+}
+
+impl Service for SenateService {
+    type Parameters = ();
+
+    async fn new(runtime: ServiceRuntime<Self>) -> Self {
+        let state = SenateState::load(runtime.root_view_storage_context())
+            .await
+            .expect("Failed to load state");
+        Self {
+            state,
+            runtime: Arc::new(runtime),
+        }
+    }
+
+    async fn handle_query(&self, request: Request) -> Response {
+        let mut parameters = Vec::new();
+        for parameter in GovernedParameter::all() {
+            let value = self.state.parameter_value(*parameter).await
+                .expect("Failed to read parameters");
+            parameters.push(ParameterView {
+                parameter: *parameter,
+                name: parameter.name().to_string(),
+                value,
+                default_value: parameter.default_value(),
+                bounds: parameter.bounds(),
+            });
+        }
+
+        let mut stakes = Vec::new();
+        self.state.stakes
+            .for_each_index_value(|chain_id, stake| {
+                stakes.push(StakeView {
+                    chain_id,
+                    stake: stake.into_owned(),
+                });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read stakes");
+
+        let mut proposals = Vec::new();
+        self.state.proposals
+            .for_each_index_value(|proposal_id, proposal| {
+                proposals.push(ProposalView {
+                    proposal_id,
+                    proposal: proposal.into_owned(),
+                });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read proposals");
+
+        let mut votes = Vec::new();
+        self.state.votes
+            .for_each_index_value(|(proposal_id, voter_chain), vote| {
+                votes.push(VoteView {
+                    proposal_id,
+                    voter_chain,
+                    vote: vote.into_owned(),
+                });
+                Ok(())
+            })
+            .await
+            .expect("Failed to read votes");
+
+        let subscriber_count = self.state.subscribers.count().await
+            .expect("Failed to count subscribers");
+
+        let schema = Schema::build(
+            QueryRoot {
+                parameters,
+                stakes,
+                proposals,
+                votes,
+                subscriber_count,
+            },
+            Operation::mutation_root(self.runtime.clone()),
+            EmptySubscription,
+        )
+        .finish();
+        schema.execute(request).await
+    }
+}
+
+struct QueryRoot {
+    parameters: Vec<ParameterView>,
+    stakes: Vec<StakeView>,
+    proposals: Vec<ProposalView>,
+    votes: Vec<VoteView>,
+    subscriber_count: usize,
+}
+
+#[Object]
+impl QueryRoot {
+    /// Every governed parameter with its current value and bounds
+    async fn parameters(&self) -> &[ParameterView] {
+        &self.parameters
+    }
+
+    async fn stakes(&self) -> &[StakeView] {
+        &self.stakes
+    }
+
+    /// Crystals staked in the Senate by all players
+    async fn total_staked(&self) -> u64 {
+        self.stakes.iter().map(|view| view.stake.crystals).sum()
+    }
+
+    async fn proposals(&self) -> &[ProposalView] {
+        &self.proposals
+    }
+
+    /// Every vote cast, for auditing the tallies
+    async fn votes(&self, proposal_id: Option<u64>) -> Vec<&VoteView> {
+        self.votes
+            .iter()
+            .filter(|vote| proposal_id.is_none_or(|id| vote.proposal_id == id))
+            .collect()
+    }
+
+    async fn subscriber_count(&self) -> usize {
+        self.subscriber_count
+    }
+}
+
+/// A governed parameter and the limits on changing it
+#[derive(SimpleObject)]
+struct ParameterView {
+    parameter: GovernedParameter,
+    name: String,
+    value: u64,
+    default_value: u64,
+    bounds: ParameterBounds,
+}
+
+/// A stake and the User Chain it belongs to
+#[derive(SimpleObject)]
+struct StakeView {
+    chain_id: ChainId,
+    #[graphql(flatten)]
+    stake: StakeData,
+}
+
+#[derive(SimpleObject)]
+struct ProposalView {
+    proposal_id: u64,
+    #[graphql(flatten)]
+    proposal: ProposalData,
+}
+
+/// A vote and the User Chain that cast it
+#[derive(SimpleObject)]
+struct VoteView {
+    proposal_id: u64,
+    voter_chain: ChainId,
+    #[graphql(flatten)]
+    vote: VoteData,
+}
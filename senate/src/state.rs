@@ -0,0 +1,138 @@
+//! State management for the Senate Chain
+
+use async_graphql::SimpleObject;
+use linera_dominion_common::{
+    governance::GovernedParameter,
+    messages::DominionMessage,
+    types::VoteTally,
+};
+use linera_sdk::linera_base_types::{AccountOwner, ApplicationId, ChainId};
+use linera_sdk::views::{
+    linera_views, MapView, QueueView, RegisterView, RootView, ViewError, ViewStorageContext,
+};
+use serde::{Deserialize, Serialize};
+
+/// Crystals a player staked in the Senate, keyed by their User Chain
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct StakeData {
+    pub owner: AccountOwner,
+    pub crystals: u64,
+    /// The stake cannot be withdrawn before the last vote it backs has closed
+    pub locked_until_micros: u64,
+}
+
+/// A proposed parameter change put to the vote of the stakers
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ProposalData {
+    pub proposer: AccountOwner,
+    pub proposer_chain: ChainId,
+    pub parameter: GovernedParameter,
+    /// Value of the parameter when the change was proposed
+    pub old_value: u64,
+    pub new_value: u64,
+    pub created_micros: u64,
+    /// End of the voting window
+    pub ends_micros: u64,
+    pub tally: VoteTally,
+    pub voter_count: u32,
+    /// Crystals staked in the Senate when the vote closed
+    pub total_power: u64,
+    /// When an approved change takes effect
+    pub effective_micros: Option<u64>,
+    pub status: u8,             // 0=open, 1=passed, 2=rejected
+}
+
+impl ProposalData {
+    pub const OPEN: u8 = 0;
+    pub const PASSED: u8 = 1;
+    pub const REJECTED: u8 = 2;
+}
+
+/// A vote cast on a proposal, kept for the audit trail
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct VoteData {
+    pub voter: AccountOwner,
+    pub approve: bool,
+    pub power: u64,
+    pub cast_micros: u64,
+}
+
+#[derive(RootView)]
+#[view(context = ViewStorageContext)]
+pub struct SenateState {
+    // ===== Senate Chain =====
+    pub stakes: MapView<ChainId, StakeData>,
+    /// Current value of every parameter changed by a vote
+    pub parameters: MapView<GovernedParameter, u64>,
+    pub proposals: MapView<u64, ProposalData>,
+    pub proposal_count: RegisterView<u64>,
+    /// Votes: key = (proposal_id, voter chain)
+    pub votes: MapView<(u64, ChainId), VoteData>,
+    /// Chains that receive parameter updates
+    pub subscribers: MapView<ChainId, ()>,
+
+    // ===== User Chain =====
+    /// Application subscribed on this chain: the dominion on User Chains
+    pub subscribed_app: RegisterView<Option<ApplicationId>>,
+    /// Proposals the Senate Chain counted a vote of this chain on
+    pub voted: MapView<u64, bool>,
+    /// Notifications for the dominion application of this chain
+    pub notifications: QueueView<DominionMessage>,
+}
+
+impl SenateState {
+    /// Current value of `parameter`, its default until a vote changes it
+    pub async fn parameter_value(&self, parameter: GovernedParameter) -> Result<u64, ViewError> {
+        Ok(self.parameters.get(&parameter).await?
+            .unwrap_or_else(|| parameter.default_value()))
+    }
+
+    /// Crystals staked in the Senate by all players
+    pub async fn total_staked(&self) -> Result<u64, ViewError> {
+        let mut total = 0u64;
+        self.stakes
+            .for_each_index_value(|_, stake| {
+                total = total.saturating_add(stake.crystals);
+                Ok(())
+            })
+            .await?;
+        Ok(total)
+    }
+
+    pub fn add_proposal(&mut self, proposal: ProposalData) -> Result<u64, ViewError> {
+        let proposal_id = *self.proposal_count.get();
+        self.proposals.insert(&proposal_id, proposal)?;
+        self.proposal_count.set(proposal_id + 1);
+        Ok(proposal_id)
+    }
+
+    /// Whether a proposal to change `parameter` is still open
+    pub async fn has_open_proposal(&self, parameter: GovernedParameter) -> Result<bool, ViewError> {
+        let mut found = false;
+        self.proposals
+            .for_each_index_value_while(|_, proposal| {
+                found = proposal.status == ProposalData::OPEN && proposal.parameter == parameter;
+                Ok(!found)
+            })
+            .await?;
+        Ok(found)
+    }
+
+    /// Open proposals whose voting window has closed by `now_micros`
+    pub async fn closed_proposals(&self, now_micros: u64) -> Result<Vec<u64>, ViewError> {
+        let mut closed = Vec::new();
+        self.proposals
+            .for_each_index_value(|proposal_id, proposal| {
+                if proposal.status == ProposalData::OPEN && proposal.ends_micros <= now_micros {
+                    closed.push(proposal_id);
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(closed)
+    }
+
+    pub async fn subscriber_chains(&self) -> Result<Vec<ChainId>, ViewError> {
+        self.subscribers.indices().await
+    }
+}